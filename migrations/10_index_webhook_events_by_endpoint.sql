@@ -0,0 +1,3 @@
+-- Delivery log of an endpoint, newest first (keyset pagination)
+CREATE INDEX idx_webhook_events_endpoint
+    ON webhook_events(webhook_endpoint_id, created_at DESC, id DESC);
@@ -0,0 +1,4 @@
+-- Secret replaced by the last rotation, still accepted until it expires
+ALTER TABLE webhook_endpoints
+    ADD COLUMN previous_secret VARCHAR(64),
+    ADD COLUMN previous_secret_expires_at TIMESTAMPTZ;
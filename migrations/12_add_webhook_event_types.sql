@@ -0,0 +1,13 @@
+-- Event types an endpoint subscribes to ('*' and 'category.*' are wildcards)
+ALTER TABLE webhook_endpoints
+    ADD COLUMN enabled_events TEXT[] NOT NULL DEFAULT '{*}';
+
+-- Events are no longer only about transactions (e.g. account.created)
+ALTER TABLE webhook_events
+    ADD COLUMN event_type VARCHAR(100);
+
+UPDATE webhook_events SET event_type = payload->>'event_type';
+
+ALTER TABLE webhook_events
+    ALTER COLUMN event_type SET NOT NULL,
+    ALTER COLUMN transaction_id DROP NOT NULL;
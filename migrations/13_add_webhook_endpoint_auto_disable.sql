@@ -0,0 +1,6 @@
+-- Failure streak of an endpoint, used to disable endpoints that keep failing
+ALTER TABLE webhook_endpoints
+    ADD COLUMN consecutive_failures INTEGER NOT NULL DEFAULT 0,
+    ADD COLUMN failing_since TIMESTAMPTZ,
+    ADD COLUMN disabled_at TIMESTAMPTZ,
+    ADD COLUMN disabled_reason TEXT;
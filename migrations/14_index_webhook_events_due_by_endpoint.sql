@@ -0,0 +1,4 @@
+-- Dispatcher claims a bounded number of due events per endpoint
+CREATE INDEX idx_webhook_events_endpoint_due
+    ON webhook_events(webhook_endpoint_id, next_attempt_at)
+    WHERE status = 'pending';
@@ -0,0 +1,44 @@
+-- Opt-in ordered delivery: events touching the same account are delivered
+-- one at a time, in commit order
+ALTER TABLE webhook_endpoints
+    ADD COLUMN ordered_delivery BOOLEAN NOT NULL DEFAULT false;
+
+-- Increasing number shared by all copies of an event (one per endpoint)
+CREATE SEQUENCE webhook_event_sequence;
+
+ALTER TABLE webhook_events
+    ADD COLUMN sequence BIGINT,
+    ADD COLUMN account_ids UUID[] NOT NULL DEFAULT '{}';
+
+-- Number existing events in creation order
+UPDATE webhook_events e
+SET sequence = numbered.n
+FROM (
+    SELECT id, ROW_NUMBER() OVER (ORDER BY created_at, id) AS n
+    FROM webhook_events
+) numbered
+WHERE e.id = numbered.id;
+
+SELECT setval(
+    'webhook_event_sequence',
+    COALESCE((SELECT MAX(sequence) FROM webhook_events), 0) + 1,
+    false
+);
+
+ALTER TABLE webhook_events
+    ALTER COLUMN sequence SET NOT NULL;
+
+-- Accounts of existing events
+UPDATE webhook_events e
+SET account_ids = ARRAY_REMOVE(ARRAY[t.from_account_id, t.to_account_id], NULL)
+FROM transactions t
+WHERE t.id = e.transaction_id;
+
+UPDATE webhook_events
+SET account_ids = ARRAY[(payload->'data'->'account'->>'id')::uuid]
+WHERE event_type = 'account.created';
+
+-- Dispatcher looks for earlier pending events of an endpoint
+CREATE INDEX idx_webhook_events_endpoint_pending_sequence
+    ON webhook_events(webhook_endpoint_id, sequence)
+    WHERE status = 'pending';
@@ -0,0 +1,15 @@
+-- Reversals undo all or part of an earlier transaction
+ALTER TABLE transactions DROP CONSTRAINT transactions_transaction_type_check;
+
+ALTER TABLE transactions ADD CONSTRAINT transactions_transaction_type_check
+    CHECK (transaction_type IN ('credit', 'debit', 'transfer', 'opening_balance', 'reversal'));
+
+-- The transaction a reversal undoes (set exactly for reversals)
+ALTER TABLE transactions ADD COLUMN reversed_transaction_id UUID REFERENCES transactions(id);
+
+ALTER TABLE transactions ADD CONSTRAINT transactions_reversed_transaction_check
+    CHECK ((transaction_type = 'reversal') = (reversed_transaction_id IS NOT NULL));
+
+CREATE INDEX idx_transactions_reversed
+    ON transactions(reversed_transaction_id)
+    WHERE reversed_transaction_id IS NOT NULL;
@@ -0,0 +1,35 @@
+-- Funds reserved by open authorizations; available balance is balance_cents - held_cents
+ALTER TABLE accounts ADD COLUMN held_cents BIGINT NOT NULL DEFAULT 0;
+
+ALTER TABLE accounts ADD CONSTRAINT held_within_balance
+    CHECK (held_cents >= 0 AND held_cents <= balance_cents);
+
+-- Authorizations place a hold; captures post (part of) it
+ALTER TABLE transactions DROP CONSTRAINT transactions_transaction_type_check;
+
+ALTER TABLE transactions ADD CONSTRAINT transactions_transaction_type_check
+    CHECK (transaction_type IN (
+        'credit', 'debit', 'transfer', 'opening_balance', 'reversal', 'authorization', 'capture'
+    ));
+
+-- The authorization a capture posts (set exactly for captures)
+ALTER TABLE transactions ADD COLUMN authorization_id UUID REFERENCES transactions(id);
+
+ALTER TABLE transactions ADD CONSTRAINT transactions_authorization_check
+    CHECK ((transaction_type = 'capture') = (authorization_id IS NOT NULL));
+
+-- When an open authorization releases its hold (set exactly for authorizations)
+ALTER TABLE transactions ADD COLUMN expires_at TIMESTAMPTZ;
+
+ALTER TABLE transactions ADD CONSTRAINT transactions_expires_at_check
+    CHECK ((transaction_type = 'authorization') = (expires_at IS NOT NULL));
+
+-- An authorization is captured at most once
+CREATE UNIQUE INDEX idx_transactions_capture
+    ON transactions(authorization_id)
+    WHERE authorization_id IS NOT NULL;
+
+-- Expiry task looks up open authorizations past their expiry
+CREATE INDEX idx_transactions_open_authorizations
+    ON transactions(expires_at)
+    WHERE transaction_type = 'authorization' AND status = 'pending';
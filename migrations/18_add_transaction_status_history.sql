@@ -0,0 +1,55 @@
+-- Transaction statuses follow a state machine enforced by the application
+ALTER TABLE transactions ADD CONSTRAINT transactions_status_check
+    CHECK (status IN (
+        'pending', 'processing', 'completed', 'failed', 'reversed', 'canceled',
+        'captured', 'voided', 'expired'
+    ));
+
+-- Fully reversed transactions move from 'completed' to 'reversed'
+UPDATE transactions t
+SET status = 'reversed'
+WHERE status = 'completed'
+  AND amount_cents = (
+      SELECT SUM(r.amount_cents) FROM transactions r WHERE r.reversed_transaction_id = t.id
+  );
+
+-- One row per status change; the first row of a transaction has no from_status
+CREATE TABLE transaction_status_history (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    transaction_id UUID NOT NULL REFERENCES transactions(id),
+    from_status VARCHAR(20),
+    to_status VARCHAR(20) NOT NULL,
+    reason TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_transaction_status_history_transaction
+    ON transaction_status_history(transaction_id, created_at);
+
+-- Backfill the status each existing transaction was created with
+INSERT INTO transaction_status_history (transaction_id, from_status, to_status, created_at)
+SELECT
+    id,
+    NULL,
+    CASE WHEN transaction_type = 'authorization' THEN 'pending' ELSE 'completed' END,
+    created_at
+FROM transactions;
+
+-- ...and how it got to its current status, timed as closely as is known
+INSERT INTO transaction_status_history (transaction_id, from_status, to_status, created_at)
+SELECT
+    t.id,
+    CASE WHEN t.transaction_type = 'authorization' THEN 'pending' ELSE 'completed' END,
+    t.status,
+    CASE
+        WHEN t.status = 'captured' THEN (
+            SELECT c.created_at FROM transactions c WHERE c.authorization_id = t.id
+        )
+        WHEN t.status = 'reversed' THEN (
+            SELECT MAX(r.created_at) FROM transactions r WHERE r.reversed_transaction_id = t.id
+        )
+        WHEN t.status = 'expired' THEN LEAST(t.expires_at, NOW())
+        ELSE NOW()
+    END
+FROM transactions t
+WHERE t.status NOT IN ('pending', 'completed');
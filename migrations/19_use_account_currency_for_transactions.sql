@@ -0,0 +1,59 @@
+-- Currency codes are stored in canonical uppercase form
+UPDATE accounts SET currency = UPPER(TRIM(currency)) WHERE currency <> UPPER(TRIM(currency));
+
+-- Anything still not a three-letter code (e.g. '$' or 'US') can't be mapped
+-- to a currency safely. Stop and name the accounts, so their currency can be
+-- corrected by hand before the migration is retried.
+DO $$
+DECLARE
+    invalid TEXT;
+BEGIN
+    SELECT string_agg(format('%s (%L)', id, currency), ', ' ORDER BY id)
+    INTO invalid
+    FROM accounts
+    WHERE currency !~ '^[A-Z]{3}$';
+
+    IF invalid IS NOT NULL THEN
+        RAISE EXCEPTION 'Accounts without a three-letter currency code: %', invalid
+            USING HINT = 'Set their currency to the ISO 4217 code, then run the migrations again';
+    END IF;
+END
+$$;
+
+-- Every account must use a three-letter code (validated against all rows,
+-- which Postgres also re-checks on every later update of a row)
+ALTER TABLE accounts ADD CONSTRAINT accounts_currency_format
+    CHECK (currency ~ '^[A-Z]{3}$');
+
+-- Transactions take their currency from their accounts; no silent USD default
+ALTER TABLE transactions ALTER COLUMN currency DROP DEFAULT;
+
+-- Transactions labeled with a currency other than their accounts' (e.g. USD
+-- on EUR accounts). Transfers between accounts of different currencies
+-- can't be relabeled meaningfully and are left as they are.
+CREATE TEMPORARY TABLE currency_corrections ON COMMIT DROP AS
+SELECT t.id, t.currency AS old_currency, COALESCE(src.currency, dst.currency) AS new_currency
+FROM transactions t
+LEFT JOIN accounts src ON src.id = t.from_account_id
+LEFT JOIN accounts dst ON dst.id = t.to_account_id
+WHERE (src.id IS NULL OR dst.id IS NULL OR src.currency = dst.currency)
+  AND t.currency <> COALESCE(src.currency, dst.currency);
+
+-- Postings are immutable: offset each mislabeled posting and re-post it in
+-- the right currency (both sides stay balanced per currency)
+INSERT INTO ledger_entries (transaction_id, account_id, system_account_code, entry_type, amount_cents, currency)
+SELECT le.transaction_id, le.account_id, le.system_account_code,
+       CASE le.entry_type WHEN 'debit' THEN 'credit' ELSE 'debit' END,
+       le.amount_cents, le.currency
+FROM ledger_entries le
+JOIN currency_corrections c ON c.id = le.transaction_id AND le.currency = c.old_currency
+UNION ALL
+SELECT le.transaction_id, le.account_id, le.system_account_code, le.entry_type,
+       le.amount_cents, c.new_currency
+FROM ledger_entries le
+JOIN currency_corrections c ON c.id = le.transaction_id AND le.currency = c.old_currency;
+
+UPDATE transactions t
+SET currency = c.new_currency
+FROM currency_corrections c
+WHERE t.id = c.id;
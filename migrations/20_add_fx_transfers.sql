@@ -0,0 +1,47 @@
+-- Exchange rates loaded from the FX rates file.
+-- Rates are fixed-point: rate_e12 = destination units per source unit * 10^12
+CREATE TABLE fx_rates (
+    source_currency VARCHAR(3) NOT NULL,
+    destination_currency VARCHAR(3) NOT NULL,
+    rate_e12 BIGINT NOT NULL CHECK (rate_e12 > 0),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (source_currency, destination_currency),
+    CHECK (source_currency <> destination_currency)
+);
+
+-- Rates locked for a short time for one cross-currency transfer
+CREATE TABLE fx_quotes (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    api_key_id UUID NOT NULL REFERENCES api_keys(id),
+    source_currency VARCHAR(3) NOT NULL,
+    destination_currency VARCHAR(3) NOT NULL,
+    rate_e12 BIGINT NOT NULL CHECK (rate_e12 > 0),
+    source_amount_cents BIGINT NOT NULL CHECK (source_amount_cents > 0),
+    destination_amount_cents BIGINT NOT NULL CHECK (destination_amount_cents > 0),
+    expires_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Cross-currency transfers record the conversion they were made at.
+-- amount_cents/currency are the source side.
+ALTER TABLE transactions
+    ADD COLUMN fx_quote_id UUID REFERENCES fx_quotes(id),
+    ADD COLUMN destination_amount_cents BIGINT CHECK (destination_amount_cents > 0),
+    ADD COLUMN destination_currency VARCHAR(3),
+    ADD COLUMN fx_rate_e12 BIGINT CHECK (fx_rate_e12 > 0);
+
+ALTER TABLE transactions ADD CONSTRAINT transactions_fx_check CHECK (
+    (fx_quote_id IS NULL AND destination_amount_cents IS NULL
+        AND destination_currency IS NULL AND fx_rate_e12 IS NULL)
+    OR (transaction_type = 'transfer' AND fx_quote_id IS NOT NULL
+        AND destination_amount_cents IS NOT NULL
+        AND destination_currency IS NOT NULL AND fx_rate_e12 IS NOT NULL)
+);
+
+-- A quote is used by at most one transfer
+CREATE UNIQUE INDEX idx_transactions_fx_quote
+    ON transactions(fx_quote_id)
+    WHERE fx_quote_id IS NOT NULL;
+
+INSERT INTO system_accounts (code, description) VALUES
+    ('fx_clearing', 'Counter-party converting between currencies in cross-currency transfers');
@@ -0,0 +1,10 @@
+-- Account lifecycle: frozen accounts can't send money, closed accounts
+-- can't send or receive it. Transitions are enforced by the application.
+ALTER TABLE accounts
+    ADD COLUMN status VARCHAR(20) NOT NULL DEFAULT 'active'
+        CHECK (status IN ('active', 'frozen', 'closed')),
+    ADD COLUMN status_changed_at TIMESTAMPTZ;
+
+-- A closed account holds no money
+ALTER TABLE accounts ADD CONSTRAINT accounts_closed_empty_check
+    CHECK (status <> 'closed' OR balance_cents = 0);
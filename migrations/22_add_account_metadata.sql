@@ -0,0 +1,14 @@
+-- Client-defined data on accounts: string key/value metadata and an
+-- optional reference to the client's own record (e.g. a customer ID)
+ALTER TABLE accounts
+    ADD COLUMN metadata JSONB NOT NULL DEFAULT '{}'::jsonb
+        CHECK (jsonb_typeof(metadata) = 'object'),
+    ADD COLUMN external_reference VARCHAR(255);
+
+-- A reference identifies one account per business
+CREATE UNIQUE INDEX idx_accounts_external_reference
+    ON accounts(api_key_id, external_reference)
+    WHERE external_reference IS NOT NULL;
+
+-- Metadata search in account listings
+CREATE INDEX idx_accounts_metadata ON accounts USING GIN (metadata);
@@ -0,0 +1,7 @@
+-- Keyset pagination of account lists: one index per sort column, with id
+-- as the tie-breaker. The leading api_key_id makes idx_accounts_api_key redundant.
+CREATE INDEX idx_accounts_api_key_created ON accounts(api_key_id, created_at, id);
+CREATE INDEX idx_accounts_api_key_name ON accounts(api_key_id, account_name, id);
+CREATE INDEX idx_accounts_api_key_balance ON accounts(api_key_id, balance_cents, id);
+
+DROP INDEX idx_accounts_api_key;
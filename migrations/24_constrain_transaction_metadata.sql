@@ -0,0 +1,11 @@
+-- Transaction metadata is set through the API: string key/value pairs like
+-- account metadata. Transactions recorded before have none.
+UPDATE transactions SET metadata = '{}'::jsonb WHERE metadata IS NULL;
+
+ALTER TABLE transactions
+    ALTER COLUMN metadata SET DEFAULT '{}'::jsonb,
+    ALTER COLUMN metadata SET NOT NULL,
+    ADD CONSTRAINT transactions_metadata_check CHECK (jsonb_typeof(metadata) = 'object');
+
+-- Metadata search in transaction listings
+CREATE INDEX idx_transactions_metadata ON transactions USING GIN (metadata);
@@ -0,0 +1,4 @@
+-- Redelivery keeps attempt_count as the event's full attempt history and
+-- records where the new retry schedule starts
+ALTER TABLE webhook_events
+    ADD COLUMN attempts_before_redelivery INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,43 @@
+-- Accounts created before opening balances were recorded as transactions
+-- hold money no transaction explains. Only backfill balances that are
+-- provably the seeded opening balance: the account has no transactions at
+-- all and its balance was never changed after it was inserted (every
+-- balance change also sets updated_at). Any other gap may be drift and is
+-- left for reconciliation to report.
+--
+-- The transactions and postings are dated to when they are written; the
+-- account's created_at still records when it was opened.
+CREATE TEMPORARY TABLE opening_balance_backfill ON COMMIT DROP AS
+SELECT
+    gen_random_uuid() AS transaction_id,
+    a.id AS account_id,
+    a.api_key_id,
+    a.currency,
+    a.balance_cents AS amount_cents
+FROM accounts a
+WHERE a.balance_cents > 0
+  AND a.updated_at = a.created_at
+  AND NOT EXISTS (
+      SELECT 1 FROM transactions t
+      WHERE t.from_account_id = a.id OR t.to_account_id = a.id
+  );
+
+INSERT INTO transactions (
+    id, transaction_type, to_account_id, amount_cents, currency, description,
+    status, api_key_id
+)
+SELECT
+    transaction_id, 'opening_balance', account_id, amount_cents, currency, 'Opening balance',
+    'completed', api_key_id
+FROM opening_balance_backfill;
+
+INSERT INTO ledger_entries (transaction_id, account_id, system_account_code, entry_type, amount_cents, currency)
+SELECT transaction_id, NULL, 'opening_balances', 'debit', amount_cents, currency
+FROM opening_balance_backfill
+UNION ALL
+SELECT transaction_id, account_id, NULL, 'credit', amount_cents, currency
+FROM opening_balance_backfill;
+
+INSERT INTO transaction_status_history (transaction_id, from_status, to_status)
+SELECT transaction_id, NULL, 'completed'
+FROM opening_balance_backfill;
@@ -0,0 +1,9 @@
+-- Account creation accepts an idempotency key, so a retried create returns
+-- the account instead of opening (and funding) a second one
+ALTER TABLE accounts
+    ADD COLUMN idempotency_key VARCHAR(255),
+    ADD COLUMN request_fingerprint VARCHAR(64);
+
+CREATE UNIQUE INDEX idx_accounts_idempotency
+    ON accounts(api_key_id, idempotency_key)
+    WHERE idempotency_key IS NOT NULL;
@@ -0,0 +1,7 @@
+-- Keyset pagination of account transaction history: one index per side of
+-- a transaction, in the (created_at, id) order pages are read in.
+CREATE INDEX idx_transactions_from_created ON transactions(from_account_id, created_at, id);
+CREATE INDEX idx_transactions_to_created ON transactions(to_account_id, created_at, id);
+
+DROP INDEX idx_transactions_from;
+DROP INDEX idx_transactions_to;
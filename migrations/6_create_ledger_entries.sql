@@ -0,0 +1,64 @@
+CREATE TABLE system_accounts (
+    code VARCHAR(50) PRIMARY KEY,
+    description TEXT NOT NULL
+);
+
+INSERT INTO system_accounts (code, description) VALUES
+    ('external_funding', 'Counter-party for money entering the system through credits'),
+    ('external_payout', 'Counter-party for money leaving the system through debits');
+
+CREATE TABLE ledger_entries (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    transaction_id UUID NOT NULL REFERENCES transactions(id),
+    account_id UUID REFERENCES accounts(id),
+    system_account_code VARCHAR(50) REFERENCES system_accounts(code),
+    entry_type VARCHAR(6) NOT NULL CHECK (entry_type IN ('debit', 'credit')),
+    amount_cents BIGINT NOT NULL CHECK (amount_cents > 0),
+    currency VARCHAR(3) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT single_ledger_account CHECK ((account_id IS NULL) <> (system_account_code IS NULL))
+);
+
+CREATE INDEX idx_ledger_entries_transaction ON ledger_entries(transaction_id);
+
+CREATE INDEX idx_ledger_entries_account ON ledger_entries(account_id, created_at);
+
+-- Postings are append-only: corrections are made with new transactions
+CREATE FUNCTION prevent_ledger_entry_mutation() RETURNS trigger AS $$
+BEGIN
+    RAISE EXCEPTION 'ledger_entries are immutable';
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER ledger_entries_immutable
+    BEFORE UPDATE OR DELETE ON ledger_entries
+    FOR EACH ROW EXECUTE FUNCTION prevent_ledger_entry_mutation();
+
+-- Debits and credits of a transaction must net to zero per currency at commit time
+CREATE FUNCTION check_ledger_transaction_balanced() RETURNS trigger AS $$
+BEGIN
+    IF EXISTS (
+        SELECT 1 FROM ledger_entries
+        WHERE transaction_id = NEW.transaction_id
+        GROUP BY currency
+        HAVING SUM(CASE WHEN entry_type = 'debit' THEN amount_cents ELSE -amount_cents END) <> 0
+    ) THEN
+        RAISE EXCEPTION 'ledger entries for transaction % do not balance', NEW.transaction_id;
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE CONSTRAINT TRIGGER ledger_entries_balanced
+    AFTER INSERT ON ledger_entries
+    DEFERRABLE INITIALLY DEFERRED
+    FOR EACH ROW EXECUTE FUNCTION check_ledger_transaction_balanced();
+
+-- Backfill postings for transactions recorded before the ledger existed
+INSERT INTO ledger_entries (transaction_id, account_id, system_account_code, entry_type, amount_cents, currency, created_at)
+SELECT id, from_account_id, CASE WHEN from_account_id IS NULL THEN 'external_funding' END, 'debit', amount_cents, currency, created_at
+FROM transactions;
+
+INSERT INTO ledger_entries (transaction_id, account_id, system_account_code, entry_type, amount_cents, currency, created_at)
+SELECT id, to_account_id, CASE WHEN to_account_id IS NULL THEN 'external_payout' END, 'credit', amount_cents, currency, created_at
+FROM transactions;
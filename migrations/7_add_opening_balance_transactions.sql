@@ -0,0 +1,8 @@
+-- Opening balances are recorded as their own transaction type
+ALTER TABLE transactions DROP CONSTRAINT transactions_transaction_type_check;
+
+ALTER TABLE transactions ADD CONSTRAINT transactions_transaction_type_check
+    CHECK (transaction_type IN ('credit', 'debit', 'transfer', 'opening_balance'));
+
+INSERT INTO system_accounts (code, description) VALUES
+    ('opening_balances', 'Counter-party for balances an account is opened with');
@@ -0,0 +1,21 @@
+-- Transactions record the business that created them
+ALTER TABLE transactions ADD COLUMN api_key_id UUID REFERENCES api_keys(id);
+
+UPDATE transactions t
+SET api_key_id = a.api_key_id
+FROM accounts a
+WHERE a.id = COALESCE(t.to_account_id, t.from_account_id);
+
+ALTER TABLE transactions ALTER COLUMN api_key_id SET NOT NULL;
+
+-- SHA-256 of the request an idempotency key was first used with
+ALTER TABLE transactions ADD COLUMN request_fingerprint VARCHAR(64);
+
+-- Idempotency keys are unique per business and operation, not globally
+ALTER TABLE transactions DROP CONSTRAINT transactions_idempotency_key_key;
+
+DROP INDEX idx_transactions_idempotency;
+
+CREATE UNIQUE INDEX idx_transactions_idempotency
+    ON transactions(api_key_id, transaction_type, idempotency_key)
+    WHERE idempotency_key IS NOT NULL;
@@ -0,0 +1,31 @@
+-- Turn webhook_events into a transactional outbox.
+--
+-- Events are inserted as 'pending' in the same database transaction as the
+-- money movement; a background dispatcher delivers them and retries with
+-- exponential backoff until they are 'delivered' or 'dead'.
+
+ALTER TABLE webhook_events RENAME COLUMN sent_at TO created_at;
+
+ALTER TABLE webhook_events
+    ADD COLUMN status VARCHAR(20) NOT NULL DEFAULT 'pending'
+        CHECK (status IN ('pending', 'delivered', 'dead')),
+    ADD COLUMN attempt_count INTEGER NOT NULL DEFAULT 0,
+    ADD COLUMN next_attempt_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    ADD COLUMN last_attempt_at TIMESTAMPTZ,
+    ADD COLUMN delivered_at TIMESTAMPTZ;
+
+-- Events recorded before the outbox existed were single delivery attempts
+UPDATE webhook_events
+SET attempt_count = 1,
+    last_attempt_at = created_at,
+    status = CASE
+        WHEN response_status BETWEEN 200 AND 299 THEN 'delivered'
+        ELSE 'dead'
+    END,
+    delivered_at = CASE
+        WHEN response_status BETWEEN 200 AND 299 THEN created_at
+    END;
+
+-- Dispatcher polls for due pending events
+CREATE INDEX idx_webhook_events_due ON webhook_events(next_attempt_at)
+    WHERE status = 'pending';
@@ -173,6 +173,7 @@ fi
 echo ""
 
 # Test 9: Register Webhook
+# (plain HTTP to localhost requires WEBHOOK_ALLOWED_HOSTS=localhost on the server)
 echo -e "${BLUE}[9/10] Registering webhook endpoint...${NC}"
 WEBHOOK=$(curl -s -X POST $BASE_URL/api/v1/webhooks \
   -H "Authorization: Bearer $API_KEY" \
@@ -5,18 +5,91 @@
 
 use serde::Deserialize;
 
+/// Lowest accepted `WEBHOOK_ENDPOINT_RATE_PER_SEC` other than 0 (one request
+/// per 100 seconds). Waits for a token are `1 / rate` seconds, which must
+/// fit in a `Duration`.
+const MIN_WEBHOOK_ENDPOINT_RATE_PER_SEC: f64 = 0.01;
+
 /// Application configuration loaded from environment variables.
 ///
 /// # Environment Variables
 ///
 /// - `DATABASE_URL` (required): PostgreSQL connection string
 /// - `SERVER_PORT` (optional): HTTP server port, defaults to 3000
+/// - `RECONCILIATION_INTERVAL_SECS` (optional): Seconds between balance reconciliation runs (> 0), defaults to 300
+/// - `AUTHORIZATION_EXPIRY_INTERVAL_SECS` (optional): Seconds between runs releasing expired authorization holds (> 0), defaults to 60
+/// - `FX_RATES_FILE` (optional): Path of a JSON file of exchange rates, loaded at startup; cross-currency quotes are unavailable if unset
+/// - `FX_RATES_RELOAD_INTERVAL_SECS` (optional): Seconds between reloads of `FX_RATES_FILE` (> 0), defaults to 60
+/// - `WEBHOOK_DISPATCH_INTERVAL_SECS` (optional): Seconds between webhook outbox polls (> 0), defaults to 1
+/// - `WEBHOOK_MAX_ATTEMPTS` (optional): Delivery attempts before a webhook event is marked dead (> 0), defaults to 12
+/// - `WEBHOOK_RETRY_BASE_SECS` (optional): Delay before the first webhook retry, doubled per attempt, defaults to 30
+/// - `WEBHOOK_RETRY_MAX_SECS` (optional): Upper bound for the webhook retry delay, defaults to 21600 (6 hours)
+/// - `WEBHOOK_TIMEOUT_SECS` (optional): Total time allowed for one webhook delivery attempt (> 0), defaults to 5
+/// - `WEBHOOK_CONNECT_TIMEOUT_SECS` (optional): Time allowed to connect to a webhook receiver (> 0), defaults to 2
+/// - `WEBHOOK_MAX_CONCURRENCY` (optional): Maximum in-flight webhook requests across all endpoints (> 0), defaults to 64
+/// - `WEBHOOK_ENDPOINT_MAX_CONCURRENCY` (optional): Maximum in-flight webhook requests per endpoint (> 0), defaults to 4
+/// - `WEBHOOK_ENDPOINT_RATE_PER_SEC` (optional): Sustained webhook requests per second per endpoint (0 = unlimited, otherwise at least 0.01), defaults to 10
+/// - `WEBHOOK_ENDPOINT_BURST` (optional): Webhook requests per endpoint allowed at once before the rate applies (> 0), defaults to 20
+/// - `WEBHOOK_DISABLE_AFTER_FAILURES` (optional): Consecutive failed delivery attempts before an endpoint is disabled (0 = never), defaults to 50
+/// - `WEBHOOK_DISABLE_AFTER_SECS` (optional): Minimum duration of the failure streak before an endpoint is disabled, defaults to 86400 (24 hours)
+/// - `WEBHOOK_ALLOWED_HOSTS` (optional): Comma-separated hosts exempt from webhook SSRF checks (development only), defaults to none
 #[derive(Debug, Clone, Deserialize)]
 pub struct Config {
     pub database_url: String,
 
     #[serde(default = "default_port")]
     pub server_port: u16,
+
+    #[serde(default = "default_reconciliation_interval_secs")]
+    pub reconciliation_interval_secs: u64,
+
+    #[serde(default = "default_authorization_expiry_interval_secs")]
+    pub authorization_expiry_interval_secs: u64,
+
+    #[serde(default)]
+    pub fx_rates_file: Option<String>,
+
+    #[serde(default = "default_fx_rates_reload_interval_secs")]
+    pub fx_rates_reload_interval_secs: u64,
+
+    #[serde(default = "default_webhook_dispatch_interval_secs")]
+    pub webhook_dispatch_interval_secs: u64,
+
+    #[serde(default = "default_webhook_max_attempts")]
+    pub webhook_max_attempts: i32,
+
+    #[serde(default = "default_webhook_retry_base_secs")]
+    pub webhook_retry_base_secs: u64,
+
+    #[serde(default = "default_webhook_retry_max_secs")]
+    pub webhook_retry_max_secs: u64,
+
+    #[serde(default = "default_webhook_timeout_secs")]
+    pub webhook_timeout_secs: u64,
+
+    #[serde(default = "default_webhook_connect_timeout_secs")]
+    pub webhook_connect_timeout_secs: u64,
+
+    #[serde(default = "default_webhook_max_concurrency")]
+    pub webhook_max_concurrency: usize,
+
+    #[serde(default = "default_webhook_endpoint_max_concurrency")]
+    pub webhook_endpoint_max_concurrency: usize,
+
+    #[serde(default = "default_webhook_endpoint_rate_per_sec")]
+    pub webhook_endpoint_rate_per_sec: f64,
+
+    #[serde(default = "default_webhook_endpoint_burst")]
+    pub webhook_endpoint_burst: u32,
+
+    #[serde(default = "default_webhook_disable_after_failures")]
+    pub webhook_disable_after_failures: i32,
+
+    #[serde(default = "default_webhook_disable_after_secs")]
+    pub webhook_disable_after_secs: u64,
+
+    #[serde(default)]
+    pub webhook_allowed_hosts: Vec<String>,
 }
 
 /// Default port if SERVER_PORT environment variable is not set.
@@ -24,6 +97,86 @@ fn default_port() -> u16 {
     3000
 }
 
+/// Default reconciliation interval if RECONCILIATION_INTERVAL_SECS is not set.
+fn default_reconciliation_interval_secs() -> u64 {
+    300
+}
+
+/// Default expiry interval if AUTHORIZATION_EXPIRY_INTERVAL_SECS is not set.
+fn default_authorization_expiry_interval_secs() -> u64 {
+    60
+}
+
+/// Default rates file reload interval if FX_RATES_RELOAD_INTERVAL_SECS is not set.
+fn default_fx_rates_reload_interval_secs() -> u64 {
+    60
+}
+
+/// Default outbox poll interval if WEBHOOK_DISPATCH_INTERVAL_SECS is not set.
+fn default_webhook_dispatch_interval_secs() -> u64 {
+    1
+}
+
+/// Default delivery attempts if WEBHOOK_MAX_ATTEMPTS is not set.
+///
+/// With the default backoff this spreads retries over roughly 14 hours.
+fn default_webhook_max_attempts() -> i32 {
+    12
+}
+
+/// Default first retry delay if WEBHOOK_RETRY_BASE_SECS is not set.
+fn default_webhook_retry_base_secs() -> u64 {
+    30
+}
+
+/// Default retry delay cap if WEBHOOK_RETRY_MAX_SECS is not set.
+fn default_webhook_retry_max_secs() -> u64 {
+    6 * 60 * 60
+}
+
+/// Default delivery attempt timeout if WEBHOOK_TIMEOUT_SECS is not set.
+fn default_webhook_timeout_secs() -> u64 {
+    5
+}
+
+/// Default connect timeout if WEBHOOK_CONNECT_TIMEOUT_SECS is not set.
+fn default_webhook_connect_timeout_secs() -> u64 {
+    2
+}
+
+/// Default global webhook concurrency if WEBHOOK_MAX_CONCURRENCY is not set.
+fn default_webhook_max_concurrency() -> usize {
+    64
+}
+
+/// Default per-endpoint concurrency if WEBHOOK_ENDPOINT_MAX_CONCURRENCY is not set.
+fn default_webhook_endpoint_max_concurrency() -> usize {
+    4
+}
+
+/// Default per-endpoint rate if WEBHOOK_ENDPOINT_RATE_PER_SEC is not set.
+fn default_webhook_endpoint_rate_per_sec() -> f64 {
+    10.0
+}
+
+/// Default per-endpoint burst if WEBHOOK_ENDPOINT_BURST is not set.
+fn default_webhook_endpoint_burst() -> u32 {
+    20
+}
+
+/// Default failure threshold if WEBHOOK_DISABLE_AFTER_FAILURES is not set.
+fn default_webhook_disable_after_failures() -> i32 {
+    50
+}
+
+/// Default minimum failure streak if WEBHOOK_DISABLE_AFTER_SECS is not set.
+///
+/// Keeps a short outage of a busy endpoint (many failures in little time)
+/// from disabling it.
+fn default_webhook_disable_after_secs() -> u64 {
+    24 * 60 * 60
+}
+
 impl Config {
     /// Load configuration from environment variables.
     ///
@@ -35,12 +188,134 @@ impl Config {
     /// Returns an error if:
     /// - Required environment variables are missing (e.g., DATABASE_URL)
     /// - Environment variable values cannot be parsed into expected types
+    /// - A setting that must be positive is 0
     pub fn from_env() -> Result<Self, envy::Error> {
         // Try to load .env file if it exists (does nothing if not found)
         dotenvy::dotenv().ok();
 
         // Parse environment variables into Config struct
         // Field names are automatically converted: database_url -> DATABASE_URL
-        envy::from_env::<Config>()
+        let config = envy::from_env::<Config>()?;
+        config.validate()?;
+
+        Ok(config)
+    }
+
+    /// Reject settings that parse but can't work.
+    ///
+    /// Task intervals must be positive: `tokio::time::interval` panics on a
+    /// zero period, which would kill the background task while the server
+    /// keeps running. Concurrency limits must be positive: a semaphore
+    /// without permits makes every webhook delivery wait forever. So must
+    /// the delivery attempts and timeouts: without attempts no event is ever
+    /// sent, and a zero timeout fails every attempt. The endpoint rate must
+    /// be 0 (unlimited) or a finite rate of at least
+    /// `MIN_WEBHOOK_ENDPOINT_RATE_PER_SEC`: NaN would silently disable the
+    /// limit and a tiny rate overflows the wait for the next token.
+    fn validate(&self) -> Result<(), envy::Error> {
+        let positive = [
+            (
+                "RECONCILIATION_INTERVAL_SECS",
+                self.reconciliation_interval_secs,
+            ),
+            (
+                "AUTHORIZATION_EXPIRY_INTERVAL_SECS",
+                self.authorization_expiry_interval_secs,
+            ),
+            (
+                "FX_RATES_RELOAD_INTERVAL_SECS",
+                self.fx_rates_reload_interval_secs,
+            ),
+            (
+                "WEBHOOK_DISPATCH_INTERVAL_SECS",
+                self.webhook_dispatch_interval_secs,
+            ),
+            (
+                "WEBHOOK_MAX_ATTEMPTS",
+                u64::try_from(self.webhook_max_attempts).unwrap_or(0),
+            ),
+            ("WEBHOOK_TIMEOUT_SECS", self.webhook_timeout_secs),
+            (
+                "WEBHOOK_CONNECT_TIMEOUT_SECS",
+                self.webhook_connect_timeout_secs,
+            ),
+            (
+                "WEBHOOK_MAX_CONCURRENCY",
+                self.webhook_max_concurrency as u64,
+            ),
+            (
+                "WEBHOOK_ENDPOINT_MAX_CONCURRENCY",
+                self.webhook_endpoint_max_concurrency as u64,
+            ),
+            (
+                "WEBHOOK_ENDPOINT_BURST",
+                u64::from(self.webhook_endpoint_burst),
+            ),
+        ];
+
+        for (name, value) in positive {
+            if value == 0 {
+                return Err(envy::Error::Custom(format!(
+                    "{} must be greater than 0",
+                    name
+                )));
+            }
+        }
+
+        let rate = self.webhook_endpoint_rate_per_sec;
+        if rate != 0.0 && !(rate.is_finite() && rate >= MIN_WEBHOOK_ENDPOINT_RATE_PER_SEC) {
+            return Err(envy::Error::Custom(format!(
+                "WEBHOOK_ENDPOINT_RATE_PER_SEC must be 0 (unlimited) or a finite number of at least {}",
+                MIN_WEBHOOK_ENDPOINT_RATE_PER_SEC
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Parse a configuration from the given variables plus `DATABASE_URL`.
+    fn parse(vars: &[(&str, &str)]) -> Result<Config, envy::Error> {
+        let config = envy::from_iter::<_, Config>(
+            [("DATABASE_URL", "postgres://localhost/test")]
+                .iter()
+                .chain(vars)
+                .map(|(name, value)| (name.to_string(), value.to_string())),
+        )?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    #[test]
+    fn accepts_defaults() {
+        assert!(parse(&[]).is_ok());
+    }
+
+    #[test]
+    fn rejects_webhook_deliveries_that_cannot_succeed() {
+        assert!(parse(&[("WEBHOOK_MAX_ATTEMPTS", "0")]).is_err());
+        assert!(parse(&[("WEBHOOK_MAX_ATTEMPTS", "-3")]).is_err());
+        assert!(parse(&[("WEBHOOK_TIMEOUT_SECS", "0")]).is_err());
+        assert!(parse(&[("WEBHOOK_CONNECT_TIMEOUT_SECS", "0")]).is_err());
+        assert!(parse(&[("WEBHOOK_MAX_ATTEMPTS", "1"), ("WEBHOOK_TIMEOUT_SECS", "1")]).is_ok());
+    }
+
+    #[test]
+    fn rejects_unusable_webhook_endpoint_rates() {
+        assert!(parse(&[("WEBHOOK_ENDPOINT_RATE_PER_SEC", "0")]).is_ok());
+        assert!(parse(&[("WEBHOOK_ENDPOINT_RATE_PER_SEC", "0.01")]).is_ok());
+
+        for rate in ["1e-30", "-1", "NaN", "inf"] {
+            assert!(
+                parse(&[("WEBHOOK_ENDPOINT_RATE_PER_SEC", rate)]).is_err(),
+                "{}",
+                rate
+            );
+        }
+        assert!(parse(&[("WEBHOOK_ENDPOINT_BURST", "0")]).is_err());
     }
 }
@@ -62,3 +62,76 @@ pub async fn run_migrations(pool: &DbPool) -> Result<(), sqlx::migrate::MigrateE
     // The macro reads migrations at compile time from ./migrations directory
     sqlx::migrate!("./migrations").run(pool).await
 }
+
+#[cfg(all(test, feature = "db-tests"))]
+mod tests {
+    use sqlx::{Acquire, Executor, PgConnection};
+    use uuid::Uuid;
+
+    use super::*;
+
+    /// Apply one migration in its own transaction, as `run_migrations` does.
+    async fn apply(conn: &mut PgConnection, version: i64) -> Result<(), sqlx::Error> {
+        let migrator = sqlx::migrate!("./migrations");
+        let migration = migrator
+            .iter()
+            .find(|migration| migration.version == version)
+            .unwrap();
+
+        let mut tx = conn.begin().await?;
+        tx.execute(&*migration.sql).await?;
+        tx.commit().await
+    }
+
+    async fn insert_account(conn: &mut PgConnection, api_key_id: Uuid, currency: &str) -> Uuid {
+        sqlx::query_scalar(
+            "INSERT INTO accounts (api_key_id, account_name, currency) VALUES ($1, 'Legacy', $2) RETURNING id",
+        )
+        .bind(api_key_id)
+        .bind(currency)
+        .fetch_one(&mut *conn)
+        .await
+        .unwrap()
+    }
+
+    #[sqlx::test(migrations = false)]
+    async fn currency_migration_reports_accounts_it_cannot_fix(pool: DbPool) {
+        let mut conn = pool.acquire().await.unwrap();
+        for version in 1..19 {
+            apply(&mut conn, version).await.unwrap();
+        }
+
+        let api_key_id: Uuid = sqlx::query_scalar(
+            "INSERT INTO api_keys (key_hash, business_name) VALUES ('legacy', 'Legacy') RETURNING id",
+        )
+        .fetch_one(&mut *conn)
+        .await
+        .unwrap();
+        let lowercase = insert_account(&mut conn, api_key_id, "eur").await;
+        let symbol = insert_account(&mut conn, api_key_id, "$").await;
+        let short = insert_account(&mut conn, api_key_id, "US ").await;
+
+        let error = apply(&mut conn, 19).await.unwrap_err().to_string();
+        assert!(error.contains(&symbol.to_string()), "{}", error);
+        assert!(error.contains(&short.to_string()), "{}", error);
+        assert!(!error.contains(&lowercase.to_string()), "{}", error);
+
+        // Once corrected by hand, the migration applies and every account,
+        // old or new, stays updatable
+        sqlx::query("UPDATE accounts SET currency = 'USD' WHERE id = ANY($1)")
+            .bind([symbol, short])
+            .execute(&mut *conn)
+            .await
+            .unwrap();
+        apply(&mut conn, 19).await.unwrap();
+
+        let currency: String = sqlx::query_scalar(
+            "UPDATE accounts SET balance_cents = balance_cents + 1 WHERE id = $1 RETURNING currency",
+        )
+        .bind(lowercase)
+        .fetch_one(&mut *conn)
+        .await
+        .unwrap();
+        assert_eq!(currency, "EUR");
+    }
+}
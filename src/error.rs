@@ -10,6 +10,8 @@ use axum::{
 };
 use serde_json::json;
 
+use crate::models::transaction::TransactionStatus;
+
 /// Application-wide error type.
 ///
 /// This enum represents all possible errors that can occur in the application.
@@ -43,6 +45,91 @@ pub enum AppError {
     #[error("Account not found")]
     AccountNotFound,
 
+    /// Account is frozen, so money can't leave it.
+    ///
+    /// Returns HTTP 409 Conflict.
+    #[error("Account is frozen")]
+    AccountFrozen,
+
+    /// Account is closed, so money can't enter or leave it and its status
+    /// can't change.
+    ///
+    /// Returns HTTP 409 Conflict.
+    #[error("Account is closed")]
+    AccountClosed,
+
+    /// Account can't be closed while it holds money.
+    ///
+    /// Returns HTTP 409 Conflict.
+    /// The i64 is the account's balance in cents.
+    #[error("Account balance must be zero to close it (balance: {0} cents)")]
+    AccountNotEmpty(i64),
+
+    /// Another account of the business already has this external reference.
+    ///
+    /// Returns HTTP 409 Conflict.
+    #[error("An account with external_reference '{0}' already exists")]
+    ExternalReferenceConflict(String),
+
+    /// Requested transaction does not exist or doesn't belong to authenticated business.
+    ///
+    /// Returns HTTP 404 Not Found.
+    #[error("Transaction not found")]
+    TransactionNotFound,
+
+    /// Reversal amount exceeds the part of the transaction not yet reversed.
+    ///
+    /// Returns HTTP 422 Unprocessable Entity.
+    /// The i64 is the amount (in cents) that can still be reversed.
+    #[error("Reversal exceeds the remaining reversible amount of {0} cents")]
+    ReversalExceedsRemaining(i64),
+
+    /// Authorization was already captured, voided or expired.
+    ///
+    /// Returns HTTP 409 Conflict.
+    /// Carries the authorization's current status.
+    #[error("Authorization is no longer pending (status: {0})")]
+    AuthorizationNotPending(TransactionStatus),
+
+    /// Transaction status change not allowed by the transition table.
+    ///
+    /// Returns HTTP 409 Conflict.
+    #[error("Cannot change transaction status from '{from}' to '{to}'")]
+    InvalidStatusTransition {
+        from: TransactionStatus,
+        to: TransactionStatus,
+    },
+
+    /// Accounts of a transfer hold different currencies and no FX quote was given.
+    ///
+    /// Returns HTTP 422 Unprocessable Entity.
+    #[error("Currency mismatch: source account is in {from}, destination account is in {to}")]
+    CurrencyMismatch { from: String, to: String },
+
+    /// No exchange rate is loaded for the currency pair.
+    ///
+    /// Returns HTTP 422 Unprocessable Entity.
+    #[error("No exchange rate available from {from} to {to}")]
+    FxRateUnavailable { from: String, to: String },
+
+    /// Requested FX quote does not exist or doesn't belong to authenticated business.
+    ///
+    /// Returns HTTP 404 Not Found.
+    #[error("FX quote not found")]
+    FxQuoteNotFound,
+
+    /// FX quote is past its expiry; request a new one.
+    ///
+    /// Returns HTTP 409 Conflict.
+    #[error("FX quote has expired")]
+    FxQuoteExpired,
+
+    /// FX quote was already used by another transfer.
+    ///
+    /// Returns HTTP 409 Conflict.
+    #[error("FX quote was already used")]
+    FxQuoteAlreadyUsed,
+
     /// Account has insufficient balance for the requested operation.
     ///
     /// Returns HTTP 422 Unprocessable Entity.
@@ -67,6 +154,38 @@ pub enum AppError {
     /// Returns HTTP 404 Not Found.
     #[error("Webhook not found")]
     WebhookNotFound,
+
+    /// Requested webhook event does not exist or doesn't belong to authenticated business.
+    ///
+    /// Returns HTTP 404 Not Found.
+    #[error("Webhook event not found")]
+    WebhookEventNotFound,
+
+    /// Webhook event is still pending (waiting for or in the middle of a
+    /// delivery attempt) and can't be redelivered.
+    ///
+    /// Returns HTTP 409 Conflict.
+    #[error("Webhook event is still pending delivery")]
+    WebhookEventPending,
+
+    /// All delivery slots of the webhook endpoint stayed in use while a
+    /// test ping waited for one.
+    ///
+    /// Returns HTTP 409 Conflict. Clients should retry after a short delay.
+    #[error("Webhook endpoint is busy delivering other events")]
+    WebhookEndpointBusy,
+
+    /// Idempotency key was already used with a different request payload.
+    ///
+    /// Returns HTTP 409 Conflict.
+    #[error("Idempotency key was already used with a different request")]
+    IdempotencyKeyConflict,
+
+    /// Another request with the same idempotency key is still being processed.
+    ///
+    /// Returns HTTP 409 Conflict. Clients should retry after a short delay.
+    #[error("A request with this idempotency key is already in progress")]
+    RequestInProgress,
 }
 
 /// Convert AppError into an HTTP response.
@@ -90,8 +209,18 @@ pub enum AppError {
 ///
 /// - `InvalidApiKey` → 401 Unauthorized
 /// - `AccountNotFound` → 404 Not Found
-/// - `InsufficientBalance` → 422 Unprocessable Entity
+/// - `AccountFrozen` / `AccountClosed` / `AccountNotEmpty` → 409 Conflict
+/// - `ExternalReferenceConflict` → 409 Conflict
+/// - `WebhookNotFound` / `WebhookEventNotFound` → 404 Not Found
+/// - `TransactionNotFound` / `FxQuoteNotFound` → 404 Not Found
+/// - `InsufficientBalance` / `ReversalExceedsRemaining` / `CurrencyMismatch` /
+///   `FxRateUnavailable` → 422 Unprocessable Entity
 /// - `InvalidRequest` → 400 Bad Request
+/// - `WebhookEventPending` / `WebhookEndpointBusy` → 409 Conflict
+/// - `IdempotencyKeyConflict` → 409 Conflict
+/// - `AuthorizationNotPending` / `InvalidStatusTransition` → 409 Conflict
+/// - `FxQuoteExpired` / `FxQuoteAlreadyUsed` → 409 Conflict
+/// - `RequestInProgress` → 409 Conflict
 /// - `Database` → 500 Internal Server Error (hides details from client)
 impl IntoResponse for AppError {
     fn into_response(self) -> Response {
@@ -105,6 +234,59 @@ impl IntoResponse for AppError {
             AppError::AccountNotFound => {
                 (StatusCode::NOT_FOUND, "account_not_found", self.to_string())
             }
+            AppError::AccountFrozen => (StatusCode::CONFLICT, "account_frozen", self.to_string()),
+            AppError::AccountClosed => (StatusCode::CONFLICT, "account_closed", self.to_string()),
+            AppError::AccountNotEmpty(_) => {
+                (StatusCode::CONFLICT, "account_not_empty", self.to_string())
+            }
+            AppError::ExternalReferenceConflict(_) => (
+                StatusCode::CONFLICT,
+                "external_reference_conflict",
+                self.to_string(),
+            ),
+            AppError::TransactionNotFound => (
+                StatusCode::NOT_FOUND,
+                "transaction_not_found",
+                self.to_string(),
+            ),
+            AppError::ReversalExceedsRemaining(_) => (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "reversal_exceeds_remaining",
+                self.to_string(),
+            ),
+            AppError::AuthorizationNotPending(_) => (
+                StatusCode::CONFLICT,
+                "authorization_not_pending",
+                self.to_string(),
+            ),
+            AppError::InvalidStatusTransition { .. } => (
+                StatusCode::CONFLICT,
+                "invalid_status_transition",
+                self.to_string(),
+            ),
+            AppError::CurrencyMismatch { .. } => (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "currency_mismatch",
+                self.to_string(),
+            ),
+            AppError::FxRateUnavailable { .. } => (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "fx_rate_unavailable",
+                self.to_string(),
+            ),
+            AppError::FxQuoteNotFound => (
+                StatusCode::NOT_FOUND,
+                "fx_quote_not_found",
+                self.to_string(),
+            ),
+            AppError::FxQuoteExpired => {
+                (StatusCode::CONFLICT, "fx_quote_expired", self.to_string())
+            }
+            AppError::FxQuoteAlreadyUsed => (
+                StatusCode::CONFLICT,
+                "fx_quote_already_used",
+                self.to_string(),
+            ),
             AppError::InsufficientBalance => (
                 StatusCode::UNPROCESSABLE_ENTITY,
                 "insufficient_balance",
@@ -119,6 +301,31 @@ impl IntoResponse for AppError {
             AppError::WebhookNotFound => {
                 (StatusCode::NOT_FOUND, "webhook_not_found", self.to_string())
             }
+            AppError::WebhookEventNotFound => (
+                StatusCode::NOT_FOUND,
+                "webhook_event_not_found",
+                self.to_string(),
+            ),
+            AppError::WebhookEventPending => (
+                StatusCode::CONFLICT,
+                "webhook_event_pending",
+                self.to_string(),
+            ),
+            AppError::WebhookEndpointBusy => (
+                StatusCode::CONFLICT,
+                "webhook_endpoint_busy",
+                self.to_string(),
+            ),
+            AppError::IdempotencyKeyConflict => (
+                StatusCode::CONFLICT,
+                "idempotency_key_conflict",
+                self.to_string(),
+            ),
+            AppError::RequestInProgress => (
+                StatusCode::CONFLICT,
+                "request_in_progress",
+                self.to_string(),
+            ),
             AppError::Database(_) => (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 "internal_error",
@@ -4,16 +4,21 @@
 //! - POST /api/v1/accounts - Create new account
 //! - GET /api/v1/accounts/:id - Get account by ID
 //! - GET /api/v1/accounts - List all accounts for authenticated business
+//! - PATCH /api/v1/accounts/:id - Update account (name, metadata, external reference, status)
 
 use crate::{
     db::DbPool,
     error::AppError,
     middleware::auth::AuthContext,
-    models::account::{Account, AccountResponse, CreateAccountRequest},
+    models::account::{
+        Account, AccountListQuery, AccountListResponse, AccountResponse, CreateAccountRequest,
+        UpdateAccountRequest,
+    },
+    services::account_service,
 };
 use axum::{
     Extension, Json,
-    extract::{Path, State},
+    extract::{Path, Query, State},
 };
 use uuid::Uuid;
 
@@ -32,13 +37,20 @@ use uuid::Uuid;
 /// ```json
 /// {
 ///   "account_name": "My Account",
-///   "currency": "USD"  // optional, defaults to USD
+///   "currency": "USD",  // optional, defaults to USD
+///   "initial_balance_cents": 10000,  // optional, defaults to 0
+///   "external_reference": "cus_123",  // optional, unique per business
+///   "metadata": { "segment": "enterprise" },  // optional string key/value pairs
+///   "idempotency_key": "open-cus_123"  // optional, for safe retries
 /// }
 /// ```
 ///
 /// # Response
 ///
 /// - **Success (201 Created)**: Returns the created account
+/// - **Error (400)**: Negative initial balance, invalid reference or metadata
+/// - **Error (409)**: External reference used by another account, or
+///   idempotency key reused with a different payload or still in progress
 /// - **Error (401)**: Invalid API key
 /// - **Error (500)**: Database error
 ///
@@ -46,7 +58,8 @@ use uuid::Uuid;
 /// {
 ///   "id": "550e8400-e29b-41d4-a716-446655440000",
 ///   "account_name": "My Account",
-///   "balance_cents": 0,
+///   "balance_cents": 10000,
+///   "available_balance_cents": 10000,
 ///   "currency": "USD",
 ///   "created_at": "2025-12-20T10:00:00Z",
 ///   "updated_at": "2025-12-20T10:00:00Z"
@@ -61,30 +74,18 @@ use uuid::Uuid;
 ///
 /// # Database Operation
 ///
-/// Inserts a new row into `accounts` table with:
-/// - api_key_id from auth context (ensures ownership)
-/// - account_name from request
-/// - currency from request (or "USD" default)
-/// - balance_cents initialized to 0
+/// In a single database transaction:
+/// - Returns the account already created with `idempotency_key`, if any
+/// - Inserts a new row into `accounts` with balance_cents 0 and the
+///   api_key_id from auth context (ensures ownership)
+/// - Records a non-zero `initial_balance_cents` as an `opening_balance`
+///   transaction, which also fires the usual webhook
 pub async fn create_account(
     State(pool): State<DbPool>,
     Extension(auth): Extension<AuthContext>,
     Json(request): Json<CreateAccountRequest>,
 ) -> Result<Json<AccountResponse>, AppError> {
-    let account = sqlx::query_as::<_, Account>(
-        r#"
-        INSERT INTO accounts (api_key_id, account_name, currency, balance_cents)
-        VALUES ($1, $2, $3, $4)
-        RETURNING id, api_key_id, account_name, balance_cents, currency, created_at, updated_at
-        "#,
-    )
-    // Link to authenticated business
-    .bind(auth.api_key_id)
-    .bind(request.account_name)
-    .bind(&request.currency)
-    .bind(request.initial_balance_cents)
-    .fetch_one(&pool)
-    .await?;
+    let account = account_service::create_account(&pool, auth.api_key_id, request).await?;
 
     // Convert Account to AccountResponse (removes api_key_id)
     Ok(Json(account.into()))
@@ -127,7 +128,7 @@ pub async fn get_account(
     // Query account by ID AND api_key_id (security filter)
     let account = sqlx::query_as::<_, Account>(
         r#"
-        SELECT id, api_key_id, account_name, balance_cents, currency, created_at, updated_at
+        SELECT id, api_key_id, account_name, balance_cents, held_cents, currency, status, status_changed_at, metadata, external_reference, created_at, updated_at
         FROM accounts
         WHERE id = $1 AND api_key_id = $2
         "#,
@@ -143,7 +144,8 @@ pub async fn get_account(
     Ok(Json(account.into()))
 }
 
-/// List all accounts for the authenticated business.
+/// List the accounts of the authenticated business (paginated, newest first
+/// by default).
 ///
 /// # Endpoint
 ///
@@ -153,60 +155,107 @@ pub async fn get_account(
 ///
 /// Requires valid API key.
 ///
+/// # Query Parameters
+///
+/// - `limit` - Page size (1-100, default 25)
+/// - `starting_after` - Cursor: `next_cursor` from the previous page
+/// - `sort` - `created_at` (default), `account_name` or `balance_cents`
+/// - `order` - `desc` (default) or `asc`
+/// - `currency` - ISO 4217 code
+/// - `name_prefix` - Account name starts with (case-sensitive)
+/// - `min_balance_cents` / `max_balance_cents` - Inclusive balance range
+/// - `created_after` / `created_before` - RFC 3339 timestamps
+/// - `external_reference` - Only the account with this reference
+/// - `metadata_key` - Only accounts with this metadata key
+/// - `metadata_value` - With `metadata_key`, only accounts whose key has this value
+///
 /// # Response
 ///
-/// - **Success (200 OK)**: Returns array of accounts (may be empty)
+/// - **Success (200 OK)**: One page of accounts (may be empty)
+/// - **Error (400)**: Invalid limit or filters
 /// - **Error (401)**: Invalid API key
 ///
 /// ```json
-/// [
-///   {
-///     "id": "550e8400-e29b-41d4-a716-446655440000",
-///     "account_name": "Account 1",
-///     "balance_cents": 100000,
-///     "currency": "USD",
-///     "created_at": "2025-12-20T10:00:00Z",
-///     "updated_at": "2025-12-20T10:00:00Z"
-///   },
-///   {
-///     "id": "660e8400-e29b-41d4-a716-446655440001",
-///     "account_name": "Account 2",
-///     "balance_cents": 50000,
-///     "currency": "USD",
-///     "created_at": "2025-12-20T11:00:00Z",
-///     "updated_at": "2025-12-20T11:00:00Z"
-///   }
-/// ]
+/// {
+///   "data": [
+///     {
+///       "id": "550e8400-e29b-41d4-a716-446655440000",
+///       "account_name": "Account 1",
+///       "balance_cents": 100000,
+///       "available_balance_cents": 100000,
+///       "currency": "USD",
+///       "created_at": "2025-12-20T10:00:00Z",
+///       "updated_at": "2025-12-20T10:00:00Z"
+///     }
+///   ],
+///   "has_more": true,
+///   "next_cursor": "550e8400-e29b-41d4-a716-446655440000"
+/// }
 /// ```
 ///
-/// # Ordering
-///
-/// Accounts are returned in reverse chronological order (newest first).
-///
 /// # Arguments
 ///
 /// * `State(pool)` - Database connection pool
 /// * `Extension(auth)` - Authentication context
+/// * `Query(query)` - Pagination, sort and filters
 pub async fn list_accounts(
     State(pool): State<DbPool>,
     Extension(auth): Extension<AuthContext>,
-) -> Result<Json<Vec<AccountResponse>>, AppError> {
-    // Fetch all accounts for this business
-    let accounts = sqlx::query_as::<_, Account>(
-        r#"
-        SELECT id, api_key_id, account_name, balance_cents, currency, created_at, updated_at
-        FROM accounts
-        WHERE api_key_id = $1
-        ORDER BY created_at DESC
-        "#,
-    )
-    // Only fetch accounts for authenticated business
-    .bind(auth.api_key_id)
-    .fetch_all(&pool)
-    .await?;
+    Query(query): Query<AccountListQuery>,
+) -> Result<Json<AccountListResponse>, AppError> {
+    let (accounts, has_more) =
+        account_service::list_accounts(&pool, auth.api_key_id, query).await?;
 
-    // Convert each Account to AccountResponse
-    let responses: Vec<AccountResponse> = accounts.into_iter().map(Into::into).collect();
+    Ok(Json(AccountListResponse::from_rows(
+        accounts,
+        has_more,
+        |a| a.id,
+    )))
+}
 
-    Ok(Json(responses))
+/// Update an account: rename it, link it to a client record, change its
+/// metadata or its status (e.g. freeze it during an investigation).
+///
+/// # Endpoint
+///
+/// `PATCH /api/v1/accounts/:id`
+///
+/// # Request Body
+///
+/// ```json
+/// {
+///   "account_name": "Payouts",
+///   "external_reference": "cus_123",  // null removes it
+///   "metadata": { "segment": "enterprise", "legacy_id": null },  // merged; null removes a key
+///   "status": "frozen"  // "active", "frozen" or "closed"
+/// }
+/// ```
+///
+/// All fields are optional, but at least one is required.
+///
+/// # Response
+///
+/// - **Success (200 OK)**: Returns the updated account
+/// - **Error (400)**: No field given, empty name, invalid reference or metadata
+/// - **Error (404)**: Account not found or not owned by authenticated business
+/// - **Error (409)**: Account is closed, closing an account with a non-zero
+///   balance, or external reference used by another account
+///
+/// # Status Effects
+///
+/// - `frozen`: Money can still come in; debits, transfers out,
+///   authorizations and captures are rejected with `account_frozen`
+/// - `closed`: Final; all money movement is rejected with `account_closed`
+///
+/// Each change sends an `account.status_changed` webhook.
+pub async fn update_account(
+    State(pool): State<DbPool>,
+    Extension(auth): Extension<AuthContext>,
+    Path(account_id): Path<Uuid>,
+    Json(request): Json<UpdateAccountRequest>,
+) -> Result<Json<AccountResponse>, AppError> {
+    let account =
+        account_service::update_account(&pool, account_id, auth.api_key_id, request).await?;
+
+    Ok(Json(account.into()))
 }
@@ -0,0 +1,61 @@
+//! Foreign-exchange HTTP handlers.
+//!
+//! This module implements FX-related API endpoints:
+//! - POST /api/v1/fx/quotes - Lock an exchange rate for a cross-currency transfer
+
+use crate::{
+    db::DbPool,
+    error::AppError,
+    middleware::auth::AuthContext,
+    models::fx::{CreateFxQuoteRequest, FxQuoteResponse},
+    services::fx_service,
+};
+use axum::{Extension, Json, extract::State};
+
+/// Quote a cross-currency amount at the current rate.
+///
+/// # Endpoint
+///
+/// `POST /api/v1/fx/quotes`
+///
+/// # Request Body
+///
+/// ```json
+/// {
+///   "source_currency": "USD",
+///   "destination_currency": "EUR",
+///   "source_amount_cents": 10000
+/// }
+/// ```
+///
+/// # Response (200)
+///
+/// ```json
+/// {
+///   "id": "a20e8400-e29b-41d4-a716-446655440000",
+///   "source_currency": "USD",
+///   "destination_currency": "EUR",
+///   "rate": "0.92",
+///   "source_amount_cents": 10000,
+///   "destination_amount_cents": 9200,
+///   "expires_at": "2025-12-21T16:01:00Z",
+///   "created_at": "2025-12-21T16:00:00Z"
+/// }
+/// ```
+///
+/// The quote is valid until `expires_at` and can be used by one transfer
+/// (`fx_quote_id`) of exactly `source_amount_cents`.
+///
+/// # Errors
+///
+/// - **400**: Unknown currency, same currency on both sides, or invalid amount
+/// - **422**: No rate loaded for the currency pair
+pub async fn create_fx_quote(
+    State(pool): State<DbPool>,
+    Extension(auth): Extension<AuthContext>,
+    Json(request): Json<CreateFxQuoteRequest>,
+) -> Result<Json<FxQuoteResponse>, AppError> {
+    let quote = fx_service::create_quote(&pool, auth.api_key_id, request).await?;
+
+    Ok(Json(quote.into()))
+}
@@ -0,0 +1,39 @@
+//! Ledger reporting HTTP handlers.
+//!
+//! This module implements ledger-related API endpoints:
+//! - GET /api/v1/ledger/trial-balance - Debit/credit totals per ledger account
+
+use crate::{
+    db::DbPool, error::AppError, middleware::auth::AuthContext,
+    models::ledger::TrialBalanceResponse, services::ledger_service,
+};
+use axum::{Extension, Json, extract::State};
+
+/// Get the trial balance for the authenticated business.
+///
+/// # Endpoint
+///
+/// `GET /api/v1/ledger/trial-balance`
+///
+/// # Response (200)
+///
+/// ```json
+/// {
+///   "generated_at": "2025-12-21T20:00:00Z",
+///   "is_balanced": true,
+///   "totals": [{ "currency": "USD", "debits_cents": 150000, "credits_cents": 150000 }],
+///   "lines": [ ... ]
+/// }
+/// ```
+///
+/// # Security
+///
+/// Only postings of transactions touching the business's accounts are included.
+pub async fn get_trial_balance(
+    State(pool): State<DbPool>,
+    Extension(auth): Extension<AuthContext>,
+) -> Result<Json<TrialBalanceResponse>, AppError> {
+    let report = ledger_service::trial_balance(&pool, auth.api_key_id).await?;
+
+    Ok(Json(report))
+}
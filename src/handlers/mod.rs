@@ -7,9 +7,18 @@
 /// Account management handlers
 pub mod accounts;
 
+/// Foreign-exchange quote handlers
+pub mod fx;
+
 /// Health check handler for monitoring
 pub mod health;
 
+/// Ledger reporting handlers
+pub mod ledger;
+
+/// Balance reconciliation handlers
+pub mod reconciliation;
+
 /// Transaction handlers for credit, debit, and transfer operations
 pub mod transactions;
 
@@ -0,0 +1,39 @@
+//! Balance reconciliation HTTP handlers.
+//!
+//! This module implements reconciliation-related API endpoints:
+//! - GET /api/v1/reconciliation - Compare stored balances with transaction history
+
+use crate::{
+    db::DbPool, error::AppError, middleware::auth::AuthContext,
+    models::reconciliation::ReconciliationReport, services::reconciliation_service,
+};
+use axum::{Extension, Json, extract::State};
+
+/// Reconcile the balances of the authenticated business's accounts.
+///
+/// # Endpoint
+///
+/// `GET /api/v1/reconciliation`
+///
+/// # Response (200)
+///
+/// ```json
+/// {
+///   "checked_at": "2025-12-21T20:00:00Z",
+///   "accounts_checked": 42,
+///   "is_consistent": true,
+///   "mismatches": []
+/// }
+/// ```
+///
+/// # Security
+///
+/// Only accounts belonging to the authenticated business are checked.
+pub async fn get_reconciliation(
+    State(pool): State<DbPool>,
+    Extension(auth): Extension<AuthContext>,
+) -> Result<Json<ReconciliationReport>, AppError> {
+    let report = reconciliation_service::reconcile(&pool, Some(auth.api_key_id)).await?;
+
+    Ok(Json(report))
+}
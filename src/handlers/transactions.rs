@@ -4,18 +4,29 @@
 //! - POST /api/v1/transactions/credit - Add money to account
 //! - POST /api/v1/transactions/debit - Remove money from account
 //! - POST /api/v1/transactions/transfer - Move money between accounts
+//! - POST /api/v1/transactions/:id/reverse - Reverse all or part of a transaction
+//! - POST /api/v1/transactions/authorize - Place a hold on an account
+//! - POST /api/v1/transactions/:id/capture - Capture (post) an authorization
+//! - POST /api/v1/transactions/:id/void - Release an authorization's hold
+//! - POST /api/v1/transactions/:id/status - Change a transaction's status
+//! - GET /api/v1/transactions/:id/status-history - List a transaction's status changes
 //! - GET /api/v1/transactions/:id - Get transaction details
+//! - GET /api/v1/accounts/:id/transactions - List an account's transactions
 
 use crate::{
     db::DbPool,
     error::AppError,
     middleware::auth::AuthContext,
-    models::transaction::{CreditRequest, DebitRequest, TransactionResponse, TransferRequest},
+    models::transaction::{
+        AuthorizeRequest, CaptureRequest, CreditRequest, DebitRequest, ReverseTransactionRequest,
+        TransactionListQuery, TransactionListResponse, TransactionResponse,
+        TransactionStatusChange, TransferRequest, UpdateTransactionStatusRequest,
+    },
     services::transaction_service,
 };
 use axum::{
     Extension, Json,
-    extract::{Path, State},
+    extract::{Path, Query, State},
 };
 use uuid::Uuid;
 
@@ -47,6 +58,7 @@ async fn verify_account_ownership(
 ///   "account_id": "550e8400-...",
 ///   "amount_cents": 100000,
 ///   "description": "Initial deposit",
+///   "metadata": { "order_id": "ord_1042" },
 ///   "idempotency_key": "deposit-001"
 /// }
 /// ```
@@ -59,6 +71,8 @@ async fn verify_account_ownership(
 ///   "transaction_type": "credit",
 ///   "to_account_id": "550e8400-...",
 ///   "amount_cents": 100000,
+///   "metadata": { "order_id": "ord_1042" },
+///   "idempotency_key": "deposit-001",
 ///   "status": "completed",
 ///   "created_at": "2025-12-21T16:00:00Z"
 /// }
@@ -77,6 +91,7 @@ pub async fn create_credit(
         account_id,
         request.amount_cents,
         request.description,
+        request.metadata,
         request.idempotency_key,
         auth.api_key_id,
     )
@@ -109,6 +124,7 @@ pub async fn create_debit(
         account_id,
         request.amount_cents,
         request.description,
+        request.metadata,
         request.idempotency_key,
         auth.api_key_id,
     )
@@ -129,6 +145,14 @@ pub async fn create_debit(
 /// - Both accounts must belong to authenticated business
 /// - Source must have sufficient balance
 /// - Accounts must be different
+///
+/// With `"pending": true` the amount is only held on the source account
+/// until the transfer is completed, failed or canceled via the status
+/// endpoint.
+///
+/// Accounts in different currencies need an `fx_quote_id` from
+/// `POST /api/v1/fx/quotes`; the destination is credited the quoted
+/// `destination_amount_cents`.
 pub async fn create_transfer(
     State(pool): State<DbPool>,
     Extension(auth): Extension<AuthContext>,
@@ -147,13 +171,94 @@ pub async fn create_transfer(
     }
 
     // Execute transfer
-    let transaction = transaction_service::execute_transfer(
+    let transaction =
+        transaction_service::execute_transfer(&pool, request, auth.api_key_id).await?;
+
+    Ok(Json(transaction.into()))
+}
+
+/// Reverse all or part of a transaction.
+///
+/// # Endpoint
+///
+/// `POST /api/v1/transactions/{id}/reverse`
+///
+/// # Request Body (optional)
+///
+/// ```json
+/// {
+///   "amount_cents": 10000,
+///   "description": "Refund for duplicate charge",
+///   "idempotency_key": "refund-2025-042"
+/// }
+/// ```
+///
+/// Without a body (or without `amount_cents`) the whole remaining amount is
+/// reversed.
+///
+/// # Validation
+///
+/// - Transaction must belong to authenticated business
+/// - Reversals of a transaction together never exceed its amount
+/// - Account money is taken back from must have sufficient balance
+pub async fn reverse_transaction(
+    State(pool): State<DbPool>,
+    Extension(auth): Extension<AuthContext>,
+    Path(transaction_id): Path<Uuid>,
+    request: Option<Json<ReverseTransactionRequest>>,
+) -> Result<Json<TransactionResponse>, AppError> {
+    let Json(request) = request.unwrap_or_default();
+
+    let transaction = transaction_service::execute_reversal(
+        &pool,
+        transaction_id,
+        request.amount_cents,
+        request.description,
+        request.idempotency_key,
+        auth.api_key_id,
+    )
+    .await?;
+
+    Ok(Json(transaction.into()))
+}
+
+/// Place a hold on an account (authorize).
+///
+/// # Endpoint
+///
+/// `POST /api/v1/transactions/authorize`
+///
+/// # Request Body
+///
+/// ```json
+/// {
+///   "account_id": "550e8400-...",
+///   "amount_cents": 15000,
+///   "description": "Order #1042",
+///   "idempotency_key": "order-1042-auth",
+///   "expires_in_seconds": 86400
+/// }
+/// ```
+///
+/// # Validation
+///
+/// - Account must have sufficient available balance
+/// - Account must belong to authenticated business
+pub async fn create_authorization(
+    State(pool): State<DbPool>,
+    Extension(auth): Extension<AuthContext>,
+    Json(request): Json<AuthorizeRequest>,
+) -> Result<Json<TransactionResponse>, AppError> {
+    // Verify account belongs to authenticated business
+    let account_id = verify_account_ownership(&pool, request.account_id, auth.api_key_id).await?;
+
+    let transaction = transaction_service::execute_authorization(
         &pool,
-        request.from_account_id,
-        request.to_account_id,
+        account_id,
         request.amount_cents,
         request.description,
         request.idempotency_key,
+        request.expires_in_seconds,
         auth.api_key_id,
     )
     .await?;
@@ -161,6 +266,120 @@ pub async fn create_transfer(
     Ok(Json(transaction.into()))
 }
 
+/// Capture an authorization (post the held funds).
+///
+/// # Endpoint
+///
+/// `POST /api/v1/transactions/{id}/capture`
+///
+/// # Request Body (optional)
+///
+/// ```json
+/// {
+///   "amount_cents": 12000,
+///   "description": "Order #1042 shipped",
+///   "idempotency_key": "order-1042-capture"
+/// }
+/// ```
+///
+/// Without a body (or without `amount_cents`) the full authorized amount is
+/// captured. Returns the new `capture` transaction.
+pub async fn capture_authorization(
+    State(pool): State<DbPool>,
+    Extension(auth): Extension<AuthContext>,
+    Path(authorization_id): Path<Uuid>,
+    request: Option<Json<CaptureRequest>>,
+) -> Result<Json<TransactionResponse>, AppError> {
+    let Json(request) = request.unwrap_or_default();
+
+    let transaction = transaction_service::execute_capture(
+        &pool,
+        authorization_id,
+        request.amount_cents,
+        request.description,
+        request.idempotency_key,
+        auth.api_key_id,
+    )
+    .await?;
+
+    Ok(Json(transaction.into()))
+}
+
+/// Void an authorization (release the hold).
+///
+/// # Endpoint
+///
+/// `POST /api/v1/transactions/{id}/void`
+///
+/// Returns the authorization with status `voided`. Voiding twice is safe.
+pub async fn void_authorization(
+    State(pool): State<DbPool>,
+    Extension(auth): Extension<AuthContext>,
+    Path(authorization_id): Path<Uuid>,
+) -> Result<Json<TransactionResponse>, AppError> {
+    let transaction =
+        transaction_service::void_authorization(&pool, authorization_id, auth.api_key_id).await?;
+
+    Ok(Json(transaction.into()))
+}
+
+/// Change the status of a transaction.
+///
+/// # Endpoint
+///
+/// `POST /api/v1/transactions/{id}/status`
+///
+/// # Request Body
+///
+/// ```json
+/// {
+///   "status": "completed",
+///   "reason": "Approved by operations"
+/// }
+/// ```
+///
+/// # Validation
+///
+/// - Transaction must belong to authenticated business
+/// - `status` must be `pending`, `processing`, `completed`, `failed` or `canceled`
+/// - The change must be allowed from the current status (409 otherwise)
+pub async fn update_transaction_status(
+    State(pool): State<DbPool>,
+    Extension(auth): Extension<AuthContext>,
+    Path(transaction_id): Path<Uuid>,
+    Json(request): Json<UpdateTransactionStatusRequest>,
+) -> Result<Json<TransactionResponse>, AppError> {
+    let transaction = transaction_service::update_transaction_status(
+        &pool,
+        transaction_id,
+        request.status,
+        request.reason,
+        auth.api_key_id,
+    )
+    .await?;
+
+    Ok(Json(transaction.into()))
+}
+
+/// List the status changes of a transaction, oldest first.
+///
+/// # Endpoint
+///
+/// `GET /api/v1/transactions/{id}/status-history`
+///
+/// The first entry (with `from_status: null`) is the status the
+/// transaction was created with.
+pub async fn get_transaction_status_history(
+    State(pool): State<DbPool>,
+    Extension(auth): Extension<AuthContext>,
+    Path(transaction_id): Path<Uuid>,
+) -> Result<Json<Vec<TransactionStatusChange>>, AppError> {
+    let history =
+        transaction_service::get_status_history(&pool, transaction_id, auth.api_key_id).await?;
+
+    Ok(Json(history))
+}
+
 /// Get transaction by ID.
 ///
 /// # Security
@@ -174,7 +393,7 @@ pub async fn get_transaction(
 ) -> Result<Json<TransactionResponse>, AppError> {
     let transaction = transaction_service::get_transaction_by_id(&pool, transaction_id)
         .await?
-        .ok_or(AppError::AccountNotFound)?;
+        .ok_or(AppError::TransactionNotFound)?;
 
     // Verify transaction involves at least one account owned by this business
     let has_access: bool = sqlx::query_scalar(
@@ -193,8 +412,59 @@ pub async fn get_transaction(
     .await?;
 
     if !has_access {
-        return Err(AppError::AccountNotFound);
+        return Err(AppError::TransactionNotFound);
     }
 
     Ok(Json(transaction.into()))
 }
+
+/// List the transactions of an account (paginated, newest first).
+///
+/// # Endpoint
+///
+/// `GET /api/v1/accounts/{id}/transactions`
+///
+/// # Query Parameters
+///
+/// - `limit` - Page size (1-100, default 25)
+/// - `starting_after` - Cursor: `next_cursor` from the previous page
+/// - `transaction_type` - `credit`, `debit`, `transfer`, `opening_balance`, `reversal`,
+///   `authorization` or `capture`
+/// - `status` - e.g. `pending`, `completed`, `reversed`
+/// - `direction` - `incoming` or `outgoing`
+/// - `created_after` / `created_before` - RFC 3339 timestamps
+/// - `min_amount_cents` / `max_amount_cents` - Inclusive amount range
+/// - `metadata_key` - Only transactions with this metadata key
+/// - `metadata_value` - With `metadata_key`, only transactions whose key has this value
+///
+/// # Response (200)
+///
+/// ```json
+/// {
+///   "data": [ { "id": "770e8400-...", "transaction_type": "credit", ... } ],
+///   "has_more": true,
+///   "next_cursor": "770e8400-..."
+/// }
+/// ```
+///
+/// # Security
+///
+/// Returns 404 if the account doesn't belong to the authenticated business.
+pub async fn list_account_transactions(
+    State(pool): State<DbPool>,
+    Extension(auth): Extension<AuthContext>,
+    Path(account_id): Path<Uuid>,
+    Query(query): Query<TransactionListQuery>,
+) -> Result<Json<TransactionListResponse>, AppError> {
+    // Verify account belongs to authenticated business
+    let account_id = verify_account_ownership(&pool, account_id, auth.api_key_id).await?;
+
+    let (transactions, has_more) =
+        transaction_service::list_account_transactions(&pool, account_id, &query).await?;
+
+    Ok(Json(TransactionListResponse::from_rows(
+        transactions,
+        has_more,
+        |t| t.id,
+    )))
+}
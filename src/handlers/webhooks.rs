@@ -1,11 +1,12 @@
 //! HTTP handlers for webhook endpoint management.
 //!
-//! This module provides API endpoints for businesses to register,
-//! list, and delete webhook endpoints that receive transaction notifications.
+//! This module provides API endpoints for businesses to register, list,
+//! enable/disable and delete webhook endpoints that receive transaction
+//! notifications, and to inspect and redeliver the events sent to them.
 
 use axum::{
     Extension, Json,
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
     response::IntoResponse,
 };
@@ -14,8 +15,14 @@ use uuid::Uuid;
 use crate::db::DbPool;
 use crate::error::AppError;
 use crate::middleware::auth::AuthContext;
-use crate::models::webhook::{WebhookEndpointRequest, WebhookEndpointResponse};
-use crate::services::webhook_service;
+use crate::models::webhook::{
+    RotateSecretRequest, UpdateWebhookEndpointRequest, WebhookEndpointRequest,
+    WebhookEndpointResponse, WebhookEventListQuery, WebhookEventListResponse, WebhookEventResponse,
+    WebhookTestResponse,
+};
+use crate::services::{
+    webhook_sender::WebhookSender, webhook_service, webhook_url_guard::WebhookUrlGuard,
+};
 
 /// Register a new webhook endpoint.
 ///
@@ -45,32 +52,40 @@ use crate::services::webhook_service;
 /// # Security
 ///
 /// - Requires valid API key authentication
-/// - HTTPS URLs required (HTTP localhost allowed for development)
+/// - HTTPS URLs required (HTTP allowed for allowlisted development hosts)
+/// - URLs resolving to private or reserved addresses are rejected
 /// - Secret is 64-character hex string for HMAC-SHA256
 pub async fn create_webhook(
     State(pool): State<DbPool>,
+    State(url_guard): State<WebhookUrlGuard>,
     Extension(auth): Extension<AuthContext>,
     Json(request): Json<WebhookEndpointRequest>,
 ) -> Result<impl IntoResponse, AppError> {
     let endpoint =
-        webhook_service::create_webhook_endpoint(&pool, auth.api_key_id, request).await?;
+        webhook_service::create_webhook_endpoint(&pool, &url_guard, auth.api_key_id, request)
+            .await?;
 
     Ok((StatusCode::CREATED, Json(endpoint)))
 }
 
-/// List all active webhook endpoints.
+/// List all webhook endpoints, including disabled ones.
 ///
 /// # Response
 ///
-/// Returns array of webhook endpoints (secrets NOT included).
+/// Returns array of webhook endpoints (secrets NOT included). Disabled
+/// endpoints have `is_active: false` with `disabled_at` and
+/// `disabled_reason`; deleted endpoints are not listed.
 ///
 /// ```json
 /// [
 ///   {
 ///     "id": "550e8400-e29b-41d4-a716-446655440000",
 ///     "url": "https://example.com/webhook",
-///     "is_active": true,
-///     "created_at": "2025-01-15T10:30:00Z"
+///     "is_active": false,
+///     "created_at": "2025-01-15T10:30:00Z",
+///     "consecutive_failures": 50,
+///     "disabled_at": "2025-01-17T08:00:00Z",
+///     "disabled_reason": "Disabled after 50 consecutive failed delivery attempts since 2025-01-16T07:12:40+00:00"
 ///   }
 /// ]
 /// ```
@@ -114,3 +129,205 @@ pub async fn delete_webhook(
 
     Ok(StatusCode::NO_CONTENT)
 }
+
+/// Update a webhook endpoint (enable/disable, ordered delivery).
+///
+/// # Endpoint
+///
+/// `PATCH /api/v1/webhooks/{id}`
+///
+/// # Request Body
+///
+/// Both fields are optional, but at least one is required.
+///
+/// ```json
+/// {
+///   "is_active": true,
+///   "ordered_delivery": true
+/// }
+/// ```
+///
+/// # Response (200)
+///
+/// The updated endpoint. Re-enabling an automatically disabled endpoint
+/// resets its failure streak and resumes deliveries.
+///
+/// # Security
+///
+/// Returns 404 if the webhook doesn't belong to the authenticated business
+/// or has been deleted.
+pub async fn update_webhook(
+    State(pool): State<DbPool>,
+    Extension(auth): Extension<AuthContext>,
+    Path(webhook_id): Path<Uuid>,
+    Json(request): Json<UpdateWebhookEndpointRequest>,
+) -> Result<Json<WebhookEndpointResponse>, AppError> {
+    let endpoint =
+        webhook_service::update_webhook_endpoint(&pool, auth.api_key_id, webhook_id, request)
+            .await?;
+
+    Ok(Json(endpoint))
+}
+
+/// Rotate the secret of a webhook endpoint.
+///
+/// # Endpoint
+///
+/// `POST /api/v1/webhooks/{id}/rotate-secret`
+///
+/// # Request Body (optional)
+///
+/// ```json
+/// {
+///   "grace_period_seconds": 86400
+/// }
+/// ```
+///
+/// # Response (200)
+///
+/// The endpoint with the new `secret` (only shown once) and
+/// `previous_secret_expires_at`, until which webhooks are also signed with
+/// the old secret.
+///
+/// # Security
+///
+/// Returns 404 if the webhook doesn't belong to the authenticated business
+/// or has been deleted.
+pub async fn rotate_webhook_secret(
+    State(pool): State<DbPool>,
+    Extension(auth): Extension<AuthContext>,
+    Path(webhook_id): Path<Uuid>,
+    request: Option<Json<RotateSecretRequest>>,
+) -> Result<Json<WebhookEndpointResponse>, AppError> {
+    let request = request.map(|Json(request)| request).unwrap_or_default();
+
+    let endpoint =
+        webhook_service::rotate_webhook_secret(&pool, auth.api_key_id, webhook_id, request).await?;
+
+    Ok(Json(endpoint))
+}
+
+/// Send a test webhook and return the receiver's response.
+///
+/// # Endpoint
+///
+/// `POST /api/v1/webhooks/{id}/test`
+///
+/// # Response (200)
+///
+/// Returned even if the receiver fails; check `success`.
+///
+/// ```json
+/// {
+///   "event_id": "dd0e8400-e29b-41d4-a716-446655440008",
+///   "success": true,
+///   "response_status": 200,
+///   "response_body": "ok",
+///   "latency_ms": 84
+/// }
+/// ```
+///
+/// # Security
+///
+/// Returns 404 if the webhook doesn't belong to the authenticated business
+/// or has been deleted.
+pub async fn test_webhook(
+    State(pool): State<DbPool>,
+    State(sender): State<WebhookSender>,
+    Extension(auth): Extension<AuthContext>,
+    Path(webhook_id): Path<Uuid>,
+) -> Result<Json<WebhookTestResponse>, AppError> {
+    let result =
+        webhook_service::send_test_webhook(&pool, &sender, auth.api_key_id, webhook_id).await?;
+
+    Ok(Json(result))
+}
+
+/// List the delivery log of a webhook endpoint (paginated, newest first).
+///
+/// # Endpoint
+///
+/// `GET /api/v1/webhooks/{id}/events`
+///
+/// # Query Parameters
+///
+/// - `limit` - Page size (1-100, default 25)
+/// - `starting_after` - Cursor: `next_cursor` from the previous page
+/// - `status` - `pending`, `delivered` or `dead`
+///
+/// # Response (200)
+///
+/// ```json
+/// {
+///   "data": [ { "id": "bb0e8400-...", "status": "dead", "attempt_count": 12, ... } ],
+///   "has_more": false,
+///   "next_cursor": null
+/// }
+/// ```
+///
+/// # Security
+///
+/// Returns 404 if the webhook doesn't belong to the authenticated business.
+pub async fn list_webhook_events(
+    State(pool): State<DbPool>,
+    Extension(auth): Extension<AuthContext>,
+    Path(webhook_id): Path<Uuid>,
+    Query(query): Query<WebhookEventListQuery>,
+) -> Result<Json<WebhookEventListResponse>, AppError> {
+    let (events, has_more) =
+        webhook_service::list_webhook_events(&pool, auth.api_key_id, webhook_id, &query).await?;
+
+    Ok(Json(WebhookEventListResponse::from_rows(
+        events,
+        has_more,
+        |e| e.id,
+    )))
+}
+
+/// Get a single webhook event, including the last delivery attempt.
+///
+/// # Endpoint
+///
+/// `GET /api/v1/webhook-events/{event_id}`
+///
+/// # Security
+///
+/// Returns 404 if the event doesn't belong to the authenticated business.
+pub async fn get_webhook_event(
+    State(pool): State<DbPool>,
+    Extension(auth): Extension<AuthContext>,
+    Path(event_id): Path<Uuid>,
+) -> Result<Json<WebhookEventResponse>, AppError> {
+    let event = webhook_service::get_webhook_event(&pool, auth.api_key_id, event_id).await?;
+
+    Ok(Json(event.into()))
+}
+
+/// Redeliver a webhook event.
+///
+/// # Endpoint
+///
+/// `POST /api/v1/webhook-events/{event_id}/redeliver`
+///
+/// # Response
+///
+/// Returns 202 Accepted with the event, now `pending` and due immediately.
+/// The stored payload is resent by the dispatcher with a fresh signature
+/// and, on failure, retried like a new event. Returns 409 if the event is
+/// still `pending`.
+///
+/// # Security
+///
+/// Returns 404 if the event doesn't belong to the authenticated business.
+pub async fn redeliver_webhook_event(
+    State(pool): State<DbPool>,
+    Extension(auth): Extension<AuthContext>,
+    Path(event_id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let event = webhook_service::redeliver_webhook_event(&pool, auth.api_key_id, event_id).await?;
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(WebhookEventResponse::from(event)),
+    ))
+}
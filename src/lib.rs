@@ -0,0 +1,8 @@
+//! Library target of the transaction service.
+//!
+//! Exposes the parts that API consumers can reuse in their own Rust code,
+//! such as webhook signature verification. The server itself lives in the
+//! binary target (`main.rs`).
+
+/// Timestamped webhook signatures (signing and verification)
+pub mod webhook_signature;
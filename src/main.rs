@@ -1,6 +1,6 @@
 //! Transaction Service - Main Application Entry Point
 //!
-//! This is a REST API server for managing financial accounts and transactions. It provides secure, authenticated endpoints for creating accounts and executing transactions (credit, debit, transfer).
+//! This is a REST API server for managing financial accounts and transactions. It provides secure, authenticated endpoints for creating accounts and executing transactions (credit, debit, transfer, reversal, authorize/capture/void) with a tracked status lifecycle.
 //!
 //! # Architecture
 //!
@@ -14,8 +14,11 @@
 //! 1. Load configuration from environment variables
 //! 2. Create database connection pool
 //! 3. Run database migrations
-//! 4. Build HTTP router with routes and middleware
-//! 5. Start server on configured port
+//! 4. Load exchange rates from `FX_RATES_FILE`, if set
+//! 5. Start background tasks (balance reconciliation, authorization expiry,
+//!    FX rates reload, webhook dispatcher)
+//! 6. Build HTTP router with routes and middleware
+//! 7. Start server on configured port
 
 mod config;
 mod db;
@@ -24,12 +27,13 @@ mod handlers;
 mod middleware;
 mod models;
 mod services;
+mod state;
 
 use tracing_subscriber::EnvFilter;
 
 use axum::{
     Router, middleware as axum_middleware,
-    routing::{delete, get, post},
+    routing::{delete, get, patch, post},
 };
 use tower_http::trace::TraceLayer;
 
@@ -52,6 +56,75 @@ async fn main() -> anyhow::Result<()> {
     db::run_migrations(&pool).await?;
     tracing::info!("Database migrations complete");
 
+    // Start periodic balance reconciliation
+    services::reconciliation_service::spawn_reconciliation_task(
+        pool.clone(),
+        std::time::Duration::from_secs(config.reconciliation_interval_secs),
+    );
+    tracing::info!(
+        "Balance reconciliation scheduled every {}s",
+        config.reconciliation_interval_secs
+    );
+
+    // Start periodic release of expired authorization holds
+    services::transaction_service::spawn_authorization_expiry_task(
+        pool.clone(),
+        std::time::Duration::from_secs(config.authorization_expiry_interval_secs),
+    );
+    tracing::info!(
+        "Authorization expiry scheduled every {}s",
+        config.authorization_expiry_interval_secs
+    );
+
+    // Load exchange rates and keep them in sync with the rates file
+    if let Some(path) = &config.fx_rates_file {
+        let path = std::path::PathBuf::from(path);
+        let loaded = services::fx_service::load_rates_file(&pool, &path).await?;
+        tracing::info!("Loaded {} FX rates from {}", loaded, path.display());
+
+        services::fx_service::spawn_rates_reload_task(
+            pool.clone(),
+            path,
+            std::time::Duration::from_secs(config.fx_rates_reload_interval_secs),
+        );
+        tracing::info!(
+            "FX rates reload scheduled every {}s",
+            config.fx_rates_reload_interval_secs
+        );
+    }
+
+    // SSRF checks for webhook URLs (shared by registration and delivery)
+    let webhook_url_guard =
+        services::webhook_url_guard::WebhookUrlGuard::new(config.webhook_allowed_hosts.clone());
+
+    // Shared webhook HTTP client (used by the dispatcher and test pings)
+    let webhook_sender = services::webhook_sender::WebhookSender::new(
+        webhook_url_guard.clone(),
+        services::webhook_sender::WebhookSenderSettings {
+            request_timeout: std::time::Duration::from_secs(config.webhook_timeout_secs),
+            connect_timeout: std::time::Duration::from_secs(config.webhook_connect_timeout_secs),
+            max_concurrency: config.webhook_max_concurrency,
+            endpoint_max_concurrency: config.webhook_endpoint_max_concurrency,
+            endpoint_rate_per_sec: config.webhook_endpoint_rate_per_sec,
+            endpoint_burst: config.webhook_endpoint_burst,
+        },
+    )?;
+
+    // Start webhook outbox dispatcher
+    services::webhook_service::spawn_webhook_dispatcher(
+        pool.clone(),
+        services::webhook_service::DispatcherSettings {
+            poll_interval: std::time::Duration::from_secs(config.webhook_dispatch_interval_secs),
+            max_attempts: config.webhook_max_attempts,
+            retry_base: std::time::Duration::from_secs(config.webhook_retry_base_secs),
+            retry_max: std::time::Duration::from_secs(config.webhook_retry_max_secs),
+            disable_after_failures: config.webhook_disable_after_failures,
+            disable_after: std::time::Duration::from_secs(config.webhook_disable_after_secs),
+            sender: webhook_sender.clone(),
+        },
+    );
+    tracing::info!("Webhook dispatcher started");
+
     // Create authenticated routes (API endpoints)
     let authenticated_routes = Router::new()
         // Account management routes
@@ -61,6 +134,14 @@ async fn main() -> anyhow::Result<()> {
             "/api/v1/accounts/{id}",
             get(handlers::accounts::get_account),
         )
+        .route(
+            "/api/v1/accounts/{id}",
+            patch(handlers::accounts::update_account),
+        )
+        .route(
+            "/api/v1/accounts/{id}/transactions",
+            get(handlers::transactions::list_account_transactions),
+        )
         // Transaction routes
         .route(
             "/api/v1/transactions/credit",
@@ -74,10 +155,46 @@ async fn main() -> anyhow::Result<()> {
             "/api/v1/transactions/transfer",
             post(handlers::transactions::create_transfer),
         )
+        .route(
+            "/api/v1/transactions/{id}/reverse",
+            post(handlers::transactions::reverse_transaction),
+        )
+        .route(
+            "/api/v1/transactions/authorize",
+            post(handlers::transactions::create_authorization),
+        )
+        .route(
+            "/api/v1/transactions/{id}/capture",
+            post(handlers::transactions::capture_authorization),
+        )
+        .route(
+            "/api/v1/transactions/{id}/void",
+            post(handlers::transactions::void_authorization),
+        )
+        .route(
+            "/api/v1/transactions/{id}/status",
+            post(handlers::transactions::update_transaction_status),
+        )
+        .route(
+            "/api/v1/transactions/{id}/status-history",
+            get(handlers::transactions::get_transaction_status_history),
+        )
         .route(
             "/api/v1/transactions/{id}",
             get(handlers::transactions::get_transaction),
         )
+        // FX routes
+        .route("/api/v1/fx/quotes", post(handlers::fx::create_fx_quote))
+        // Ledger routes
+        .route(
+            "/api/v1/ledger/trial-balance",
+            get(handlers::ledger::get_trial_balance),
+        )
+        // Reconciliation routes
+        .route(
+            "/api/v1/reconciliation",
+            get(handlers::reconciliation::get_reconciliation),
+        )
         // Webhook routes
         .route("/api/v1/webhooks", post(handlers::webhooks::create_webhook))
         .route("/api/v1/webhooks", get(handlers::webhooks::list_webhooks))
@@ -85,6 +202,30 @@ async fn main() -> anyhow::Result<()> {
             "/api/v1/webhooks/{id}",
             delete(handlers::webhooks::delete_webhook),
         )
+        .route(
+            "/api/v1/webhooks/{id}",
+            patch(handlers::webhooks::update_webhook),
+        )
+        .route(
+            "/api/v1/webhooks/{id}/rotate-secret",
+            post(handlers::webhooks::rotate_webhook_secret),
+        )
+        .route(
+            "/api/v1/webhooks/{id}/test",
+            post(handlers::webhooks::test_webhook),
+        )
+        .route(
+            "/api/v1/webhooks/{id}/events",
+            get(handlers::webhooks::list_webhook_events),
+        )
+        .route(
+            "/api/v1/webhook-events/{id}",
+            get(handlers::webhooks::get_webhook_event),
+        )
+        .route(
+            "/api/v1/webhook-events/{id}/redeliver",
+            post(handlers::webhooks::redeliver_webhook_event),
+        )
         // Apply authentication middleware to all routes in this group
         .route_layer(axum_middleware::from_fn_with_state(
             pool.clone(),
@@ -99,8 +240,12 @@ async fn main() -> anyhow::Result<()> {
         .merge(authenticated_routes)
         // Add distributed tracing middleware for observability
         .layer(TraceLayer::new_for_http())
-        // Share database pool with all handlers via State extraction
-        .with_state(pool);
+        // Share database pool and webhook services with all handlers via State extraction
+        .with_state(state::AppState {
+            pool,
+            webhook_url_guard,
+            webhook_sender,
+        });
 
     // Bind to network address and start server
     let addr = format!("0.0.0.0:{}", config.server_port);
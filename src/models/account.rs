@@ -2,13 +2,119 @@
 //!
 //! This module defines:
 //! - `Account`: Database entity representing an account
+//! - `AccountStatus`: Lifecycle states (active, frozen, closed)
 //! - `CreateAccountRequest`: Request body for creating accounts
+//! - `UpdateAccountRequest`: Request body for updating accounts
+//! - `AccountListQuery` / `AccountListResponse`: Paginated, filtered and sorted account list
 //! - `AccountResponse`: Response body returned to clients
 
+use std::{fmt, str::FromStr};
+
 use chrono::{DateTime, Utc};
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
+use sqlx::{
+    Postgres,
+    encode::IsNull,
+    error::BoxDynError,
+    postgres::{PgArgumentBuffer, PgTypeInfo, PgValueRef},
+    types::Json,
+};
 use uuid::Uuid;
 
+use super::{
+    currency::Currency,
+    metadata::Metadata,
+    pagination::{ListResponse, SortOrder},
+};
+
+/// Lifecycle status of an account.
+///
+/// Stored as lowercase text in `accounts.status`.
+///
+/// | Status   | Receive money | Send money |
+/// | -------- | ------------- | ---------- |
+/// | `active` | Yes           | Yes        |
+/// | `frozen` | Yes           | No         |
+/// | `closed` | No            | No         |
+///
+/// `active` and `frozen` can change into each other and into `closed`;
+/// `closed` is final and requires a zero balance.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AccountStatus {
+    /// Normal operation
+    Active,
+
+    /// Under investigation: money can come in but not go out
+    Frozen,
+
+    /// Permanently closed; no money moves in or out
+    Closed,
+}
+
+impl AccountStatus {
+    /// String form stored in `accounts.status`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AccountStatus::Active => "active",
+            AccountStatus::Frozen => "frozen",
+            AccountStatus::Closed => "closed",
+        }
+    }
+
+    /// Whether money may leave the account.
+    pub fn can_send(self) -> bool {
+        self == AccountStatus::Active
+    }
+
+    /// Whether money may enter the account.
+    pub fn can_receive(self) -> bool {
+        self != AccountStatus::Closed
+    }
+}
+
+impl fmt::Display for AccountStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for AccountStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "active" => Ok(AccountStatus::Active),
+            "frozen" => Ok(AccountStatus::Frozen),
+            "closed" => Ok(AccountStatus::Closed),
+            other => Err(format!("unknown account status '{}'", other)),
+        }
+    }
+}
+
+/// Stored as text, so it binds and decodes like a `&str`.
+impl sqlx::Type<Postgres> for AccountStatus {
+    fn type_info() -> PgTypeInfo {
+        <&str as sqlx::Type<Postgres>>::type_info()
+    }
+
+    fn compatible(ty: &PgTypeInfo) -> bool {
+        <&str as sqlx::Type<Postgres>>::compatible(ty)
+    }
+}
+
+impl sqlx::Encode<'_, Postgres> for AccountStatus {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, BoxDynError> {
+        <&str as sqlx::Encode<Postgres>>::encode(self.as_str(), buf)
+    }
+}
+
+impl<'r> sqlx::Decode<'r, Postgres> for AccountStatus {
+    fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+        Ok(<&str as sqlx::Decode<Postgres>>::decode(value)?.parse()?)
+    }
+}
+
 /// Represents an account record from the database.
 ///
 /// # Database Table
@@ -16,14 +122,17 @@ use uuid::Uuid;
 /// Maps to the `accounts` table. Each account:
 /// - Belongs to one business (via `api_key_id`)
 /// - Has a balance stored in cents (to avoid floating-point errors)
+/// - Has part of that balance held by open authorizations
 ///
 /// # Balance Storage
 ///
 /// Balances are stored as `i64` cents to avoid floating-point precision issues.
+/// "Cents" means the minor unit of the account's currency (see `Currency`).
 ///
 /// For example:
 /// - $10.50 is stored as 1050 cents
 /// - $100.00 is stored as 10000 cents
+/// - ¥1050 is stored as 1050 (JPY has no minor unit)
 #[derive(Debug, Clone, sqlx::FromRow, Serialize)]
 pub struct Account {
     /// Unique identifier for this account
@@ -43,12 +152,29 @@ pub struct Account {
     /// Using i64 allows balances up to ~92 quadrillion dollars.
     pub balance_cents: i64,
 
+    /// Part of the balance reserved by open authorizations, in cents
+    ///
+    /// Never exceeds `balance_cents` (enforced by database CHECK constraint).
+    pub held_cents: i64,
+
     /// Currency code (ISO 4217, 3 letters)
     ///
-    /// Examples: "USD", "EUR", "GBP"
-    /// Currently defaults to "USD" but stored for future multi-currency support.
+    /// Examples: "USD", "EUR", "GBP". Every transaction of the account is
+    /// in this currency.
     pub currency: String,
 
+    /// Lifecycle status (see `AccountStatus`)
+    pub status: AccountStatus,
+
+    /// Timestamp of the last status change (`None` if never changed)
+    pub status_changed_at: Option<DateTime<Utc>>,
+
+    /// Client-defined string key/value pairs
+    pub metadata: Json<Metadata>,
+
+    /// Client's own identifier for the account, unique per business
+    pub external_reference: Option<String>,
+
     /// Timestamp when account was created
     pub created_at: DateTime<Utc>,
 
@@ -59,6 +185,13 @@ pub struct Account {
     pub updated_at: DateTime<Utc>,
 }
 
+impl Account {
+    /// Balance that can be spent: posted balance minus open holds.
+    pub fn available_balance_cents(&self) -> i64 {
+        self.balance_cents - self.held_cents
+    }
+}
+
 /// Request body for creating a new account.
 ///
 /// # JSON Example
@@ -67,27 +200,42 @@ pub struct Account {
 /// {
 ///   "account_name": "My Savings Account",
 ///   "currency": "USD",
-///   "initial_balance_cents": 10000
+///   "initial_balance_cents": 10000,
+///   "external_reference": "cus_123",
+///   "metadata": { "segment": "enterprise" },
+///   "idempotency_key": "open-cus_123-savings"
 /// }
 /// ```
 ///
 /// # Validation
 ///
 /// - `account_name`: Required, any non-empty string
-/// - `currency`: Optional, defaults to "USD"
+/// - `currency`: Optional ISO 4217 code, defaults to "USD"
 /// - `initial_balance_cents`: Optional, defaults to 0
+/// - `external_reference`: Optional, 1 to 255 characters, unique per business
+/// - `metadata`: Optional, see `models::metadata` for the limits
 #[derive(Debug, Deserialize)]
 pub struct CreateAccountRequest {
     /// Name for the new account
     pub account_name: String,
 
-    /// Currency code (defaults to "USD" if not provided)
+    /// ISO 4217 currency code (defaults to "USD" if not provided)
     #[serde(default = "default_currency")]
     pub currency: String,
 
     /// Initial balance in cents (defaults to 0 if not provided)
     #[serde(default)]
     pub initial_balance_cents: i64,
+
+    /// Client's own identifier for the account
+    pub external_reference: Option<String>,
+
+    /// Client-defined string key/value pairs (defaults to empty)
+    #[serde(default)]
+    pub metadata: Metadata,
+
+    /// Optional idempotency key to prevent duplicate accounts on retries
+    pub idempotency_key: Option<String>,
 }
 
 /// Default currency value when not specified in request.
@@ -95,6 +243,141 @@ fn default_currency() -> String {
     "USD".to_string()
 }
 
+/// Request body for updating an account.
+///
+/// # JSON Example
+///
+/// ```json
+/// {
+///   "account_name": "Payouts",
+///   "external_reference": "cus_123",
+///   "metadata": { "segment": "enterprise", "legacy_id": null }
+/// }
+/// ```
+///
+/// Fields left out are unchanged; at least one is required.
+/// `metadata` is merged into the stored metadata (`null` removes a key) and
+/// `"external_reference": null` removes the reference.
+#[derive(Debug, Deserialize)]
+pub struct UpdateAccountRequest {
+    /// New account name
+    pub account_name: Option<String>,
+
+    /// New lifecycle status
+    pub status: Option<AccountStatus>,
+
+    /// New external reference (`Some(None)` removes it)
+    #[serde(default, deserialize_with = "deserialize_present")]
+    pub external_reference: Option<Option<String>>,
+
+    /// Metadata keys to set or (with `null`) remove
+    pub metadata: Option<Metadata>,
+}
+
+/// Deserialize a field that is present (even as `null`) into `Some`, so
+/// `Option<Option<T>>` tells "left out" apart from "set to null".
+fn deserialize_present<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    T::deserialize(deserializer).map(Some)
+}
+
+/// Column a list of accounts is sorted by (`sort` query parameter).
+///
+/// Ties are broken by account ID, so every sort is a total order that
+/// cursors can resume from.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AccountSort {
+    #[default]
+    CreatedAt,
+    AccountName,
+    BalanceCents,
+}
+
+impl AccountSort {
+    /// Column of the `accounts` table to order by.
+    pub fn column(self) -> &'static str {
+        match self {
+            AccountSort::CreatedAt => "created_at",
+            AccountSort::AccountName => "account_name",
+            AccountSort::BalanceCents => "balance_cents",
+        }
+    }
+}
+
+/// Query parameters for listing accounts.
+///
+/// All filters are optional and combined with AND.
+///
+/// # Example
+///
+/// ```text
+/// GET /api/v1/accounts?currency=EUR&name_prefix=Payouts&sort=account_name&order=asc&limit=50
+/// ```
+///
+/// # Pagination
+///
+/// Results are ordered by `(sort, id)`, newest first by default. To fetch
+/// the next page, pass the `next_cursor` of the previous response as
+/// `starting_after` with the same filters and sort.
+///
+/// # Validation
+///
+/// - `limit`: 1 to 100, defaults to 25
+/// - `currency` must be an ISO 4217 code
+/// - `min_balance_cents` must not exceed `max_balance_cents`
+/// - `created_after` must be earlier than `created_before`
+/// - `metadata_value` requires `metadata_key`
+#[derive(Debug, Deserialize)]
+pub struct AccountListQuery {
+    /// Maximum number of accounts to return
+    pub limit: Option<i64>,
+
+    /// Return accounts after this account ID in the list's order (cursor)
+    pub starting_after: Option<Uuid>,
+
+    /// Column to sort by, defaults to `created_at`
+    #[serde(default)]
+    pub sort: AccountSort,
+
+    /// Sort direction, defaults to `desc`
+    #[serde(default)]
+    pub order: SortOrder,
+
+    /// Only accounts in this currency
+    pub currency: Option<String>,
+
+    /// Only accounts whose name starts with this text (case-sensitive)
+    pub name_prefix: Option<String>,
+
+    /// Only accounts with a balance of at least this amount
+    pub min_balance_cents: Option<i64>,
+
+    /// Only accounts with a balance of at most this amount
+    pub max_balance_cents: Option<i64>,
+
+    /// Only accounts created at or after this time
+    pub created_after: Option<DateTime<Utc>>,
+
+    /// Only accounts created before this time
+    pub created_before: Option<DateTime<Utc>>,
+
+    /// Only the account with this external reference
+    pub external_reference: Option<String>,
+
+    /// Only accounts with this metadata key
+    pub metadata_key: Option<String>,
+
+    /// Only accounts whose `metadata_key` has this value (requires `metadata_key`)
+    pub metadata_value: Option<String>,
+}
+
+/// Paginated list of accounts.
+pub type AccountListResponse = ListResponse<AccountResponse>;
+
 /// Response body for account endpoints.
 ///
 /// This struct is returned to API clients.
@@ -106,7 +389,12 @@ fn default_currency() -> String {
 ///   "id": "550e8400-e29b-41d4-a716-446655440000",
 ///   "account_name": "My Account",
 ///   "balance_cents": 100000,
+///   "available_balance_cents": 75000,
 ///   "currency": "USD",
+///   "currency_exponent": 2,
+///   "status": "active",
+///   "external_reference": "cus_123",
+///   "metadata": { "segment": "enterprise" },
 ///   "created_at": "2025-12-20T10:00:00Z",
 ///   "updated_at": "2025-12-20T10:00:00Z"
 /// }
@@ -119,12 +407,35 @@ pub struct AccountResponse {
     /// Account name
     pub account_name: String,
 
-    /// Current balance in cents
+    /// Posted balance in cents
     pub balance_cents: i64,
 
+    /// Posted balance minus open authorization holds, in cents
+    pub available_balance_cents: i64,
+
     /// Currency code
     pub currency: String,
 
+    /// Decimal places of the currency's minor unit (2 for USD, 0 for JPY)
+    ///
+    /// Omitted for accounts created with a code that isn't ISO 4217.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub currency_exponent: Option<u32>,
+
+    /// Lifecycle status
+    pub status: AccountStatus,
+
+    /// Timestamp of the last status change
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status_changed_at: Option<DateTime<Utc>>,
+
+    /// Client's own identifier for the account
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub external_reference: Option<String>,
+
+    /// Client-defined string key/value pairs
+    pub metadata: Metadata,
+
     /// Creation timestamp
     pub created_at: DateTime<Utc>,
 
@@ -134,14 +445,21 @@ pub struct AccountResponse {
 
 /// Convert database Account to API AccountResponse.
 ///
-/// This transformation - Removes the internal `api_key_id` field
+/// This transformation - Removes the internal `api_key_id` field and
+/// replaces `held_cents` with the available balance
 impl From<Account> for AccountResponse {
     fn from(account: Account) -> Self {
         Self {
+            available_balance_cents: account.available_balance_cents(),
+            currency_exponent: Currency::from_code(&account.currency).map(|c| c.exponent),
             id: account.id,
             account_name: account.account_name,
             balance_cents: account.balance_cents,
             currency: account.currency,
+            status: account.status,
+            status_changed_at: account.status_changed_at,
+            external_reference: account.external_reference,
+            metadata: account.metadata.0,
             created_at: account.created_at,
             updated_at: account.updated_at,
         }
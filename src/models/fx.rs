@@ -0,0 +1,344 @@
+//! Foreign-exchange data models and API request/response types.
+//!
+//! This module defines:
+//! - `FxRate`: Fixed-point exchange rate (exact decimal, no floats)
+//! - `FxRatesFile`: Format of the rates file loaded at startup
+//! - `FxQuote`: Database entity for a rate locked for a short time
+//! - `CreateFxQuoteRequest` / `FxQuoteResponse`: Quote endpoint bodies
+//!
+//! # Rounding
+//!
+//! Converting an amount multiplies it by the rate exactly (integer
+//! arithmetic) and rounds once, to the destination currency's minor unit,
+//! half to even (banker's rounding): 0.5 cents rounds to 0, 1.5 and 2.5
+//! cents round to 2.
+
+use std::{fmt, str::FromStr};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use sqlx::{
+    Postgres,
+    encode::IsNull,
+    error::BoxDynError,
+    postgres::{PgArgumentBuffer, PgTypeInfo, PgValueRef},
+};
+use uuid::Uuid;
+
+use super::currency::Currency;
+
+/// Decimal places of an `FxRate`.
+pub const RATE_DECIMALS: u32 = 12;
+
+/// Exchange rate: destination currency units per one source currency unit
+/// (major units, e.g. 0.92 EUR per USD).
+///
+/// Held as an integer scaled by 10^12 and stored as BIGINT (`*_rate_e12`
+/// columns). Serialized as a decimal string such as `"0.92"`, so no
+/// precision is lost to floating point.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FxRate(i64);
+
+impl FxRate {
+    /// Convert an amount in minor units of `from` to minor units of `to`.
+    ///
+    /// Rounds half to even at the destination minor unit.
+    ///
+    /// # Returns
+    ///
+    /// `None` if the result does not fit in an `i64`.
+    pub fn convert(self, amount_cents: i64, from: Currency, to: Currency) -> Option<i64> {
+        // amount * rate / 10^12, rescaled from source to destination minor units
+        let numerator = i128::from(amount_cents)
+            .checked_mul(i128::from(self.0))?
+            .checked_mul(10i128.pow(to.exponent))?;
+        let denominator = 10i128.pow(RATE_DECIMALS + from.exponent);
+
+        let quotient = numerator / denominator;
+        let twice_remainder = (numerator % denominator) * 2;
+
+        let rounded = if twice_remainder > denominator
+            || (twice_remainder == denominator && quotient % 2 == 1)
+        {
+            quotient + 1
+        } else {
+            quotient
+        };
+
+        i64::try_from(rounded).ok()
+    }
+}
+
+impl fmt::Display for FxRate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let scale = 10i64.pow(RATE_DECIMALS);
+        let whole = self.0 / scale;
+        let fraction = self.0 % scale;
+
+        if fraction == 0 {
+            return write!(f, "{}", whole);
+        }
+
+        let digits = format!("{:0width$}", fraction, width = RATE_DECIMALS as usize);
+        write!(f, "{}.{}", whole, digits.trim_end_matches('0'))
+    }
+}
+
+/// Parses a positive decimal with at most 12 decimal places (e.g. "0.9213").
+impl FromStr for FxRate {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid exchange rate '{}'", s);
+
+        let (whole, fraction) = s.trim().split_once('.').unwrap_or((s.trim(), ""));
+        if whole.is_empty()
+            || !whole.bytes().all(|b| b.is_ascii_digit())
+            || !fraction.bytes().all(|b| b.is_ascii_digit())
+        {
+            return Err(invalid());
+        }
+        if fraction.len() > RATE_DECIMALS as usize {
+            return Err(format!(
+                "exchange rate '{}' has more than {} decimal places",
+                s, RATE_DECIMALS
+            ));
+        }
+
+        let whole: i64 = whole.parse().map_err(|_| invalid())?;
+        let fraction: i64 = format!("{:0<width$}", fraction, width = RATE_DECIMALS as usize)
+            .parse()
+            .map_err(|_| invalid())?;
+
+        let scaled = whole
+            .checked_mul(10i64.pow(RATE_DECIMALS))
+            .and_then(|scaled| scaled.checked_add(fraction))
+            .ok_or_else(invalid)?;
+
+        if scaled == 0 {
+            return Err(format!("exchange rate '{}' must be positive", s));
+        }
+
+        Ok(FxRate(scaled))
+    }
+}
+
+impl Serialize for FxRate {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for FxRate {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+/// Stored as BIGINT (the rate scaled by 10^12).
+impl sqlx::Type<Postgres> for FxRate {
+    fn type_info() -> PgTypeInfo {
+        <i64 as sqlx::Type<Postgres>>::type_info()
+    }
+}
+
+impl sqlx::Encode<'_, Postgres> for FxRate {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, BoxDynError> {
+        <i64 as sqlx::Encode<Postgres>>::encode(self.0, buf)
+    }
+}
+
+impl<'r> sqlx::Decode<'r, Postgres> for FxRate {
+    fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+        match <i64 as sqlx::Decode<Postgres>>::decode(value)? {
+            scaled if scaled > 0 => Ok(FxRate(scaled)),
+            scaled => Err(format!("non-positive exchange rate {}", scaled).into()),
+        }
+    }
+}
+
+/// Contents of the file named by `FX_RATES_FILE`.
+///
+/// # JSON Example
+///
+/// ```json
+/// {
+///   "rates": [
+///     { "source_currency": "USD", "destination_currency": "EUR", "rate": "0.92" },
+///     { "source_currency": "EUR", "destination_currency": "USD", "rate": "1.08" }
+///   ]
+/// }
+/// ```
+///
+/// Each direction is listed separately; inverse rates are never derived
+/// (buy and sell rates usually differ).
+#[derive(Debug, Deserialize)]
+pub struct FxRatesFile {
+    pub rates: Vec<FxRateEntry>,
+}
+
+/// One currency pair of the rates file.
+#[derive(Debug, Deserialize)]
+pub struct FxRateEntry {
+    pub source_currency: String,
+    pub destination_currency: String,
+    pub rate: FxRate,
+}
+
+/// A rate locked for a short time for one cross-currency transfer.
+///
+/// # Database Table
+///
+/// Maps to the `fx_quotes` table. A quote belongs to one business and can
+/// be used by at most one transfer (`transactions.fx_quote_id` is unique).
+#[derive(Debug, Clone, sqlx::FromRow, Serialize)]
+pub struct FxQuote {
+    pub id: Uuid,
+    pub api_key_id: Uuid,
+    pub source_currency: String,
+    pub destination_currency: String,
+    #[sqlx(rename = "rate_e12")]
+    pub rate: FxRate,
+    pub source_amount_cents: i64,
+    pub destination_amount_cents: i64,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Request to quote a cross-currency amount.
+///
+/// # JSON Example
+///
+/// ```json
+/// {
+///   "source_currency": "USD",
+///   "destination_currency": "EUR",
+///   "source_amount_cents": 10000
+/// }
+/// ```
+#[derive(Debug, Deserialize)]
+pub struct CreateFxQuoteRequest {
+    /// Currency of the account paying
+    pub source_currency: String,
+
+    /// Currency of the account receiving
+    pub destination_currency: String,
+
+    /// Amount debited from the source account, in its minor units
+    pub source_amount_cents: i64,
+}
+
+/// Response body for quote endpoints.
+///
+/// # JSON Example
+///
+/// ```json
+/// {
+///   "id": "a20e8400-e29b-41d4-a716-446655440000",
+///   "source_currency": "USD",
+///   "destination_currency": "EUR",
+///   "rate": "0.92",
+///   "source_amount_cents": 10000,
+///   "destination_amount_cents": 9200,
+///   "expires_at": "2025-12-21T16:01:00Z",
+///   "created_at": "2025-12-21T16:00:00Z"
+/// }
+/// ```
+#[derive(Debug, Serialize)]
+pub struct FxQuoteResponse {
+    pub id: Uuid,
+    pub source_currency: String,
+    pub destination_currency: String,
+    pub rate: FxRate,
+    pub source_amount_cents: i64,
+    pub destination_amount_cents: i64,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Convert database FxQuote to API FxQuoteResponse (drops `api_key_id`).
+impl From<FxQuote> for FxQuoteResponse {
+    fn from(quote: FxQuote) -> Self {
+        Self {
+            id: quote.id,
+            source_currency: quote.source_currency,
+            destination_currency: quote.destination_currency,
+            rate: quote.rate,
+            source_amount_cents: quote.source_amount_cents,
+            destination_amount_cents: quote.destination_amount_cents,
+            expires_at: quote.expires_at,
+            created_at: quote.created_at,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn currency(code: &str) -> Currency {
+        Currency::from_code(code).unwrap()
+    }
+
+    #[test]
+    fn converts_and_rounds_half_to_even() {
+        let cases = [
+            // (rate, amount, from, to, expected)
+            ("0.92", 1050, "USD", "EUR", Some(966)),
+            // 0.5, 1.5, 2.5 and 3.5 cents
+            ("0.005", 100, "USD", "EUR", Some(0)),
+            ("0.015", 100, "USD", "EUR", Some(2)),
+            ("0.025", 100, "USD", "EUR", Some(2)),
+            ("0.035", 100, "USD", "EUR", Some(4)),
+            // Just above and below half a cent
+            ("0.0051", 100, "USD", "EUR", Some(1)),
+            ("0.0049", 100, "USD", "EUR", Some(0)),
+            // JPY has no minor unit, USD has two decimals
+            ("0.0067", 1000, "JPY", "USD", Some(670)),
+            ("0.0067", 1, "JPY", "USD", Some(1)),
+            ("149.5", 1050, "USD", "JPY", Some(1570)),
+            ("150", 1, "USD", "JPY", Some(2)),
+            ("150", 3, "USD", "JPY", Some(4)),
+            // KWD has three decimals
+            ("0.3075", 10000, "USD", "KWD", Some(30750)),
+            ("3.25", 1, "KWD", "USD", Some(0)),
+            // Result does not fit in an i64
+            ("2", i64::MAX, "USD", "EUR", None),
+        ];
+
+        for (rate, amount, from, to, expected) in cases {
+            let rate: FxRate = rate.parse().unwrap();
+            assert_eq!(
+                rate.convert(amount, currency(from), currency(to)),
+                expected,
+                "{} {} -> {} at {}",
+                amount,
+                from,
+                to,
+                rate
+            );
+        }
+    }
+
+    #[test]
+    fn parses_and_formats_rates() {
+        let cases = [
+            ("0.92", Ok("0.92")),
+            ("1", Ok("1")),
+            ("149.50", Ok("149.5")),
+            ("0.000000000001", Ok("0.000000000001")),
+            ("0.0000000000001", Err(())),
+            ("0", Err(())),
+            ("-1.2", Err(())),
+            (".5", Err(())),
+            ("1e3", Err(())),
+        ];
+
+        for (input, expected) in cases {
+            let parsed = input.parse::<FxRate>().map(|rate| rate.to_string());
+            assert_eq!(parsed.as_deref().map_err(|_| ()), expected, "{}", input);
+        }
+    }
+}
@@ -0,0 +1,134 @@
+//! Double-entry ledger models.
+//!
+//! This module defines:
+//! - `LedgerAccount` / `Posting`: Postings written to the `ledger_entries` table
+//! - `TrialBalanceResponse`: Response body for the trial balance report
+//!
+//! # Double-Entry Bookkeeping
+//!
+//! Every transaction writes at least one debit and one credit posting of
+//! equal value. Money entering or leaving the system is booked against a
+//! system account (e.g. `external_funding`), so the sum of all debits always
+//! equals the sum of all credits.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+
+/// Side of a double-entry posting.
+///
+/// For customer accounts a credit increases the balance and a debit
+/// decreases it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntryType {
+    Debit,
+    Credit,
+}
+
+impl EntryType {
+    /// String form stored in `ledger_entries.entry_type`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EntryType::Debit => "debit",
+            EntryType::Credit => "credit",
+        }
+    }
+}
+
+/// Account a posting is booked against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LedgerAccount {
+    /// A business account from the `accounts` table
+    Customer(Uuid),
+
+    /// A system account from the `system_accounts` table
+    System(&'static str),
+}
+
+/// A posting to be written to `ledger_entries`.
+#[derive(Debug, Clone)]
+pub struct Posting {
+    pub account: LedgerAccount,
+    pub entry_type: EntryType,
+    pub amount_cents: i64,
+    pub currency: String,
+}
+
+/// One line of the trial balance: the totals of a single ledger account.
+///
+/// `balance_cents` is derived purely from postings (`credits - debits`), so
+/// for customer accounts it should equal `accounts.balance_cents`.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct TrialBalanceLine {
+    /// Customer account (NULL for system accounts)
+    pub account_id: Option<Uuid>,
+
+    /// System account code (NULL for customer accounts)
+    pub system_account_code: Option<String>,
+
+    /// Currency of the postings
+    pub currency: String,
+
+    /// Sum of debit postings
+    pub debits_cents: i64,
+
+    /// Sum of credit postings
+    pub credits_cents: i64,
+
+    /// Balance derived from postings (credits - debits)
+    pub balance_cents: i64,
+}
+
+/// Debit and credit totals for one currency.
+#[derive(Debug, Serialize)]
+pub struct TrialBalanceTotal {
+    pub currency: String,
+    pub debits_cents: i64,
+    pub credits_cents: i64,
+}
+
+/// Response body for the trial balance report.
+///
+/// # JSON Example
+///
+/// ```json
+/// {
+///   "generated_at": "2025-12-21T20:00:00Z",
+///   "is_balanced": true,
+///   "totals": [
+///     { "currency": "USD", "debits_cents": 150000, "credits_cents": 150000 }
+///   ],
+///   "lines": [
+///     {
+///       "account_id": null,
+///       "system_account_code": "external_funding",
+///       "currency": "USD",
+///       "debits_cents": 150000,
+///       "credits_cents": 0,
+///       "balance_cents": -150000
+///     },
+///     {
+///       "account_id": "550e8400-e29b-41d4-a716-446655440000",
+///       "system_account_code": null,
+///       "currency": "USD",
+///       "debits_cents": 0,
+///       "credits_cents": 150000,
+///       "balance_cents": 150000
+///     }
+///   ]
+/// }
+/// ```
+#[derive(Debug, Serialize)]
+pub struct TrialBalanceResponse {
+    /// When the report was computed
+    pub generated_at: DateTime<Utc>,
+
+    /// True when debits equal credits in every currency
+    pub is_balanced: bool,
+
+    /// Debit/credit totals per currency
+    pub totals: Vec<TrialBalanceTotal>,
+
+    /// Per-account totals
+    pub lines: Vec<TrialBalanceLine>,
+}
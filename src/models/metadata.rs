@@ -0,0 +1,147 @@
+//! Client-defined metadata attached to resources.
+//!
+//! Metadata is a flat JSON object of string keys and string values, e.g.
+//! `{"customer_id": "cus_123", "segment": "enterprise"}`. It is stored as
+//! JSONB and can be searched by key and value.
+//!
+//! # Limits
+//!
+//! - At most `MAX_KEYS` keys
+//! - Keys: 1 to `MAX_KEY_LENGTH` characters
+//! - Values: strings of at most `MAX_VALUE_LENGTH` characters
+//!
+//! # Updates
+//!
+//! Updates are merged into the stored metadata: a string sets the key and
+//! `null` removes it; keys that are left out stay unchanged.
+
+use serde_json::{Map, Value};
+
+/// Metadata as a JSON object.
+pub type Metadata = Map<String, Value>;
+
+/// Maximum number of keys.
+pub const MAX_KEYS: usize = 50;
+
+/// Maximum key length in characters.
+pub const MAX_KEY_LENGTH: usize = 40;
+
+/// Maximum value length in characters.
+pub const MAX_VALUE_LENGTH: usize = 500;
+
+/// Check metadata against the limits.
+///
+/// # Errors
+///
+/// A message naming the first offending key, suitable for an
+/// `InvalidRequest` response.
+pub fn validate(metadata: &Metadata) -> Result<(), String> {
+    if metadata.len() > MAX_KEYS {
+        return Err(format!("metadata can have at most {} keys", MAX_KEYS));
+    }
+
+    for (key, value) in metadata {
+        if key.is_empty() || key.chars().count() > MAX_KEY_LENGTH {
+            return Err(format!(
+                "metadata key '{}' must be 1 to {} characters",
+                key, MAX_KEY_LENGTH
+            ));
+        }
+
+        match value {
+            Value::String(value) if value.chars().count() <= MAX_VALUE_LENGTH => {}
+            Value::String(_) => {
+                return Err(format!(
+                    "metadata value of '{}' must be at most {} characters",
+                    key, MAX_VALUE_LENGTH
+                ));
+            }
+            _ => {
+                return Err(format!("metadata value of '{}' must be a string", key));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Merge an update into stored metadata (`null` removes a key).
+///
+/// The result still has to be checked with `validate`.
+pub fn merge(metadata: &mut Metadata, update: Metadata) {
+    for (key, value) in update {
+        if value.is_null() {
+            metadata.remove(&key);
+        } else {
+            metadata.insert(key, value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn metadata(value: Value) -> Metadata {
+        match value {
+            Value::Object(map) => map,
+            _ => panic!("metadata must be an object"),
+        }
+    }
+
+    fn with_keys(count: usize) -> Metadata {
+        (0..count)
+            .map(|i| (format!("key_{}", i), json!("value")))
+            .collect()
+    }
+
+    #[test]
+    fn validates_limits() {
+        let cases = [
+            (Metadata::new(), true),
+            (metadata(json!({"customer_id": "cus_123"})), true),
+            (with_keys(MAX_KEYS), true),
+            (with_keys(MAX_KEYS + 1), false),
+            (metadata(json!({"": "value"})), false),
+            (metadata(json!({"k".repeat(MAX_KEY_LENGTH): "value"})), true),
+            (
+                metadata(json!({"k".repeat(MAX_KEY_LENGTH + 1): "value"})),
+                false,
+            ),
+            // Lengths count characters, not bytes
+            (metadata(json!({"é".repeat(MAX_KEY_LENGTH): "value"})), true),
+            (metadata(json!({"key": "v".repeat(MAX_VALUE_LENGTH)})), true),
+            (metadata(json!({"key": "é".repeat(MAX_VALUE_LENGTH)})), true),
+            (
+                metadata(json!({"key": "v".repeat(MAX_VALUE_LENGTH + 1)})),
+                false,
+            ),
+            (metadata(json!({"key": ""})), true),
+            (metadata(json!({"key": 1})), false),
+            (metadata(json!({"key": true})), false),
+            (metadata(json!({"key": null})), false),
+            (metadata(json!({"key": {"nested": "value"}})), false),
+            (metadata(json!({"key": ["value"]})), false),
+        ];
+
+        for (metadata, valid) in cases {
+            assert_eq!(validate(&metadata).is_ok(), valid, "{:?}", metadata);
+        }
+    }
+
+    #[test]
+    fn merges_updates() {
+        let mut stored = metadata(json!({"keep": "a", "change": "b", "remove": "c"}));
+        merge(
+            &mut stored,
+            metadata(json!({"change": "B", "remove": null, "add": "d"})),
+        );
+
+        assert_eq!(
+            stored,
+            metadata(json!({"keep": "a", "change": "B", "add": "d"}))
+        );
+    }
+}
@@ -5,6 +5,24 @@
 /// Business account model
 pub mod account;
 
+/// ISO 4217 currencies and minor units
+pub mod currency;
+
+/// Foreign-exchange rates and quotes
+pub mod fx;
+
+/// Double-entry ledger models
+pub mod ledger;
+
+/// Client-defined key/value metadata
+pub mod metadata;
+
+/// Cursor pagination shared by list endpoints
+pub mod pagination;
+
+/// Balance reconciliation models
+pub mod reconciliation;
+
 pub mod transaction;
 
 /// Webhook models for event delivery
@@ -0,0 +1,105 @@
+//! Cursor pagination shared by list endpoints.
+//!
+//! This module defines:
+//! - `ListResponse`: The `{data, has_more, next_cursor}` envelope
+//! - `SortOrder`: Direction of a sortable list
+//! - `page_limit`: Validation of the `limit` query parameter
+//!
+//! # Cursors
+//!
+//! A cursor is the ID of the last item of a page. Passing it back as
+//! `starting_after` returns the items that follow it in the list's order;
+//! services page with a keyset on `(sort column, id)` rather than OFFSET,
+//! so pages stay stable while new rows are written.
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Number of items returned per page when `limit` is omitted.
+pub const DEFAULT_PAGE_LIMIT: i64 = 25;
+
+/// Largest accepted `limit`.
+pub const MAX_PAGE_LIMIT: i64 = 100;
+
+/// Validate the `limit` query parameter, applying the default.
+///
+/// # Errors
+///
+/// Returns a message suitable for `AppError::InvalidRequest` if the limit
+/// is outside 1 to `MAX_PAGE_LIMIT`.
+pub fn page_limit(limit: Option<i64>) -> Result<i64, String> {
+    let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT);
+    if !(1..=MAX_PAGE_LIMIT).contains(&limit) {
+        return Err(format!("limit must be between 1 and {}", MAX_PAGE_LIMIT));
+    }
+    Ok(limit)
+}
+
+/// Direction of a sortable list (`order` query parameter).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    Asc,
+    #[default]
+    Desc,
+}
+
+impl SortOrder {
+    /// SQL keyword for `ORDER BY`.
+    pub fn as_sql(self) -> &'static str {
+        match self {
+            SortOrder::Asc => "ASC",
+            SortOrder::Desc => "DESC",
+        }
+    }
+
+    /// Row comparison selecting the rows after a cursor in this order.
+    pub fn after_cursor(self) -> &'static str {
+        match self {
+            SortOrder::Asc => ">",
+            SortOrder::Desc => "<",
+        }
+    }
+}
+
+/// One page of a list endpoint.
+///
+/// # JSON Example
+///
+/// ```json
+/// {
+///   "data": [ { "id": "550e8400-e29b-41d4-a716-446655440000", ... } ],
+///   "has_more": true,
+///   "next_cursor": "550e8400-e29b-41d4-a716-446655440000"
+/// }
+/// ```
+#[derive(Debug, Serialize)]
+pub struct ListResponse<T> {
+    /// Items on this page, in the list's order
+    pub data: Vec<T>,
+
+    /// Whether more items exist after this page
+    pub has_more: bool,
+
+    /// Value to pass as `starting_after` to fetch the next page
+    pub next_cursor: Option<Uuid>,
+}
+
+impl<T> ListResponse<T> {
+    /// Build a page from database rows, converting each into its response type.
+    ///
+    /// The cursor for the next page is the ID of the last row, and only set
+    /// when `has_more` is true.
+    pub fn from_rows<R>(rows: Vec<R>, has_more: bool, id: impl Fn(&R) -> Uuid) -> Self
+    where
+        R: Into<T>,
+    {
+        let next_cursor = if has_more { rows.last().map(id) } else { None };
+
+        Self {
+            data: rows.into_iter().map(Into::into).collect(),
+            has_more,
+            next_cursor,
+        }
+    }
+}
@@ -0,0 +1,68 @@
+//! Balance reconciliation models.
+//!
+//! This module defines:
+//! - `BalanceMismatch`: An account whose stored balance drifted from its transactions
+//! - `ReconciliationReport`: Response body for the reconciliation endpoint
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+
+/// An account whose stored balance differs from the balance computed
+/// from its transactions.
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct BalanceMismatch {
+    /// Account with drift
+    pub account_id: Uuid,
+
+    /// Business that owns the account (internal, used for logging)
+    #[serde(skip_serializing)]
+    pub api_key_id: Uuid,
+
+    /// Account currency
+    pub currency: String,
+
+    /// Value of `accounts.balance_cents`
+    pub stored_balance_cents: i64,
+
+    /// Incoming minus outgoing transaction amounts
+    pub computed_balance_cents: i64,
+
+    /// `stored_balance_cents - computed_balance_cents`
+    pub drift_cents: i64,
+}
+
+/// Result of a reconciliation run.
+///
+/// # JSON Example
+///
+/// ```json
+/// {
+///   "checked_at": "2025-12-21T20:00:00Z",
+///   "accounts_checked": 42,
+///   "is_consistent": false,
+///   "mismatches": [
+///     {
+///       "account_id": "550e8400-e29b-41d4-a716-446655440000",
+///       "currency": "USD",
+///       "stored_balance_cents": 150000,
+///       "computed_balance_cents": 50000,
+///       "drift_cents": 100000
+///     }
+///   ]
+/// }
+/// ```
+#[derive(Debug, Serialize)]
+pub struct ReconciliationReport {
+    /// When the snapshot the report describes was taken
+    pub checked_at: DateTime<Utc>,
+
+    /// Number of accounts compared
+    pub accounts_checked: i64,
+
+    /// True when no account has drift
+    pub is_consistent: bool,
+
+    /// Accounts whose stored balance drifted
+    pub mismatches: Vec<BalanceMismatch>,
+}
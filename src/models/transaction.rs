@@ -2,13 +2,172 @@
 //!
 //! This module defines:
 //! - `Transaction`: Database entity representing a transaction
-//! - Request types for credit, debit, and transfer operations
+//! - `TransactionStatus`: Lifecycle states and the transitions between them
+//! - `TransactionStatusChange`: One entry of a transaction's status history
+//! - Request types for credit, debit, transfer, reversal, and authorization operations
 //! - `TransactionResponse`: Response body returned to clients
+//! - `TransactionListQuery` / `TransactionListResponse`: Paginated account history
+
+use std::{fmt, str::FromStr};
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use sqlx::{
+    Postgres,
+    encode::IsNull,
+    error::BoxDynError,
+    postgres::{PgArgumentBuffer, PgTypeInfo, PgValueRef},
+    types::Json,
+};
 use uuid::Uuid;
 
+use super::{
+    fx::{FxQuote, FxRate},
+    metadata::Metadata,
+    pagination::ListResponse,
+};
+
+/// Lifecycle status of a transaction.
+///
+/// Stored as lowercase text in `transactions.status`.
+///
+/// # Transitions
+///
+/// | From         | To                                                    |
+/// | ------------ | ----------------------------------------------------- |
+/// | `pending`    | `processing`, `completed`, `failed`, `canceled`       |
+/// | `pending`    | `captured`, `voided`, `expired` (authorizations only) |
+/// | `processing` | `pending`, `completed`, `failed`                      |
+/// | `completed`  | `reversed` (once fully reversed)                      |
+///
+/// All other statuses are final.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TransactionStatus {
+    /// Awaiting approval or settlement; funds are held on the source account
+    Pending,
+
+    /// Handed to an external party for settlement; funds still held
+    Processing,
+
+    /// Applied: balances moved and ledger postings written
+    Completed,
+
+    /// Rejected before completion; held funds released
+    Failed,
+
+    /// Completed, then reversed in full
+    Reversed,
+
+    /// Withdrawn before completion; held funds released
+    Canceled,
+
+    /// Authorization whose hold was posted by a capture
+    Captured,
+
+    /// Authorization whose hold was released by a void
+    Voided,
+
+    /// Authorization whose hold was released on expiry
+    Expired,
+}
+
+impl TransactionStatus {
+    /// String form stored in `transactions.status`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TransactionStatus::Pending => "pending",
+            TransactionStatus::Processing => "processing",
+            TransactionStatus::Completed => "completed",
+            TransactionStatus::Failed => "failed",
+            TransactionStatus::Reversed => "reversed",
+            TransactionStatus::Canceled => "canceled",
+            TransactionStatus::Captured => "captured",
+            TransactionStatus::Voided => "voided",
+            TransactionStatus::Expired => "expired",
+        }
+    }
+
+    /// Whether the transition table allows moving from `self` to `next`.
+    pub fn can_transition_to(self, next: TransactionStatus) -> bool {
+        use TransactionStatus::*;
+
+        matches!(
+            (self, next),
+            (
+                Pending,
+                Processing | Completed | Failed | Canceled | Captured | Voided | Expired
+            ) | (Processing, Pending | Completed | Failed)
+                | (Completed, Reversed)
+        )
+    }
+
+    /// Whether the transaction's money movement is posted (balances and
+    /// ledger reflect it).
+    pub fn is_posted(self) -> bool {
+        matches!(
+            self,
+            TransactionStatus::Completed | TransactionStatus::Reversed
+        )
+    }
+
+    /// Whether the transaction still holds funds on its source account.
+    pub fn holds_funds(self) -> bool {
+        matches!(
+            self,
+            TransactionStatus::Pending | TransactionStatus::Processing
+        )
+    }
+}
+
+impl fmt::Display for TransactionStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for TransactionStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pending" => Ok(TransactionStatus::Pending),
+            "processing" => Ok(TransactionStatus::Processing),
+            "completed" => Ok(TransactionStatus::Completed),
+            "failed" => Ok(TransactionStatus::Failed),
+            "reversed" => Ok(TransactionStatus::Reversed),
+            "canceled" => Ok(TransactionStatus::Canceled),
+            "captured" => Ok(TransactionStatus::Captured),
+            "voided" => Ok(TransactionStatus::Voided),
+            "expired" => Ok(TransactionStatus::Expired),
+            other => Err(format!("unknown transaction status '{}'", other)),
+        }
+    }
+}
+
+/// Stored as text, so it binds and decodes like a `&str`.
+impl sqlx::Type<Postgres> for TransactionStatus {
+    fn type_info() -> PgTypeInfo {
+        <&str as sqlx::Type<Postgres>>::type_info()
+    }
+
+    fn compatible(ty: &PgTypeInfo) -> bool {
+        <&str as sqlx::Type<Postgres>>::compatible(ty)
+    }
+}
+
+impl sqlx::Encode<'_, Postgres> for TransactionStatus {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, BoxDynError> {
+        <&str as sqlx::Encode<Postgres>>::encode(self.as_str(), buf)
+    }
+}
+
+impl<'r> sqlx::Decode<'r, Postgres> for TransactionStatus {
+    fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+        Ok(<&str as sqlx::Decode<Postgres>>::decode(value)?.parse()?)
+    }
+}
+
 /// Represents a transaction record from the database.
 ///
 /// # Database Table
@@ -17,7 +176,7 @@ use uuid::Uuid;
 /// - Has a unique ID and optional idempotency key
 /// - References one or two accounts (depending on type)
 /// - Stores amount in cents (never floats!)
-/// - Tracks status (completed, failed, pending)
+/// - Tracks its lifecycle status (see `TransactionStatus`)
 #[derive(Debug, Clone, sqlx::FromRow, Serialize)]
 pub struct Transaction {
     /// Unique identifier for this transaction
@@ -25,11 +184,13 @@ pub struct Transaction {
 
     /// Optional idempotency key for preventing duplicates
     ///
-    /// If client sends same idempotency_key twice, the second request
-    /// returns the original transaction instead of creating a duplicate.
+    /// If client sends same idempotency_key twice for the same operation,
+    /// the second request returns the original transaction instead of
+    /// creating a duplicate. Keys are scoped per business and operation.
     pub idempotency_key: Option<String>,
 
-    /// Type of transaction (credit, debit, or transfer)
+    /// Type of transaction (credit, debit, transfer, opening_balance, reversal,
+    /// authorization, or capture)
     pub transaction_type: String,
 
     /// Source account (for debit and transfer)
@@ -42,29 +203,130 @@ pub struct Transaction {
     /// NULL for debit transactions (money disappearing to nowhere)
     pub to_account_id: Option<Uuid>,
 
-    /// Amount in cents
+    /// Amount in cents (minor units of `currency`)
     ///
     /// Must be positive (enforced by CHECK constraint)
     pub amount_cents: i64,
 
-    /// Currency code (ISO 4217)
+    /// Currency code (ISO 4217), taken from the accounts involved
     pub currency: String,
 
     /// Human-readable description
     pub description: Option<String>,
 
-    /// Transaction status
-    ///
-    /// - "completed": Successfully applied
-    /// - "failed": Rejected (e.g., insufficient funds)
-    /// - "pending": In progress (future use)
-    pub status: String,
+    /// Transaction status (see `TransactionStatus` for the transitions)
+    pub status: TransactionStatus,
 
     /// When transaction was created
     pub created_at: DateTime<Utc>,
 
-    /// Additional metadata (JSON)
-    pub metadata: Option<serde_json::Value>,
+    /// Client-defined key/value metadata (see `models::metadata`)
+    pub metadata: Json<Metadata>,
+
+    /// Business (API key) that created this transaction
+    pub api_key_id: Uuid,
+
+    /// SHA-256 of the request payload the idempotency key was first used with
+    ///
+    /// Replaying the key with a different payload is rejected with 409 Conflict.
+    pub request_fingerprint: Option<String>,
+
+    /// Transaction this reversal undoes (set only for reversals)
+    pub reversed_transaction_id: Option<Uuid>,
+
+    /// Authorization this capture posts (set only for captures)
+    pub authorization_id: Option<Uuid>,
+
+    /// When the hold is released if not captured (set only for authorizations)
+    pub expires_at: Option<DateTime<Utc>>,
+
+    /// Quote a cross-currency transfer was made with
+    pub fx_quote_id: Option<Uuid>,
+
+    /// Amount credited to the destination account, in its minor units
+    /// (cross-currency transfers only; otherwise it is `amount_cents`)
+    pub destination_amount_cents: Option<i64>,
+
+    /// Currency of the destination account (cross-currency transfers only)
+    pub destination_currency: Option<String>,
+
+    /// Rate the transfer was converted at (cross-currency transfers only)
+    #[sqlx(rename = "fx_rate_e12")]
+    pub fx_rate: Option<FxRate>,
+}
+
+impl Transaction {
+    /// Amount credited to `to_account_id`, in its currency.
+    pub fn credited_amount_cents(&self) -> i64 {
+        self.destination_amount_cents.unwrap_or(self.amount_cents)
+    }
+}
+
+/// Data for recording a new transaction.
+///
+/// # Database Table
+///
+/// Inserted into the `transactions` table by the transaction service,
+/// together with its ledger postings if `status` is posted.
+#[derive(Debug)]
+pub struct NewTransaction {
+    pub transaction_type: &'static str,
+    pub status: TransactionStatus,
+    pub from_account_id: Option<Uuid>,
+    pub to_account_id: Option<Uuid>,
+    pub amount_cents: i64,
+    pub description: Option<String>,
+    pub metadata: Metadata,
+    pub idempotency_key: Option<String>,
+    pub request_fingerprint: Option<String>,
+    pub api_key_id: Uuid,
+    pub reversed_transaction_id: Option<Uuid>,
+    pub authorization_id: Option<Uuid>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub fx_quote: Option<FxQuote>,
+}
+
+/// One status change of a transaction.
+///
+/// # Database Table
+///
+/// Maps to the `transaction_status_history` table. The first entry of a
+/// transaction has no `from_status` and records the status it was created
+/// with.
+#[derive(Debug, Clone, sqlx::FromRow, Serialize)]
+pub struct TransactionStatusChange {
+    pub id: Uuid,
+    pub transaction_id: Uuid,
+    pub from_status: Option<TransactionStatus>,
+    pub to_status: TransactionStatus,
+    pub reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Request to change the status of a transaction.
+///
+/// # JSON Example
+///
+/// ```json
+/// {
+///   "status": "completed",
+///   "reason": "Approved by finance"
+/// }
+/// ```
+///
+/// # Validation
+///
+/// - `status` must be reachable from the current status (see
+///   `TransactionStatus`)
+/// - Only `pending`, `processing`, `completed`, `failed` and `canceled` can
+///   be set; reversals, captures, voids and expiry have their own endpoints
+#[derive(Debug, Deserialize)]
+pub struct UpdateTransactionStatusRequest {
+    /// Status to move the transaction to
+    pub status: TransactionStatus,
+
+    /// Optional reason, stored in the status history
+    pub reason: Option<String>,
 }
 
 /// Request to credit (add money to) an account.
@@ -76,6 +338,7 @@ pub struct Transaction {
 ///   "account_id": "550e8400-e29b-41d4-a716-446655440000",
 ///   "amount_cents": 100000,
 ///   "description": "Initial deposit",
+///   "metadata": { "order_id": "ord_1042" },
 ///   "idempotency_key": "deposit-2025-001"
 /// }
 /// ```
@@ -90,6 +353,10 @@ pub struct CreditRequest {
     /// Optional description
     pub description: Option<String>,
 
+    /// Client-defined key/value metadata (e.g. an order ID)
+    #[serde(default)]
+    pub metadata: Metadata,
+
     /// Optional idempotency key to prevent duplicates
     pub idempotency_key: Option<String>,
 }
@@ -123,6 +390,10 @@ pub struct DebitRequest {
     /// Optional description
     pub description: Option<String>,
 
+    /// Client-defined key/value metadata (e.g. an order ID)
+    #[serde(default)]
+    pub metadata: Metadata,
+
     /// Optional idempotency key to prevent duplicates
     pub idempotency_key: Option<String>,
 }
@@ -137,6 +408,7 @@ pub struct DebitRequest {
 ///   "to_account_id": "660e8400-e29b-41d4-a716-446655440001",
 ///   "amount_cents": 25000,
 ///   "description": "Payment for services",
+///   "metadata": { "invoice_id": "inv_789" },
 ///   "idempotency_key": "invoice-789"
 /// }
 /// ```
@@ -145,6 +417,19 @@ pub struct DebitRequest {
 ///
 /// BOTH accounts are updated in the same database transaction.
 /// If debit fails, credit doesn't happen. If credit fails, debit is rolled back.
+///
+/// # Pending Transfers
+///
+/// With `"pending": true` the amount is only held on the source account and
+/// the transfer waits for a status change to `completed` (moves the money)
+/// or `failed` / `canceled` (releases the hold).
+///
+/// # Cross-Currency Transfers
+///
+/// Accounts of different currencies need an `fx_quote_id` from
+/// `POST /api/v1/fx/quotes` for exactly this source currency, destination
+/// currency and `amount_cents`. The destination account is credited the
+/// quoted destination amount.
 #[derive(Debug, Deserialize)]
 pub struct TransferRequest {
     /// Account to transfer from (will decrease)
@@ -159,6 +444,115 @@ pub struct TransferRequest {
     /// Optional description
     pub description: Option<String>,
 
+    /// Client-defined key/value metadata (e.g. an order ID)
+    #[serde(default)]
+    pub metadata: Metadata,
+
+    /// Optional idempotency key to prevent duplicates
+    pub idempotency_key: Option<String>,
+
+    /// Hold the funds and leave the transfer `pending` (awaiting approval or
+    /// settlement) instead of completing it immediately
+    #[serde(default)]
+    pub pending: bool,
+
+    /// Quote to convert with when the accounts' currencies differ
+    pub fx_quote_id: Option<Uuid>,
+}
+
+/// Request to reverse (undo) all or part of a transaction.
+///
+/// # JSON Example
+///
+/// ```json
+/// {
+///   "amount_cents": 10000,
+///   "description": "Refund for duplicate charge",
+///   "idempotency_key": "refund-2025-042"
+/// }
+/// ```
+///
+/// # Validation
+///
+/// - Omitting `amount_cents` reverses whatever has not been reversed yet
+/// - All reversals of a transaction together never exceed its amount
+/// - The account money is taken back from must have sufficient balance
+/// - Reversals themselves cannot be reversed
+#[derive(Debug, Default, Deserialize)]
+pub struct ReverseTransactionRequest {
+    /// Amount to reverse in cents (defaults to the remaining amount)
+    pub amount_cents: Option<i64>,
+
+    /// Optional description
+    pub description: Option<String>,
+
+    /// Optional idempotency key to prevent duplicates
+    pub idempotency_key: Option<String>,
+}
+
+/// Request to authorize (place a hold on) an account.
+///
+/// # JSON Example
+///
+/// ```json
+/// {
+///   "account_id": "550e8400-e29b-41d4-a716-446655440000",
+///   "amount_cents": 15000,
+///   "description": "Order #1042",
+///   "idempotency_key": "order-1042-auth",
+///   "expires_in_seconds": 86400
+/// }
+/// ```
+///
+/// # Validation
+///
+/// - Account must have sufficient available balance
+/// - Amount must be positive
+/// - `expires_in_seconds`: 1 second to 30 days, defaults to 7 days
+#[derive(Debug, Deserialize)]
+pub struct AuthorizeRequest {
+    /// Account to place the hold on
+    pub account_id: Uuid,
+
+    /// Amount to hold in cents
+    pub amount_cents: i64,
+
+    /// Optional description
+    pub description: Option<String>,
+
+    /// Optional idempotency key to prevent duplicates
+    pub idempotency_key: Option<String>,
+
+    /// Seconds until the hold is released if not captured
+    pub expires_in_seconds: Option<i64>,
+}
+
+/// Request to capture (post) an authorization.
+///
+/// # JSON Example
+///
+/// ```json
+/// {
+///   "amount_cents": 12000,
+///   "description": "Order #1042 shipped",
+///   "idempotency_key": "order-1042-capture"
+/// }
+/// ```
+///
+/// # Validation
+///
+/// - Omitting `amount_cents` captures the full authorized amount
+/// - Amount must not exceed the authorized amount
+/// - An authorization is captured once; the uncaptured rest of the hold is
+///   released
+#[derive(Debug, Default, Deserialize)]
+pub struct CaptureRequest {
+    /// Amount to capture in cents (defaults to the authorized amount)
+    pub amount_cents: Option<i64>,
+
+    /// Optional description
+    pub description: Option<String>,
+
     /// Optional idempotency key to prevent duplicates
     pub idempotency_key: Option<String>,
 }
@@ -176,6 +570,8 @@ pub struct TransferRequest {
 ///   "amount_cents": 25000,
 ///   "currency": "USD",
 ///   "description": "Payment for services",
+///   "metadata": { "invoice_id": "inv_789" },
+///   "idempotency_key": "invoice-789",
 ///   "status": "completed",
 ///   "created_at": "2025-12-21T16:00:00Z"
 /// }
@@ -189,14 +585,31 @@ pub struct TransactionResponse {
     pub amount_cents: i64,
     pub currency: String,
     pub description: Option<String>,
-    pub status: String,
+    pub metadata: Metadata,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub idempotency_key: Option<String>,
+    pub status: TransactionStatus,
     pub created_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reversed_transaction_id: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub authorization_id: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fx_quote_id: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub destination_amount_cents: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub destination_currency: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fx_rate: Option<FxRate>,
 }
 
 /// Convert database Transaction to API TransactionResponse.
 ///
-/// This removes internal fields like metadata and idempotency_key
-/// that clients don't need to see.
+/// This removes internal fields like `api_key_id` and
+/// `request_fingerprint` that clients don't need to see.
 impl From<Transaction> for TransactionResponse {
     fn from(transaction: Transaction) -> Self {
         Self {
@@ -207,8 +620,172 @@ impl From<Transaction> for TransactionResponse {
             amount_cents: transaction.amount_cents,
             currency: transaction.currency,
             description: transaction.description,
+            metadata: transaction.metadata.0,
+            idempotency_key: transaction.idempotency_key,
             status: transaction.status,
             created_at: transaction.created_at,
+            reversed_transaction_id: transaction.reversed_transaction_id,
+            authorization_id: transaction.authorization_id,
+            expires_at: transaction.expires_at,
+            fx_quote_id: transaction.fx_quote_id,
+            destination_amount_cents: transaction.destination_amount_cents,
+            destination_currency: transaction.destination_currency,
+            fx_rate: transaction.fx_rate,
+        }
+    }
+}
+
+/// Direction of a transaction relative to the account whose history is listed.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TransactionDirection {
+    /// Money flowing into the account (`to_account_id` matches)
+    Incoming,
+
+    /// Money flowing out of the account (`from_account_id` matches)
+    Outgoing,
+}
+
+impl TransactionDirection {
+    /// String form used when binding the filter into SQL.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TransactionDirection::Incoming => "incoming",
+            TransactionDirection::Outgoing => "outgoing",
+        }
+    }
+}
+
+/// Query parameters for listing the transactions of an account.
+///
+/// # Example
+///
+/// ```text
+/// GET /api/v1/accounts/{id}/transactions?limit=50&direction=outgoing&transaction_type=debit
+/// ```
+///
+/// # Pagination
+///
+/// Results are ordered newest first by `(created_at, id)`. To fetch the next
+/// page, pass the `next_cursor` of the previous response as `starting_after`.
+///
+/// # Validation
+///
+/// - `limit`: 1 to 100, defaults to 25
+/// - `min_amount_cents` must not exceed `max_amount_cents`
+/// - `created_after` must be earlier than `created_before`
+/// - `metadata_value` requires `metadata_key`
+#[derive(Debug, Deserialize)]
+pub struct TransactionListQuery {
+    /// Maximum number of transactions to return
+    pub limit: Option<i64>,
+
+    /// Return transactions strictly older than this transaction ID (cursor)
+    pub starting_after: Option<Uuid>,
+
+    /// Only return transactions of this type (credit, debit, transfer,
+    /// opening_balance, reversal, authorization, capture)
+    pub transaction_type: Option<String>,
+
+    /// Only return incoming or outgoing transactions
+    pub direction: Option<TransactionDirection>,
+
+    /// Only return transactions with this status
+    pub status: Option<TransactionStatus>,
+
+    /// Only return transactions created at or after this time
+    pub created_after: Option<DateTime<Utc>>,
+
+    /// Only return transactions created before this time
+    pub created_before: Option<DateTime<Utc>>,
+
+    /// Only return transactions of at least this amount, in the account's
+    /// currency (the amount received, for incoming cross-currency transfers)
+    pub min_amount_cents: Option<i64>,
+
+    /// Only return transactions of at most this amount, in the account's
+    /// currency
+    pub max_amount_cents: Option<i64>,
+
+    /// Only return transactions with this metadata key
+    pub metadata_key: Option<String>,
+
+    /// Only return transactions whose `metadata_key` has this value
+    /// (requires `metadata_key`)
+    pub metadata_value: Option<String>,
+}
+
+/// Paginated list of transactions.
+///
+/// # JSON Example
+///
+/// ```json
+/// {
+///   "data": [
+///     {
+///       "id": "770e8400-e29b-41d4-a716-446655440002",
+///       "transaction_type": "credit",
+///       "from_account_id": null,
+///       "to_account_id": "550e8400-e29b-41d4-a716-446655440000",
+///       "amount_cents": 50000,
+///       "currency": "USD",
+///       "description": "Initial deposit",
+///       "status": "completed",
+///       "created_at": "2025-12-21T19:30:00Z"
+///     }
+///   ],
+///   "has_more": true,
+///   "next_cursor": "770e8400-e29b-41d4-a716-446655440002"
+/// }
+/// ```
+pub type TransactionListResponse = ListResponse<TransactionResponse>;
+
+#[cfg(test)]
+mod tests {
+    use super::TransactionStatus::{self, *};
+
+    const ALL: [TransactionStatus; 9] = [
+        Pending, Processing, Completed, Failed, Reversed, Canceled, Captured, Voided, Expired,
+    ];
+
+    #[test]
+    fn follows_transition_table() {
+        // Every allowed transition; all other pairs must be rejected
+        let table: &[(TransactionStatus, &[TransactionStatus])] = &[
+            (
+                Pending,
+                &[
+                    Processing, Completed, Failed, Canceled, Captured, Voided, Expired,
+                ],
+            ),
+            (Processing, &[Pending, Completed, Failed]),
+            (Completed, &[Reversed]),
+            (Failed, &[]),
+            (Reversed, &[]),
+            (Canceled, &[]),
+            (Captured, &[]),
+            (Voided, &[]),
+            (Expired, &[]),
+        ];
+
+        for (from, allowed) in table {
+            for to in ALL {
+                assert_eq!(
+                    from.can_transition_to(to),
+                    allowed.contains(&to),
+                    "{} -> {}",
+                    from,
+                    to
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn round_trips_through_strings() {
+        for status in ALL {
+            assert_eq!(status.as_str().parse::<TransactionStatus>(), Ok(status));
         }
+        assert!("Pending".parse::<TransactionStatus>().is_err());
     }
 }
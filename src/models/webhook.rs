@@ -7,8 +7,11 @@
 //!
 //! 1. Business registers a webhook endpoint via `POST /api/v1/webhooks`
 //! 2. System generates a secret for HMAC signature verification
-//! 3. When transactions occur, system sends webhook with signed payload
-//! 4. Business verifies signature using the secret
+//! 3. When transactions occur, system queues a webhook event in the same
+//!    database transaction
+//! 4. Background dispatcher delivers the signed payload, retrying failures
+//!    with exponential backoff
+//! 5. Business verifies signature using the secret
 //!
 //! # Security
 //!
@@ -21,7 +24,56 @@ use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use uuid::Uuid;
 
-use crate::models::transaction::Transaction;
+use super::pagination::ListResponse;
+
+use crate::models::{
+    account::{Account, AccountStatus},
+    fx::FxRate,
+    metadata::Metadata,
+    transaction::{Transaction, TransactionStatus},
+};
+
+/// Event types an endpoint can subscribe to.
+///
+/// A new transaction sends `transaction.{transaction_type}`; each later
+/// status change sends `transaction.{status}`.
+pub const EVENT_TYPES: &[&str] = &[
+    "transaction.credit",
+    "transaction.debit",
+    "transaction.transfer",
+    "transaction.opening_balance",
+    "transaction.reversal",
+    "transaction.authorization",
+    "transaction.capture",
+    "transaction.pending",
+    "transaction.processing",
+    "transaction.completed",
+    "transaction.failed",
+    "transaction.reversed",
+    "transaction.canceled",
+    "transaction.captured",
+    "transaction.voided",
+    "transaction.expired",
+    "account.created",
+    "account.status_changed",
+    "webhook_endpoint.disabled",
+    "webhook.test",
+];
+
+/// Subscription that matches every event type.
+pub const ALL_EVENTS: &str = "*";
+
+/// Whether a subscription pattern matches an event type.
+///
+/// Patterns are exact event types, `*` (everything) or `category.*`
+/// (e.g. `transaction.*` matches `transaction.debit`).
+pub fn event_type_matches(pattern: &str, event_type: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some("") => true,
+        Some(prefix) if prefix.ends_with('.') => event_type.starts_with(prefix),
+        _ => pattern == event_type,
+    }
+}
 
 /// Webhook endpoint registered by a business.
 ///
@@ -33,6 +85,28 @@ use crate::models::transaction::Transaction;
 ///
 /// The `secret` is stored in plaintext (required for HMAC generation)
 /// but never returned in list/get operations for security.
+///
+/// # Secret Rotation
+///
+/// After a rotation, the replaced secret is kept in `previous_secret` and
+/// webhooks are signed with both until `previous_secret_expires_at`.
+///
+/// # Subscriptions
+///
+/// `enabled_events` lists the event types (or wildcards) the endpoint
+/// receives; see `subscribes_to`.
+///
+/// # Ordered Delivery
+///
+/// With `ordered_delivery`, events that touch the same account are
+/// delivered one at a time in commit order: an event is held until every
+/// earlier event sharing an account is delivered or dead.
+///
+/// # Disabling
+///
+/// `is_active = false` with `disabled_at` set means the endpoint is disabled
+/// (automatically after sustained failures, or via the API) and can be
+/// re-enabled. `is_active = false` without `disabled_at` means it was deleted.
 #[derive(Debug, Clone, FromRow, Serialize)]
 pub struct WebhookEndpoint {
     pub id: Uuid,
@@ -41,6 +115,28 @@ pub struct WebhookEndpoint {
     pub secret: String,
     pub is_active: bool,
     pub created_at: DateTime<Utc>,
+    pub previous_secret: Option<String>,
+    pub previous_secret_expires_at: Option<DateTime<Utc>>,
+    pub enabled_events: Vec<String>,
+
+    /// Failed delivery attempts since the last successful one
+    pub consecutive_failures: i32,
+
+    /// First failed attempt of the current failure streak
+    pub failing_since: Option<DateTime<Utc>>,
+
+    pub disabled_at: Option<DateTime<Utc>>,
+    pub disabled_reason: Option<String>,
+    pub ordered_delivery: bool,
+}
+
+impl WebhookEndpoint {
+    /// Whether this endpoint should receive events of the given type.
+    pub fn subscribes_to(&self, event_type: &str) -> bool {
+        self.enabled_events
+            .iter()
+            .any(|pattern| event_type_matches(pattern, event_type))
+    }
 }
 
 /// Request to register a new webhook endpoint.
@@ -49,7 +145,9 @@ pub struct WebhookEndpoint {
 ///
 /// ```json
 /// {
-///   "url": "https://example.com/webhook"
+///   "url": "https://example.com/webhook",
+///   "enabled_events": ["transaction.debit", "account.*"],
+///   "ordered_delivery": true
 /// }
 /// ```
 ///
@@ -57,17 +155,73 @@ pub struct WebhookEndpoint {
 ///
 /// - URL must be valid HTTPS (HTTP allowed for localhost in development)
 /// - URL must not exceed 2048 characters
+/// - `enabled_events`: Optional, defaults to `["*"]`; each entry must be a
+///   known event type, `*` or `category.*`
 #[derive(Debug, Deserialize)]
 pub struct WebhookEndpointRequest {
     pub url: String,
+
+    /// Event types to deliver to this endpoint
+    #[serde(default = "default_enabled_events")]
+    pub enabled_events: Vec<String>,
+
+    /// Deliver events of the same account one at a time, in commit order
+    #[serde(default)]
+    pub ordered_delivery: bool,
+}
+
+/// Default subscription when `enabled_events` is not specified.
+fn default_enabled_events() -> Vec<String> {
+    vec![ALL_EVENTS.to_string()]
+}
+
+/// Request to update a webhook endpoint.
+///
+/// # Example
+///
+/// ```json
+/// {
+///   "is_active": true,
+///   "ordered_delivery": true
+/// }
+/// ```
+///
+/// Fields that are omitted are left unchanged; at least one is required.
+/// Re-enabling an endpoint resets its failure streak; events still pending
+/// from before it was disabled are delivered again.
+#[derive(Debug, Deserialize)]
+pub struct UpdateWebhookEndpointRequest {
+    pub is_active: Option<bool>,
+    pub ordered_delivery: Option<bool>,
+}
+
+/// Request to rotate the secret of a webhook endpoint.
+///
+/// The body is optional; without it the default grace period applies.
+///
+/// # Example
+///
+/// ```json
+/// {
+///   "grace_period_seconds": 3600
+/// }
+/// ```
+///
+/// # Validation
+///
+/// - `grace_period_seconds`: 0 to 604800 (7 days), defaults to 86400 (24 hours)
+/// - 0 invalidates the old secret immediately (e.g. after a leak)
+#[derive(Debug, Default, Deserialize)]
+pub struct RotateSecretRequest {
+    pub grace_period_seconds: Option<i64>,
 }
 
 /// Response when registering or retrieving a webhook endpoint.
 ///
 /// # Security Note
 ///
-/// The `secret` field is ONLY included when creating a new endpoint.
-/// It is never returned in list/get operations.
+/// The `secret` field is ONLY included when creating a new endpoint or
+/// rotating its secret. It is never returned in list/get operations.
 ///
 /// # Example (Create Response)
 ///
@@ -76,28 +230,59 @@ pub struct WebhookEndpointRequest {
 ///   "id": "550e8400-e29b-41d4-a716-446655440000",
 ///   "url": "https://example.com/webhook",
 ///   "secret": "a1b2c3d4e5f6...",
+///   "enabled_events": ["*"],
+///   "ordered_delivery": false,
 ///   "is_active": true,
-///   "created_at": "2025-01-15T10:30:00Z"
+///   "created_at": "2025-01-15T10:30:00Z",
+///   "consecutive_failures": 0
 /// }
 /// ```
 #[derive(Debug, Serialize)]
 pub struct WebhookEndpointResponse {
     pub id: Uuid,
     pub url: String,
+    pub enabled_events: Vec<String>,
+    pub ordered_delivery: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub secret: Option<String>,
     pub is_active: bool,
     pub created_at: DateTime<Utc>,
+
+    /// Until when the previous secret is still used (only during a rotation)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub previous_secret_expires_at: Option<DateTime<Utc>>,
+
+    /// Failed delivery attempts since the last successful one
+    pub consecutive_failures: i32,
+
+    /// When the endpoint was disabled (only while disabled)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub disabled_at: Option<DateTime<Utc>>,
+
+    /// Why the endpoint was disabled (only while disabled)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub disabled_reason: Option<String>,
 }
 
 impl From<WebhookEndpoint> for WebhookEndpointResponse {
     fn from(endpoint: WebhookEndpoint) -> Self {
+        // Only report a rotation that is still in its grace period
+        let previous_secret_expires_at = endpoint
+            .previous_secret_expires_at
+            .filter(|expires_at| *expires_at > Utc::now());
+
         Self {
             id: endpoint.id,
             url: endpoint.url,
+            enabled_events: endpoint.enabled_events,
+            ordered_delivery: endpoint.ordered_delivery,
             secret: None, // Never include secret by default
             is_active: endpoint.is_active,
             created_at: endpoint.created_at,
+            previous_secret_expires_at,
+            consecutive_failures: endpoint.consecutive_failures,
+            disabled_at: endpoint.disabled_at,
+            disabled_reason: endpoint.disabled_reason,
         }
     }
 }
@@ -114,39 +299,257 @@ impl WebhookEndpointResponse {
 ///
 /// # Database Table
 ///
-/// Maps to the `webhook_events` table. Tracks every webhook delivery attempt,
-/// including the payload sent, HTTP response status, and any error messages.
+/// Maps to the `webhook_events` table, which doubles as the delivery outbox.
+/// Events are inserted as `pending` in the same database transaction as the
+/// money movement; the dispatcher then records every delivery attempt
+/// (HTTP response status, body or error message) on the same row.
 #[derive(Debug)]
 pub struct NewWebhookEvent {
     pub id: Uuid,
     pub webhook_endpoint_id: Uuid,
-    pub transaction_id: Uuid,
+    pub event_type: String,
+
+    /// Transaction the event is about (NULL for non-transaction events)
+    pub transaction_id: Option<Uuid>,
+
+    /// Position in commit order, shared by all endpoints' copies of the event
+    pub sequence: i64,
+
+    /// Accounts the event is about, used for ordered delivery
+    pub account_ids: Vec<Uuid>,
+
     pub payload: serde_json::Value,
-    pub response_status: Option<i32>,
-    pub response_body: Option<String>,
 }
 
 impl NewWebhookEvent {
-    /// Create a new webhook event record.
+    /// Create a new pending webhook event record.
     pub fn new(
         id: Uuid,
         webhook_endpoint_id: Uuid,
-        transaction_id: Uuid,
+        event_type: String,
+        transaction_id: Option<Uuid>,
+        sequence: i64,
+        account_ids: Vec<Uuid>,
         payload: serde_json::Value,
-        response_status: Option<i32>,
-        response_body: Option<String>,
     ) -> Self {
         Self {
             id,
             webhook_endpoint_id,
+            event_type,
             transaction_id,
+            sequence,
+            account_ids,
             payload,
-            response_status,
-            response_body,
         }
     }
 }
 
+/// A pending webhook event claimed by the dispatcher for delivery.
+///
+/// Joins the event with the endpoint it is addressed to, so the dispatcher
+/// has everything needed to sign and send it.
+#[derive(Debug, Clone, FromRow)]
+pub struct WebhookDelivery {
+    pub event_id: Uuid,
+    pub webhook_endpoint_id: Uuid,
+    pub url: String,
+    pub secret: String,
+
+    /// Secret replaced by a rotation, while its grace period lasts
+    pub previous_secret: Option<String>,
+
+    pub payload: serde_json::Value,
+
+    /// Delivery attempts made before this one
+    pub attempt_count: i32,
+
+    /// `attempt_count` when the event was last redelivered; the retry
+    /// schedule counts from here
+    pub attempts_before_redelivery: i32,
+}
+
+/// Delivery status of a webhook event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookEventStatus {
+    /// Waiting for its first delivery or a retry
+    Pending,
+
+    /// Receiver answered with a 2xx status
+    Delivered,
+
+    /// All delivery attempts failed
+    Dead,
+}
+
+impl WebhookEventStatus {
+    /// String form stored in `webhook_events.status`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebhookEventStatus::Pending => "pending",
+            WebhookEventStatus::Delivered => "delivered",
+            WebhookEventStatus::Dead => "dead",
+        }
+    }
+}
+
+/// A webhook event from the `webhook_events` table.
+///
+/// The response fields describe the most recent delivery attempt.
+#[derive(Debug, Clone, FromRow)]
+pub struct WebhookEvent {
+    pub id: Uuid,
+    pub webhook_endpoint_id: Uuid,
+    pub event_type: String,
+    pub transaction_id: Option<Uuid>,
+    pub sequence: i64,
+    pub payload: serde_json::Value,
+    pub status: String,
+    pub attempt_count: i32,
+    pub next_attempt_at: DateTime<Utc>,
+    pub last_attempt_at: Option<DateTime<Utc>>,
+    pub delivered_at: Option<DateTime<Utc>>,
+    pub response_status: Option<i32>,
+    pub response_body: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Webhook event as returned by the delivery log API.
+///
+/// # Example
+///
+/// ```json
+/// {
+///   "id": "bb0e8400-e29b-41d4-a716-446655440006",
+///   "webhook_endpoint_id": "aa0e8400-e29b-41d4-a716-446655440005",
+///   "event_type": "transaction.credit",
+///   "transaction_id": "770e8400-e29b-41d4-a716-446655440002",
+///   "sequence": 1042,
+///   "status": "pending",
+///   "attempt_count": 2,
+///   "next_attempt_at": "2025-01-15T10:31:30Z",
+///   "last_attempt_at": "2025-01-15T10:30:30Z",
+///   "delivered_at": null,
+///   "response_status": 503,
+///   "response_body": "Service Unavailable",
+///   "payload": { "event_type": "transaction.credit", ... },
+///   "created_at": "2025-01-15T10:30:00Z"
+/// }
+/// ```
+#[derive(Debug, Serialize)]
+pub struct WebhookEventResponse {
+    pub id: Uuid,
+    pub webhook_endpoint_id: Uuid,
+    pub event_type: String,
+    pub transaction_id: Option<Uuid>,
+    pub sequence: i64,
+    pub status: String,
+    pub attempt_count: i32,
+
+    /// When the next attempt is due (only set while `pending`)
+    pub next_attempt_at: Option<DateTime<Utc>>,
+
+    pub last_attempt_at: Option<DateTime<Utc>>,
+    pub delivered_at: Option<DateTime<Utc>>,
+
+    /// HTTP status of the last attempt (null on network errors)
+    pub response_status: Option<i32>,
+
+    /// Response body or error message of the last attempt
+    pub response_body: Option<String>,
+
+    /// Payload exactly as sent to the endpoint
+    pub payload: serde_json::Value,
+
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<WebhookEvent> for WebhookEventResponse {
+    fn from(event: WebhookEvent) -> Self {
+        let next_attempt_at =
+            (event.status == WebhookEventStatus::Pending.as_str()).then_some(event.next_attempt_at);
+
+        Self {
+            id: event.id,
+            webhook_endpoint_id: event.webhook_endpoint_id,
+            event_type: event.event_type,
+            transaction_id: event.transaction_id,
+            sequence: event.sequence,
+            status: event.status,
+            attempt_count: event.attempt_count,
+            next_attempt_at,
+            last_attempt_at: event.last_attempt_at,
+            delivered_at: event.delivered_at,
+            response_status: event.response_status,
+            response_body: event.response_body,
+            payload: event.payload,
+            created_at: event.created_at,
+        }
+    }
+}
+
+/// Result of a test webhook, returned synchronously.
+///
+/// # Example
+///
+/// ```json
+/// {
+///   "event_id": "dd0e8400-e29b-41d4-a716-446655440008",
+///   "success": false,
+///   "response_status": 401,
+///   "response_body": "invalid signature",
+///   "latency_ms": 84
+/// }
+/// ```
+#[derive(Debug, Serialize)]
+pub struct WebhookTestResponse {
+    /// ID of the recorded `webhook.test` event (also sent as `X-Webhook-Event-Id`)
+    pub event_id: Uuid,
+
+    /// Whether the receiver answered with a 2xx status
+    pub success: bool,
+
+    /// HTTP status of the response (null on network errors)
+    pub response_status: Option<i32>,
+
+    /// Response body (truncated) or error message
+    pub response_body: Option<String>,
+
+    /// Time until the response was received, in milliseconds
+    pub latency_ms: u64,
+}
+
+/// Query parameters for listing the events of a webhook endpoint.
+///
+/// # Example
+///
+/// ```text
+/// GET /api/v1/webhooks/{id}/events?status=dead&limit=50
+/// ```
+///
+/// # Pagination
+///
+/// Results are ordered newest first by `(created_at, id)`. To fetch the next
+/// page, pass the `next_cursor` of the previous response as `starting_after`.
+///
+/// # Validation
+///
+/// - `limit`: 1 to 100, defaults to 25
+#[derive(Debug, Deserialize)]
+pub struct WebhookEventListQuery {
+    /// Maximum number of events to return
+    pub limit: Option<i64>,
+
+    /// Return events strictly older than this event ID (cursor)
+    pub starting_after: Option<Uuid>,
+
+    /// Only return events with this delivery status
+    pub status: Option<WebhookEventStatus>,
+}
+
+/// Paginated list of webhook events, newest first.
+pub type WebhookEventListResponse = ListResponse<WebhookEventResponse>;
+
 /// Webhook payload sent to the registered endpoint.
 ///
 /// # Format
@@ -157,8 +560,9 @@ impl NewWebhookEvent {
 ///
 /// ```json
 /// {
-///   "event_type": "transaction.completed",
+///   "event_type": "transaction.transfer",
 ///   "event_id": "550e8400-e29b-41d4-a716-446655440000",
+///   "sequence": 1042,
 ///   "created_at": "2025-01-15T10:30:00Z",
 ///   "data": {
 ///     "transaction": {
@@ -176,36 +580,53 @@ impl NewWebhookEvent {
 /// # Signature Verification
 ///
 /// The webhook includes an `X-Webhook-Signature` header with format:
-/// `sha256=<hex_encoded_hmac>`
+/// `t=<unix_seconds>,v1=<hex_encoded_hmac>`
 ///
-/// Clients should verify this by computing HMAC-SHA256(secret, json_body)
+/// Clients should verify this by computing
+/// HMAC-SHA256(secret, "{t}.{json_body}") and checking that `t` is recent
+/// (see `webhook_signature::verify`)
 #[derive(Debug, Serialize, Deserialize)]
 pub struct WebhookPayload {
-    /// Type of event (always "transaction.completed" in this phase)
+    /// Type of event (one of `EVENT_TYPES`)
     pub event_type: String,
 
     /// Unique identifier for this webhook event
     pub event_id: Uuid,
 
+    /// Increases in commit order for events of the same account (not
+    /// contiguous); the same for every endpoint receiving the event. Always
+    /// 0 for `webhook.test` pings, which take no number
+    pub sequence: i64,
+
     /// When the event was created
     pub created_at: DateTime<Utc>,
 
-    /// Event data containing transaction details
+    /// Event data containing the affected resource
     pub data: WebhookData,
 }
 
 /// Data portion of the webhook payload.
-#[derive(Debug, Serialize, Deserialize)]
-pub struct WebhookData {
+///
+/// Serialized as an object keyed by the resource kind, e.g.
+/// `{"transaction": {...}}` or `{"account": {...}}`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookData {
     /// Transaction that triggered the webhook
-    pub transaction: TransactionWebhookData,
+    Transaction(TransactionWebhookData),
+
+    /// Account that triggered the webhook
+    Account(AccountWebhookData),
+
+    /// Webhook endpoint that triggered the webhook
+    WebhookEndpoint(WebhookEndpointWebhookData),
 }
 
 /// Transaction data included in webhook payload.
 ///
 /// This is a subset of the full Transaction model, containing
 /// only the fields relevant for webhook consumers.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TransactionWebhookData {
     pub id: Uuid,
     #[serde(rename = "type")]
@@ -215,8 +636,23 @@ pub struct TransactionWebhookData {
     pub amount_cents: i64,
     pub currency: String,
     pub description: Option<String>,
-    pub status: String,
+    pub metadata: Metadata,
+    pub status: TransactionStatus,
     pub created_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reversed_transaction_id: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub authorization_id: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fx_quote_id: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub destination_amount_cents: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub destination_currency: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fx_rate: Option<FxRate>,
 }
 
 impl From<Transaction> for TransactionWebhookData {
@@ -229,22 +665,86 @@ impl From<Transaction> for TransactionWebhookData {
             amount_cents: t.amount_cents,
             currency: t.currency,
             description: t.description,
+            metadata: t.metadata.0,
             status: t.status,
             created_at: t.created_at,
+            reversed_transaction_id: t.reversed_transaction_id,
+            authorization_id: t.authorization_id,
+            expires_at: t.expires_at,
+            fx_quote_id: t.fx_quote_id,
+            destination_amount_cents: t.destination_amount_cents,
+            destination_currency: t.destination_currency,
+            fx_rate: t.fx_rate,
+        }
+    }
+}
+
+/// Account data included in webhook payload.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountWebhookData {
+    pub id: Uuid,
+    pub account_name: String,
+    pub balance_cents: i64,
+    pub available_balance_cents: i64,
+    pub currency: String,
+    pub status: AccountStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub external_reference: Option<String>,
+    pub metadata: Metadata,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<Account> for AccountWebhookData {
+    fn from(a: Account) -> Self {
+        Self {
+            available_balance_cents: a.available_balance_cents(),
+            id: a.id,
+            account_name: a.account_name,
+            balance_cents: a.balance_cents,
+            currency: a.currency,
+            status: a.status,
+            external_reference: a.external_reference,
+            metadata: a.metadata.0,
+            created_at: a.created_at,
+        }
+    }
+}
+
+/// Webhook endpoint data included in webhook payload.
+///
+/// Never includes the endpoint's secret.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookEndpointWebhookData {
+    pub id: Uuid,
+    pub url: String,
+    pub is_active: bool,
+    pub consecutive_failures: i32,
+    pub disabled_at: Option<DateTime<Utc>>,
+    pub disabled_reason: Option<String>,
+}
+
+impl From<WebhookEndpoint> for WebhookEndpointWebhookData {
+    fn from(e: WebhookEndpoint) -> Self {
+        Self {
+            id: e.id,
+            url: e.url,
+            is_active: e.is_active,
+            consecutive_failures: e.consecutive_failures,
+            disabled_at: e.disabled_at,
+            disabled_reason: e.disabled_reason,
         }
     }
 }
 
 impl WebhookPayload {
-    /// Create a new webhook payload for a transaction event.
-    pub fn new(event_id: Uuid, transaction: Transaction) -> Self {
+    /// Create a new webhook payload.
+    pub fn new(event_id: Uuid, event_type: String, sequence: i64, data: WebhookData) -> Self {
         Self {
-            event_type: "transaction.completed".to_string(),
+            event_type,
             event_id,
+            sequence,
             created_at: Utc::now(),
-            data: WebhookData {
-                transaction: transaction.into(),
-            },
+            data,
         }
     }
 }
@@ -0,0 +1,759 @@
+//! Account service - Business logic for account lifecycle.
+//!
+//! This service handles:
+//! - Account creation (with ISO 4217 currency validation)
+//! - Recording opening balances as real transactions
+//! - Account updates: rename, metadata, external reference and lifecycle
+//!   status changes (freeze, unfreeze, close)
+//! - Listing accounts with filters, sorting and cursor pagination
+//!
+//! # Opening Balances
+//!
+//! An account is always inserted with a zero balance. A non-zero
+//! `initial_balance_cents` is then applied through the same credit path as
+//! `transaction_service::execute_credit`, in the same database transaction,
+//! so it has a transaction row, ledger postings and a webhook.
+
+use crate::{
+    db::DbPool,
+    error::AppError,
+    models::{
+        account::{
+            Account, AccountListQuery, AccountStatus, CreateAccountRequest, UpdateAccountRequest,
+        },
+        currency::Currency,
+        metadata, pagination,
+    },
+};
+use serde_json::json;
+use sqlx::{PgConnection, types::Json};
+use uuid::Uuid;
+
+use super::{transaction_service, webhook_service};
+
+/// Maximum length of an account's `external_reference`.
+const MAX_EXTERNAL_REFERENCE_LENGTH: usize = 255;
+
+/// Validate an external reference.
+fn validate_external_reference(reference: &str) -> Result<(), AppError> {
+    if reference.is_empty() || reference.chars().count() > MAX_EXTERNAL_REFERENCE_LENGTH {
+        return Err(AppError::InvalidRequest(format!(
+            "external_reference must be 1 to {} characters",
+            MAX_EXTERNAL_REFERENCE_LENGTH
+        )));
+    }
+    Ok(())
+}
+
+/// Turn a violation of the per-business unique external reference index
+/// into `ExternalReferenceConflict`.
+fn map_external_reference_conflict(e: sqlx::Error, reference: Option<&str>) -> AppError {
+    match e {
+        sqlx::Error::Database(ref db)
+            if db.constraint() == Some("idx_accounts_external_reference") =>
+        {
+            AppError::ExternalReferenceConflict(reference.unwrap_or_default().to_string())
+        }
+        e => e.into(),
+    }
+}
+
+/// Read an account inside an open database transaction.
+async fn get_account_in(conn: &mut PgConnection, account_id: Uuid) -> Result<Account, AppError> {
+    let account = sqlx::query_as::<_, Account>(
+        r#"
+        SELECT id, api_key_id, account_name, balance_cents, held_cents, currency, status, status_changed_at, metadata, external_reference, created_at, updated_at
+        FROM accounts
+        WHERE id = $1
+        "#,
+    )
+    .bind(account_id)
+    .fetch_one(conn)
+    .await?;
+
+    Ok(account)
+}
+
+/// Create a new account, recording any opening balance as a transaction.
+///
+/// # Process
+///
+/// 1. Validate the currency, opening balance, external reference and metadata
+/// 2. Start database transaction
+/// 3. Reserve the idempotency key (if given), returning the account already
+///    created with it
+/// 4. Insert account with zero balance and queue `account.created` webhooks
+/// 5. Record `opening_balance` transaction (if non-zero)
+/// 6. Commit (the opening balance's webhook events are queued with it)
+///
+/// # Idempotency
+///
+/// Keys are scoped per business and work like transaction keys (see
+/// `transaction_service::lock_idempotency_key`). A replay returns the
+/// account as it is now, which may differ from the first response if it
+/// has changed since.
+///
+/// # Errors
+///
+/// - `InvalidRequest`: Currency isn't an ISO 4217 code, opening balance is
+///   negative, or external reference or metadata are invalid
+/// - `ExternalReferenceConflict`: Another account of the business has the
+///   external reference
+/// - `IdempotencyKeyConflict`: Key reused with a different payload
+/// - `RequestInProgress`: A request with the same key is still running
+/// - `Database`: Database error occurred
+pub async fn create_account(
+    pool: &DbPool,
+    api_key_id: Uuid,
+    request: CreateAccountRequest,
+) -> Result<Account, AppError> {
+    // Stored in canonical (uppercase) form
+    let currency = Currency::from_code(&request.currency).ok_or_else(|| {
+        AppError::InvalidRequest(format!(
+            "Unsupported currency '{}': expected an ISO 4217 code such as USD or EUR",
+            request.currency
+        ))
+    })?;
+
+    if request.initial_balance_cents < 0 {
+        return Err(AppError::InvalidRequest(
+            "initial_balance_cents must not be negative".to_string(),
+        ));
+    }
+
+    if let Some(reference) = &request.external_reference {
+        validate_external_reference(reference)?;
+    }
+    metadata::validate(&request.metadata).map_err(AppError::InvalidRequest)?;
+
+    // Fingerprint the payload for idempotency checks
+    let fingerprint = transaction_service::request_fingerprint(&json!({
+        "account_name": request.account_name,
+        "currency": currency.code,
+        "initial_balance_cents": request.initial_balance_cents,
+        "external_reference": request.external_reference,
+        "metadata": request.metadata,
+    }));
+
+    let mut tx = pool.begin().await?;
+
+    // Reserve the idempotency key (waits for a concurrent duplicate to finish)
+    if let Some(key) = &request.idempotency_key {
+        transaction_service::lock_idempotency_key(&mut tx, api_key_id, "account", key).await?;
+
+        let existing = sqlx::query_as::<_, (Uuid, Option<String>)>(
+            "SELECT id, request_fingerprint FROM accounts WHERE api_key_id = $1 AND idempotency_key = $2",
+        )
+        .bind(api_key_id)
+        .bind(key)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        if let Some((account_id, stored_fingerprint)) = existing {
+            if stored_fingerprint.as_deref() != Some(fingerprint.as_str()) {
+                return Err(AppError::IdempotencyKeyConflict);
+            }
+            return get_account_in(&mut tx, account_id).await;
+        }
+    }
+
+    let mut account = sqlx::query_as::<_, Account>(
+        r#"
+        INSERT INTO accounts (
+            api_key_id, account_name, currency, external_reference, metadata,
+            idempotency_key, request_fingerprint
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        RETURNING id, api_key_id, account_name, balance_cents, held_cents, currency, status, status_changed_at, metadata, external_reference, created_at, updated_at
+        "#,
+    )
+    // Link to authenticated business
+    .bind(api_key_id)
+    .bind(request.account_name)
+    .bind(currency.code)
+    .bind(&request.external_reference)
+    .bind(Json(&request.metadata))
+    .bind(&request.idempotency_key)
+    .bind(request.idempotency_key.as_ref().map(|_| &fingerprint))
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| match e {
+        // Only reachable if a duplicate slipped past the advisory lock
+        sqlx::Error::Database(ref db) if db.constraint() == Some("idx_accounts_idempotency") => {
+            AppError::RequestInProgress
+        }
+        e => map_external_reference_conflict(e, request.external_reference.as_deref()),
+    })?;
+
+    // Queue account.created before any opening balance event
+    webhook_service::enqueue_account_created_events(&mut tx, &account).await?;
+
+    if request.initial_balance_cents > 0 {
+        transaction_service::record_opening_balance(
+            &mut tx,
+            account.id,
+            request.initial_balance_cents,
+            api_key_id,
+        )
+        .await?;
+
+        // Re-read the account so the response reflects the credited balance
+        account = get_account_in(&mut tx, account.id).await?;
+    }
+
+    // Commit account, opening balance and its webhook events atomically
+    tx.commit().await?;
+
+    Ok(account)
+}
+
+/// Update an account of the business.
+///
+/// Only the fields present in the request change. `metadata` is merged
+/// into the stored metadata (see `models::metadata`). A request that
+/// changes nothing returns the account unchanged, so the call is safe to
+/// retry.
+///
+/// # Status Changes
+///
+/// `active` and `frozen` can change into each other and into `closed`;
+/// closing requires a zero balance (which also means no open holds).
+/// Each change queues `account.status_changed` webhooks.
+///
+/// The account row is locked while it changes, so a transaction that
+/// already locked it finishes first and later ones see the new status.
+///
+/// # Errors
+///
+/// - `InvalidRequest`: No field given, empty name, or invalid external
+///   reference or metadata (after merging)
+/// - `AccountNotFound`: Account doesn't exist or belongs to another business
+/// - `AccountClosed`: Changing the status of a closed account (closed is final)
+/// - `AccountNotEmpty`: Closing an account whose balance isn't zero
+/// - `ExternalReferenceConflict`: Another account of the business has the
+///   external reference
+/// - `Database`: Database error occurred
+pub async fn update_account(
+    pool: &DbPool,
+    account_id: Uuid,
+    api_key_id: Uuid,
+    request: UpdateAccountRequest,
+) -> Result<Account, AppError> {
+    let UpdateAccountRequest {
+        account_name,
+        status,
+        external_reference,
+        metadata: metadata_update,
+    } = request;
+
+    if account_name.is_none()
+        && status.is_none()
+        && external_reference.is_none()
+        && metadata_update.is_none()
+    {
+        return Err(AppError::InvalidRequest(
+            "At least one of account_name, status, external_reference or metadata is required"
+                .to_string(),
+        ));
+    }
+
+    if account_name
+        .as_deref()
+        .is_some_and(|name| name.trim().is_empty())
+    {
+        return Err(AppError::InvalidRequest(
+            "account_name must not be empty".to_string(),
+        ));
+    }
+
+    if let Some(Some(reference)) = &external_reference {
+        validate_external_reference(reference)?;
+    }
+
+    let mut tx = pool.begin().await?;
+
+    let account = sqlx::query_as::<_, Account>(
+        r#"
+        SELECT id, api_key_id, account_name, balance_cents, held_cents, currency, status, status_changed_at, metadata, external_reference, created_at, updated_at
+        FROM accounts
+        WHERE id = $1 AND api_key_id = $2
+        FOR UPDATE
+        "#,
+    )
+    .bind(account_id)
+    .bind(api_key_id)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or(AppError::AccountNotFound)?;
+
+    let account_name = account_name.unwrap_or_else(|| account.account_name.clone());
+    let external_reference =
+        external_reference.unwrap_or_else(|| account.external_reference.clone());
+
+    let mut merged_metadata = account.metadata.0.clone();
+    if let Some(update) = metadata_update {
+        metadata::merge(&mut merged_metadata, update);
+        metadata::validate(&merged_metadata).map_err(AppError::InvalidRequest)?;
+    }
+
+    let status = status.unwrap_or(account.status);
+    let status_changed = status != account.status;
+    if status_changed {
+        if account.status == AccountStatus::Closed {
+            return Err(AppError::AccountClosed);
+        }
+        if status == AccountStatus::Closed && account.balance_cents != 0 {
+            return Err(AppError::AccountNotEmpty(account.balance_cents));
+        }
+    }
+
+    if !status_changed
+        && account_name == account.account_name
+        && external_reference == account.external_reference
+        && merged_metadata == account.metadata.0
+    {
+        return Ok(account);
+    }
+
+    let updated = sqlx::query_as::<_, Account>(
+        r#"
+        UPDATE accounts
+        SET account_name = $2,
+            status = $3,
+            status_changed_at = CASE WHEN $4 THEN NOW() ELSE status_changed_at END,
+            external_reference = $5,
+            metadata = $6,
+            updated_at = NOW()
+        WHERE id = $1
+        RETURNING id, api_key_id, account_name, balance_cents, held_cents, currency, status, status_changed_at, metadata, external_reference, created_at, updated_at
+        "#,
+    )
+    .bind(account.id)
+    .bind(&account_name)
+    .bind(status)
+    .bind(status_changed)
+    .bind(&external_reference)
+    .bind(Json(&merged_metadata))
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| map_external_reference_conflict(e, external_reference.as_deref()))?;
+
+    if status_changed {
+        webhook_service::enqueue_account_status_changed_events(&mut tx, &updated).await?;
+
+        tracing::info!(account_id = %updated.id, status = %status, "Account status changed");
+    }
+
+    tx.commit().await?;
+
+    Ok(updated)
+}
+
+/// Escape `%`, `_` and `\` so `prefix` matches literally in a `LIKE` pattern.
+fn like_prefix_pattern(prefix: &str) -> String {
+    let mut pattern = String::with_capacity(prefix.len() + 1);
+    for c in prefix.chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            pattern.push('\\');
+        }
+        pattern.push(c);
+    }
+    pattern.push('%');
+    pattern
+}
+
+/// List the accounts of the business with keyset pagination.
+///
+/// # Filters
+///
+/// - `currency`: exact match (ISO 4217 code, any case)
+/// - `name_prefix`: account name starts with the text
+/// - `min_balance_cents` / `max_balance_cents`: inclusive balance range
+/// - `created_after` / `created_before`: creation time range
+/// - `external_reference`: exact match
+/// - `metadata_key`: accounts that have the key
+/// - `metadata_key` + `metadata_value`: accounts whose key has the value
+///
+/// # Pagination
+///
+/// Pages with a keyset on `(sort column, id)` in the requested direction.
+/// The sort column is interpolated from `AccountSort::column`, never from
+/// client input, so the statement is one of a fixed set. Sorting by
+/// `balance_cents` follows live balances: an account whose balance changes
+/// between two page requests can move across the cursor.
+///
+/// One extra row is fetched to determine whether another page exists.
+///
+/// # Returns
+///
+/// The accounts on this page and whether more accounts follow.
+///
+/// # Errors
+///
+/// - `InvalidRequest`: Limit out of range, unknown currency, contradictory
+///   ranges, `metadata_value` without `metadata_key`, or a `starting_after`
+///   that is not an account of the business
+/// - `Database`: Database error occurred
+pub async fn list_accounts(
+    pool: &DbPool,
+    api_key_id: Uuid,
+    query: AccountListQuery,
+) -> Result<(Vec<Account>, bool), AppError> {
+    let limit = pagination::page_limit(query.limit).map_err(AppError::InvalidRequest)?;
+
+    let currency = query
+        .currency
+        .as_deref()
+        .map(|code| {
+            Currency::from_code(code).ok_or_else(|| {
+                AppError::InvalidRequest(format!(
+                    "Unsupported currency '{}': expected an ISO 4217 code such as USD or EUR",
+                    code
+                ))
+            })
+        })
+        .transpose()?;
+
+    if let (Some(min), Some(max)) = (query.min_balance_cents, query.max_balance_cents)
+        && min > max
+    {
+        return Err(AppError::InvalidRequest(
+            "min_balance_cents must not exceed max_balance_cents".to_string(),
+        ));
+    }
+
+    if let (Some(after), Some(before)) = (query.created_after, query.created_before)
+        && after >= before
+    {
+        return Err(AppError::InvalidRequest(
+            "created_after must be earlier than created_before".to_string(),
+        ));
+    }
+
+    if query.metadata_value.is_some() && query.metadata_key.is_none() {
+        return Err(AppError::InvalidRequest(
+            "metadata_value requires metadata_key".to_string(),
+        ));
+    }
+
+    // An unknown cursor would otherwise silently return an empty last page
+    if let Some(cursor) = query.starting_after {
+        let exists: bool = sqlx::query_scalar(
+            "SELECT EXISTS(SELECT 1 FROM accounts WHERE id = $1 AND api_key_id = $2)",
+        )
+        .bind(cursor)
+        .bind(api_key_id)
+        .fetch_one(pool)
+        .await?;
+
+        if !exists {
+            return Err(AppError::InvalidRequest(
+                "starting_after is not one of your accounts".to_string(),
+            ));
+        }
+    }
+
+    let sort = query.sort.column();
+    let order = query.order.as_sql();
+    let after_cursor = query.order.after_cursor();
+
+    // NULL parameters disable their filter
+    let sql = format!(
+        r#"
+        SELECT id, api_key_id, account_name, balance_cents, held_cents, currency, status, status_changed_at, metadata, external_reference, created_at, updated_at
+        FROM accounts
+        WHERE api_key_id = $1
+          AND ($2::text IS NULL OR external_reference = $2)
+          AND ($3::text IS NULL OR metadata ? $3)
+          AND ($4::text IS NULL OR metadata @> jsonb_build_object($3::text, $4::text))
+          AND ($5::text IS NULL OR currency = $5)
+          AND ($6::text IS NULL OR account_name LIKE $6)
+          AND ($7::bigint IS NULL OR balance_cents >= $7)
+          AND ($8::bigint IS NULL OR balance_cents <= $8)
+          AND ($9::timestamptz IS NULL OR created_at >= $9)
+          AND ($10::timestamptz IS NULL OR created_at < $10)
+          AND ($11::uuid IS NULL OR ({sort}, id) {after_cursor} (
+               SELECT {sort}, id FROM accounts
+               WHERE id = $11 AND api_key_id = $1
+          ))
+        ORDER BY {sort} {order}, id {order}
+        LIMIT $12
+        "#
+    );
+
+    let mut accounts = sqlx::query_as::<_, Account>(&sql)
+        // Only fetch accounts for authenticated business
+        .bind(api_key_id)
+        .bind(query.external_reference)
+        .bind(query.metadata_key)
+        .bind(query.metadata_value)
+        .bind(currency.map(|c| c.code))
+        .bind(query.name_prefix.as_deref().map(like_prefix_pattern))
+        .bind(query.min_balance_cents)
+        .bind(query.max_balance_cents)
+        .bind(query.created_after)
+        .bind(query.created_before)
+        .bind(query.starting_after)
+        .bind(limit + 1)
+        .fetch_all(pool)
+        .await?;
+
+    let has_more = accounts.len() as i64 > limit;
+    accounts.truncate(limit as usize);
+
+    Ok((accounts, has_more))
+}
+
+#[cfg(all(test, feature = "db-tests"))]
+mod tests {
+    use serde_json::{Value, json};
+
+    use super::*;
+    use crate::{
+        models::{metadata::Metadata, transaction::TransferRequest},
+        services::{
+            test_support::{self, balances, create_business, create_webhook_endpoint},
+            transaction_service,
+        },
+    };
+
+    async fn create_with_metadata(pool: &DbPool, api_key_id: Uuid, metadata: Value) -> Uuid {
+        let request: CreateAccountRequest = serde_json::from_value(json!({
+            "account_name": "Test account",
+            "metadata": metadata,
+        }))
+        .unwrap();
+
+        create_account(pool, api_key_id, request).await.unwrap().id
+    }
+
+    async fn list_ids(pool: &DbPool, api_key_id: Uuid, query: Value) -> Vec<Uuid> {
+        let query: AccountListQuery = serde_json::from_value(query).unwrap();
+        let (accounts, _) = list_accounts(pool, api_key_id, query).await.unwrap();
+
+        let mut ids: Vec<Uuid> = accounts.into_iter().map(|account| account.id).collect();
+        ids.sort();
+        ids
+    }
+
+    #[sqlx::test]
+    async fn filters_accounts_by_metadata(pool: DbPool) {
+        let business = create_business(&pool).await;
+        let gold = create_with_metadata(&pool, business, json!({ "tier": "gold" })).await;
+        let silver = create_with_metadata(&pool, business, json!({ "tier": "silver" })).await;
+        create_with_metadata(&pool, business, json!({ "region": "eu" })).await;
+
+        // Another business's account never matches
+        let other = create_business(&pool).await;
+        create_with_metadata(&pool, other, json!({ "tier": "gold" })).await;
+
+        let mut with_tier = vec![gold, silver];
+        with_tier.sort();
+        assert_eq!(
+            list_ids(&pool, business, json!({ "metadata_key": "tier" })).await,
+            with_tier
+        );
+        assert_eq!(
+            list_ids(
+                &pool,
+                business,
+                json!({ "metadata_key": "tier", "metadata_value": "gold" })
+            )
+            .await,
+            vec![gold]
+        );
+        assert!(
+            list_ids(
+                &pool,
+                business,
+                json!({ "metadata_key": "tier", "metadata_value": "bronze" })
+            )
+            .await
+            .is_empty()
+        );
+    }
+
+    #[sqlx::test]
+    async fn metadata_value_requires_a_key(pool: DbPool) {
+        let business = create_business(&pool).await;
+        let query: AccountListQuery =
+            serde_json::from_value(json!({ "metadata_value": "gold" })).unwrap();
+
+        let result = list_accounts(&pool, business, query).await;
+        assert!(matches!(result, Err(AppError::InvalidRequest(_))));
+    }
+
+    async fn set_status(
+        pool: &DbPool,
+        account_id: Uuid,
+        api_key_id: Uuid,
+        status: &str,
+    ) -> Result<Account, AppError> {
+        let request: UpdateAccountRequest =
+            serde_json::from_value(json!({ "status": status })).unwrap();
+
+        update_account(pool, account_id, api_key_id, request).await
+    }
+
+    async fn credit(pool: &DbPool, account_id: Uuid, api_key_id: Uuid) -> Result<(), AppError> {
+        transaction_service::execute_credit(
+            pool,
+            account_id,
+            100,
+            None,
+            Metadata::new(),
+            None,
+            api_key_id,
+        )
+        .await
+        .map(drop)
+    }
+
+    async fn debit(
+        pool: &DbPool,
+        account_id: Uuid,
+        amount_cents: i64,
+        api_key_id: Uuid,
+    ) -> Result<(), AppError> {
+        transaction_service::execute_debit(
+            pool,
+            account_id,
+            amount_cents,
+            None,
+            Metadata::new(),
+            None,
+            api_key_id,
+        )
+        .await
+        .map(drop)
+    }
+
+    async fn transfer(
+        pool: &DbPool,
+        from: Uuid,
+        to: Uuid,
+        api_key_id: Uuid,
+    ) -> Result<(), AppError> {
+        let request: TransferRequest = serde_json::from_value(json!({
+            "from_account_id": from,
+            "to_account_id": to,
+            "amount_cents": 100,
+        }))
+        .unwrap();
+
+        transaction_service::execute_transfer(pool, request, api_key_id)
+            .await
+            .map(drop)
+    }
+
+    #[sqlx::test]
+    async fn frozen_accounts_receive_but_cannot_send(pool: DbPool) {
+        let business = create_business(&pool).await;
+        let account = test_support::create_account(&pool, business, "USD", 10_000).await;
+        let other = test_support::create_account(&pool, business, "USD", 10_000).await;
+
+        let frozen = set_status(&pool, account, business, "frozen")
+            .await
+            .unwrap();
+        assert_eq!(frozen.status, AccountStatus::Frozen);
+
+        assert!(matches!(
+            debit(&pool, account, 100, business).await,
+            Err(AppError::AccountFrozen)
+        ));
+        assert!(matches!(
+            transfer(&pool, account, other, business).await,
+            Err(AppError::AccountFrozen)
+        ));
+        assert!(matches!(
+            transaction_service::execute_authorization(
+                &pool, account, 100, None, None, None, business
+            )
+            .await,
+            Err(AppError::AccountFrozen)
+        ));
+
+        credit(&pool, account, business).await.unwrap();
+        transfer(&pool, other, account, business).await.unwrap();
+        assert_eq!(balances(&pool, account).await, (10_200, 0));
+
+        // Unfrozen, it sends again
+        set_status(&pool, account, business, "active")
+            .await
+            .unwrap();
+        transfer(&pool, account, other, business).await.unwrap();
+        assert_eq!(balances(&pool, account).await, (10_100, 0));
+    }
+
+    #[sqlx::test]
+    async fn closed_accounts_neither_send_nor_receive(pool: DbPool) {
+        let business = create_business(&pool).await;
+        let account = test_support::create_account(&pool, business, "USD", 1_000).await;
+        let other = test_support::create_account(&pool, business, "USD", 10_000).await;
+
+        let result = set_status(&pool, account, business, "closed").await;
+        assert!(matches!(result, Err(AppError::AccountNotEmpty(1_000))));
+
+        debit(&pool, account, 1_000, business).await.unwrap();
+        let closed = set_status(&pool, account, business, "closed")
+            .await
+            .unwrap();
+        assert_eq!(closed.status, AccountStatus::Closed);
+
+        assert!(matches!(
+            credit(&pool, account, business).await,
+            Err(AppError::AccountClosed)
+        ));
+        assert!(matches!(
+            transfer(&pool, other, account, business).await,
+            Err(AppError::AccountClosed)
+        ));
+        assert!(matches!(
+            debit(&pool, account, 100, business).await,
+            Err(AppError::AccountClosed)
+        ));
+
+        // Closed is final
+        let result = set_status(&pool, account, business, "active").await;
+        assert!(matches!(result, Err(AppError::AccountClosed)));
+        assert_eq!(balances(&pool, account).await, (0, 0));
+    }
+
+    #[sqlx::test]
+    async fn status_changes_queue_webhook_events(pool: DbPool) {
+        let business = create_business(&pool).await;
+        create_webhook_endpoint(&pool, business).await;
+        let account = test_support::create_account(&pool, business, "USD", 0).await;
+
+        set_status(&pool, account, business, "frozen")
+            .await
+            .unwrap();
+
+        // Updates that keep the status queue none
+        set_status(&pool, account, business, "frozen")
+            .await
+            .unwrap();
+        let request: UpdateAccountRequest =
+            serde_json::from_value(json!({ "account_name": "Renamed" })).unwrap();
+        update_account(&pool, account, business, request)
+            .await
+            .unwrap();
+
+        set_status(&pool, account, business, "closed")
+            .await
+            .unwrap();
+
+        let statuses: Vec<Value> = sqlx::query_scalar(
+            r#"
+            SELECT payload -> 'data' -> 'account' -> 'status' FROM webhook_events
+            WHERE event_type = 'account.status_changed' AND $1 = ANY(account_ids)
+            ORDER BY sequence
+            "#,
+        )
+        .bind(account)
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        assert_eq!(statuses, [json!("frozen"), json!("closed")]);
+    }
+}
@@ -0,0 +1,256 @@
+//! FX service - Exchange rates and quotes for cross-currency transfers.
+//!
+//! This service handles:
+//! - Loading exchange rates from the `FX_RATES_FILE` into `fx_rates`
+//! - Quoting an amount at the current rate, locked for `FX_QUOTE_TTL_SECS`
+//! - Locking a quote for the transfer that uses it
+//!
+//! # Rates
+//!
+//! The rates file is the source of truth: every load replaces the whole
+//! `fx_rates` table in one database transaction, so a pair removed from
+//! the file can no longer be quoted. Quotes copy the rate, so reloading
+//! never changes a quote that was already given out.
+
+use std::{path::Path, path::PathBuf, time::Duration};
+
+use anyhow::Context;
+use chrono::Utc;
+use sqlx::PgConnection;
+use uuid::Uuid;
+
+use crate::{
+    db::DbPool,
+    error::AppError,
+    models::{
+        currency::Currency,
+        fx::{CreateFxQuoteRequest, FxQuote, FxRate, FxRatesFile},
+    },
+};
+
+/// How long a quote's rate is guaranteed.
+pub const FX_QUOTE_TTL_SECS: i64 = 60;
+
+/// Replace all exchange rates with the contents of a rates file.
+///
+/// # Returns
+///
+/// The number of currency pairs loaded.
+///
+/// # Errors
+///
+/// Fails without changing the stored rates if the file can't be read or
+/// parsed, lists an unknown currency, a pair of the same currency, or the
+/// same pair twice.
+pub async fn load_rates_file(pool: &DbPool, path: &Path) -> anyhow::Result<usize> {
+    let contents = tokio::fs::read_to_string(path)
+        .await
+        .with_context(|| format!("reading FX rates file {}", path.display()))?;
+    let file: FxRatesFile = serde_json::from_str(&contents)
+        .with_context(|| format!("parsing FX rates file {}", path.display()))?;
+
+    let mut pairs: Vec<(&'static str, &'static str, FxRate)> = Vec::with_capacity(file.rates.len());
+    for entry in &file.rates {
+        let source = Currency::from_code(&entry.source_currency)
+            .with_context(|| format!("unknown currency '{}'", entry.source_currency))?;
+        let destination = Currency::from_code(&entry.destination_currency)
+            .with_context(|| format!("unknown currency '{}'", entry.destination_currency))?;
+
+        anyhow::ensure!(source != destination, "rate from {} to itself", source.code);
+        anyhow::ensure!(
+            !pairs
+                .iter()
+                .any(|(s, d, _)| *s == source.code && *d == destination.code),
+            "duplicate rate {} -> {}",
+            source.code,
+            destination.code
+        );
+
+        pairs.push((source.code, destination.code, entry.rate));
+    }
+
+    let mut tx = pool.begin().await?;
+
+    sqlx::query("DELETE FROM fx_rates")
+        .execute(&mut *tx)
+        .await?;
+
+    for (source, destination, rate) in &pairs {
+        sqlx::query(
+            r#"
+            INSERT INTO fx_rates (source_currency, destination_currency, rate_e12)
+            VALUES ($1, $2, $3)
+            "#,
+        )
+        .bind(source)
+        .bind(destination)
+        .bind(rate)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+
+    Ok(pairs.len())
+}
+
+/// Spawn a background task that reloads the rates file periodically.
+///
+/// A failed reload is logged and the previously loaded rates stay in use.
+pub fn spawn_rates_reload_task(pool: DbPool, path: PathBuf, interval: Duration) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        // The first tick fires immediately; rates were just loaded at startup
+        ticker.tick().await;
+
+        loop {
+            ticker.tick().await;
+
+            if let Err(e) = load_rates_file(&pool, &path).await {
+                tracing::error!("FX rates reload failed, keeping current rates: {:?}", e);
+            }
+        }
+    });
+}
+
+/// Quote an amount at the current rate.
+///
+/// # Process
+///
+/// 1. Validate currencies and amount
+/// 2. Look up the rate for the pair
+/// 3. Convert the amount (half-even rounding at the destination minor unit)
+/// 4. Store the quote, valid for `FX_QUOTE_TTL_SECS`
+///
+/// # Errors
+///
+/// - `InvalidRequest`: Unknown currency, same currency on both sides,
+///   non-positive amount, or an amount that converts to zero or overflows
+/// - `FxRateUnavailable`: No rate is loaded for the pair
+/// - `Database`: Database error occurred
+pub async fn create_quote(
+    pool: &DbPool,
+    api_key_id: Uuid,
+    request: CreateFxQuoteRequest,
+) -> Result<FxQuote, AppError> {
+    let currency = |code: &str| {
+        Currency::from_code(code).ok_or_else(|| {
+            AppError::InvalidRequest(format!(
+                "Unsupported currency '{}': expected an ISO 4217 code",
+                code
+            ))
+        })
+    };
+    let source = currency(&request.source_currency)?;
+    let destination = currency(&request.destination_currency)?;
+
+    if source == destination {
+        return Err(AppError::InvalidRequest(
+            "source_currency and destination_currency must differ".to_string(),
+        ));
+    }
+
+    if request.source_amount_cents <= 0 {
+        return Err(AppError::InvalidRequest(
+            "source_amount_cents must be positive".to_string(),
+        ));
+    }
+
+    let rate: FxRate = sqlx::query_scalar(
+        r#"
+        SELECT rate_e12 FROM fx_rates
+        WHERE source_currency = $1 AND destination_currency = $2
+        "#,
+    )
+    .bind(source.code)
+    .bind(destination.code)
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AppError::FxRateUnavailable {
+        from: source.code.to_string(),
+        to: destination.code.to_string(),
+    })?;
+
+    let destination_amount_cents = rate
+        .convert(request.source_amount_cents, source, destination)
+        .ok_or_else(|| {
+            AppError::InvalidRequest("source_amount_cents is too large to convert".to_string())
+        })?;
+
+    if destination_amount_cents == 0 {
+        return Err(AppError::InvalidRequest(format!(
+            "source_amount_cents converts to 0 {}",
+            destination.code
+        )));
+    }
+
+    let quote = sqlx::query_as::<_, FxQuote>(
+        r#"
+        INSERT INTO fx_quotes (
+            api_key_id,
+            source_currency,
+            destination_currency,
+            rate_e12,
+            source_amount_cents,
+            destination_amount_cents,
+            expires_at
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        RETURNING *
+        "#,
+    )
+    .bind(api_key_id)
+    .bind(source.code)
+    .bind(destination.code)
+    .bind(rate)
+    .bind(request.source_amount_cents)
+    .bind(destination_amount_cents)
+    .bind(Utc::now() + chrono::Duration::seconds(FX_QUOTE_TTL_SECS))
+    .fetch_one(pool)
+    .await?;
+
+    Ok(quote)
+}
+
+/// Lock a quote for the transfer about to use it.
+///
+/// Runs inside the transfer's database transaction; the row lock makes
+/// concurrent transfers with the same quote wait, and the unique
+/// `transactions.fx_quote_id` index stops the second one.
+///
+/// # Errors
+///
+/// - `FxQuoteNotFound`: Quote doesn't exist or belongs to another business
+/// - `FxQuoteAlreadyUsed`: Another transfer already used the quote
+/// - `FxQuoteExpired`: Quote is past `expires_at`
+/// - `Database`: Database error occurred
+pub async fn lock_quote(
+    conn: &mut PgConnection,
+    quote_id: Uuid,
+    api_key_id: Uuid,
+) -> Result<FxQuote, AppError> {
+    let quote = sqlx::query_as::<_, FxQuote>(
+        "SELECT * FROM fx_quotes WHERE id = $1 AND api_key_id = $2 FOR UPDATE",
+    )
+    .bind(quote_id)
+    .bind(api_key_id)
+    .fetch_optional(&mut *conn)
+    .await?
+    .ok_or(AppError::FxQuoteNotFound)?;
+
+    let used: bool =
+        sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM transactions WHERE fx_quote_id = $1)")
+            .bind(quote.id)
+            .fetch_one(&mut *conn)
+            .await?;
+
+    if used {
+        return Err(AppError::FxQuoteAlreadyUsed);
+    }
+
+    if quote.expires_at <= Utc::now() {
+        return Err(AppError::FxQuoteExpired);
+    }
+
+    Ok(quote)
+}
@@ -0,0 +1,295 @@
+//! Ledger service - Double-entry postings underneath every transaction.
+//!
+//! This service handles:
+//! - Deriving the debit/credit postings for a transaction
+//! - Writing postings in the same database transaction as the balance update
+//! - Computing the trial balance from postings
+//!
+//! # Invariants
+//!
+//! - Postings are immutable (database trigger rejects UPDATE/DELETE)
+//! - The postings of a transaction balance per currency (deferred constraint
+//!   trigger checks this at commit)
+
+use std::collections::BTreeMap;
+
+use chrono::Utc;
+use sqlx::PgConnection;
+use uuid::Uuid;
+
+use crate::{
+    db::DbPool,
+    error::AppError,
+    models::{
+        ledger::{
+            EntryType, LedgerAccount, Posting, TrialBalanceLine, TrialBalanceResponse,
+            TrialBalanceTotal,
+        },
+        transaction::Transaction,
+    },
+};
+
+/// System account debited when money enters the system (credits).
+pub const EXTERNAL_FUNDING: &str = "external_funding";
+
+/// System account credited when money leaves the system (debits).
+pub const EXTERNAL_PAYOUT: &str = "external_payout";
+
+/// System account debited for the balance an account is opened with.
+pub const OPENING_BALANCES: &str = "opening_balances";
+
+/// System account converting between currencies in cross-currency transfers.
+pub const FX_CLEARING: &str = "fx_clearing";
+
+/// Derive the postings for a transaction.
+///
+/// # Rules
+///
+/// | Type       | Debit                | Credit              |
+/// | ---------- | -------------------- | ------------------- |
+/// | `credit`   | `external_funding`   | `to_account_id`     |
+/// | `debit`    | `from_account_id`    | `external_payout`   |
+/// | `transfer` | `from_account_id`    | `to_account_id`     |
+/// | `opening_balance` | `opening_balances` | `to_account_id` |
+/// | `capture`  | `from_account_id`    | `external_payout`   |
+///
+/// A cross-currency `transfer` posts each side in its own currency through
+/// `fx_clearing`: `from_account_id` → `fx_clearing` for `amount_cents` in
+/// `currency`, then `fx_clearing` → `to_account_id` for
+/// `destination_amount_cents` in `destination_currency`. The balance of
+/// `fx_clearing` per currency is the net FX position.
+///
+/// Authorizations only hold funds and are never posted. Reversals have no
+/// rule of their own; see `reversal_postings`.
+///
+/// # Errors
+///
+/// - `InvalidRequest`: Transaction type unknown or required account missing
+fn postings_for(transaction: &Transaction) -> Result<Vec<Posting>, AppError> {
+    let customer = |account_id: Option<Uuid>| {
+        account_id.map(LedgerAccount::Customer).ok_or_else(|| {
+            AppError::InvalidRequest(format!(
+                "Transaction {} is missing an account for its ledger postings",
+                transaction.id
+            ))
+        })
+    };
+
+    if transaction.transaction_type == "transfer"
+        && let (Some(destination_amount_cents), Some(destination_currency)) = (
+            transaction.destination_amount_cents,
+            &transaction.destination_currency,
+        )
+    {
+        let posting = |account, entry_type, amount_cents, currency: &str| Posting {
+            account,
+            entry_type,
+            amount_cents,
+            currency: currency.to_string(),
+        };
+        let source_currency = transaction.currency.as_str();
+
+        return Ok(vec![
+            posting(
+                customer(transaction.from_account_id)?,
+                EntryType::Debit,
+                transaction.amount_cents,
+                source_currency,
+            ),
+            posting(
+                LedgerAccount::System(FX_CLEARING),
+                EntryType::Credit,
+                transaction.amount_cents,
+                source_currency,
+            ),
+            posting(
+                LedgerAccount::System(FX_CLEARING),
+                EntryType::Debit,
+                destination_amount_cents,
+                destination_currency,
+            ),
+            posting(
+                customer(transaction.to_account_id)?,
+                EntryType::Credit,
+                destination_amount_cents,
+                destination_currency,
+            ),
+        ]);
+    }
+
+    let (debit, credit) = match transaction.transaction_type.as_str() {
+        "credit" => (
+            LedgerAccount::System(EXTERNAL_FUNDING),
+            customer(transaction.to_account_id)?,
+        ),
+        "debit" | "capture" => (
+            customer(transaction.from_account_id)?,
+            LedgerAccount::System(EXTERNAL_PAYOUT),
+        ),
+        "opening_balance" => (
+            LedgerAccount::System(OPENING_BALANCES),
+            customer(transaction.to_account_id)?,
+        ),
+        "transfer" => (
+            customer(transaction.from_account_id)?,
+            customer(transaction.to_account_id)?,
+        ),
+        other => {
+            return Err(AppError::InvalidRequest(format!(
+                "No ledger rule for transaction type '{}'",
+                other
+            )));
+        }
+    };
+
+    Ok(vec![
+        Posting {
+            account: debit,
+            entry_type: EntryType::Debit,
+            amount_cents: transaction.amount_cents,
+            currency: transaction.currency.clone(),
+        },
+        Posting {
+            account: credit,
+            entry_type: EntryType::Credit,
+            amount_cents: transaction.amount_cents,
+            currency: transaction.currency.clone(),
+        },
+    ])
+}
+
+/// Derive the postings for a reversal of `original`.
+///
+/// Mirrors the postings of the original transaction (debit and credit
+/// swapped) for the reversed amount, so a credit reversal is booked back
+/// against `external_funding`, a transfer reversal between the same two
+/// accounts, and so on.
+fn reversal_postings(
+    reversal: &Transaction,
+    original: &Transaction,
+) -> Result<Vec<Posting>, AppError> {
+    Ok(postings_for(original)?
+        .into_iter()
+        .map(|posting| Posting {
+            entry_type: match posting.entry_type {
+                EntryType::Debit => EntryType::Credit,
+                EntryType::Credit => EntryType::Debit,
+            },
+            amount_cents: reversal.amount_cents,
+            ..posting
+        })
+        .collect())
+}
+
+/// Write the postings for a transaction.
+///
+/// Must be called with the same database connection (transaction) that
+/// updated the balances, so postings and balances commit atomically.
+pub async fn record_postings(
+    conn: &mut PgConnection,
+    transaction: &Transaction,
+) -> Result<(), AppError> {
+    let postings = match transaction.reversed_transaction_id {
+        Some(original_id) => {
+            let original =
+                sqlx::query_as::<_, Transaction>("SELECT * FROM transactions WHERE id = $1")
+                    .bind(original_id)
+                    .fetch_one(&mut *conn)
+                    .await?;
+            reversal_postings(transaction, &original)?
+        }
+        None => postings_for(transaction)?,
+    };
+
+    for posting in postings {
+        let (account_id, system_account_code) = match posting.account {
+            LedgerAccount::Customer(id) => (Some(id), None),
+            LedgerAccount::System(code) => (None, Some(code)),
+        };
+
+        sqlx::query(
+            r#"
+            INSERT INTO ledger_entries (
+                transaction_id,
+                account_id,
+                system_account_code,
+                entry_type,
+                amount_cents,
+                currency
+            )
+            VALUES ($1, $2, $3, $4, $5, $6)
+            "#,
+        )
+        .bind(transaction.id)
+        .bind(account_id)
+        .bind(system_account_code)
+        .bind(posting.entry_type.as_str())
+        .bind(posting.amount_cents)
+        .bind(&posting.currency)
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Compute the trial balance for a business.
+///
+/// Includes every posting of every transaction that touches one of the
+/// business's accounts, including the system-account counter-sides.
+pub async fn trial_balance(
+    pool: &DbPool,
+    api_key_id: Uuid,
+) -> Result<TrialBalanceResponse, AppError> {
+    let lines = sqlx::query_as::<_, TrialBalanceLine>(
+        r#"
+        SELECT
+            le.account_id,
+            le.system_account_code,
+            le.currency,
+            SUM(CASE WHEN le.entry_type = 'debit' THEN le.amount_cents ELSE 0 END)::BIGINT AS debits_cents,
+            SUM(CASE WHEN le.entry_type = 'credit' THEN le.amount_cents ELSE 0 END)::BIGINT AS credits_cents,
+            SUM(CASE WHEN le.entry_type = 'credit' THEN le.amount_cents ELSE -le.amount_cents END)::BIGINT AS balance_cents
+        FROM ledger_entries le
+        WHERE le.transaction_id IN (
+            SELECT t.id
+            FROM transactions t
+            JOIN accounts a ON a.id = t.from_account_id OR a.id = t.to_account_id
+            WHERE a.api_key_id = $1
+        )
+        GROUP BY le.account_id, le.system_account_code, le.currency
+        ORDER BY le.currency, le.system_account_code NULLS LAST, le.account_id
+        "#,
+    )
+    .bind(api_key_id)
+    .fetch_all(pool)
+    .await?;
+
+    // Aggregate totals per currency (BTreeMap keeps output order stable)
+    let mut per_currency: BTreeMap<String, (i64, i64)> = BTreeMap::new();
+    for line in &lines {
+        let entry = per_currency.entry(line.currency.clone()).or_default();
+        entry.0 += line.debits_cents;
+        entry.1 += line.credits_cents;
+    }
+
+    let totals: Vec<TrialBalanceTotal> = per_currency
+        .into_iter()
+        .map(
+            |(currency, (debits_cents, credits_cents))| TrialBalanceTotal {
+                currency,
+                debits_cents,
+                credits_cents,
+            },
+        )
+        .collect();
+
+    let is_balanced = totals.iter().all(|t| t.debits_cents == t.credits_cents);
+
+    Ok(TrialBalanceResponse {
+        generated_at: Utc::now(),
+        is_balanced,
+        totals,
+        lines,
+    })
+}
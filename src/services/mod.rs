@@ -3,8 +3,30 @@
 //! Services contain core business logic separated from HTTP handlers.
 //! They handle database transactions, validation, and complex operations.
 
+/// Account service for account creation and opening balances
+pub mod account_service;
+
+/// Exchange rates and quotes for cross-currency transfers
+pub mod fx_service;
+
+/// Double-entry ledger postings and trial balance
+pub mod ledger_service;
+
+/// Balance reconciliation against transaction history
+pub mod reconciliation_service;
+
 /// Transaction service for atomic credit, debit, and transfer operations
 pub mod transaction_service;
 
 /// Webhook service for endpoint registration and event delivery
 pub mod webhook_service;
+
+/// Shared webhook HTTP client with concurrency and rate limits
+pub mod webhook_sender;
+
+/// SSRF protection for webhook URLs
+pub mod webhook_url_guard;
+
+/// Database fixtures shared by the service tests
+#[cfg(all(test, feature = "db-tests"))]
+mod test_support;
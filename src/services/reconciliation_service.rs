@@ -0,0 +1,152 @@
+//! Reconciliation service - Detects drift between stored and computed balances.
+//!
+//! `accounts.balance_cents` is updated in place for fast reads, so it can
+//! silently diverge from the transaction history (manual SQL, bugs, seeded
+//! balances). This service recomputes every balance from the posted
+//! transactions and reports accounts where the two disagree.
+//!
+//! # Usage
+//!
+//! - On demand via `GET /api/v1/reconciliation` (scoped to one business)
+//! - Periodically via `spawn_reconciliation_task` (all businesses, logged)
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::{
+    db::DbPool,
+    error::AppError,
+    models::reconciliation::{BalanceMismatch, ReconciliationReport},
+};
+
+/// Recompute account balances from transactions and report mismatches.
+///
+/// Only posted (`completed` or `reversed`) transactions count; authorizations
+/// and pending transfers hold funds without changing the posted balance.
+/// Cross-currency transfers count their destination amount for the
+/// receiving account.
+///
+/// # Arguments
+///
+/// * `pool` - Database connection pool
+/// * `api_key_id` - Restrict to one business, or `None` for all accounts
+///
+/// # Consistency
+///
+/// Both queries run in one read-only REPEATABLE READ transaction, so the
+/// account count, stored and computed balances all come from the same
+/// snapshot even while transactions are being written. `checked_at` is the
+/// time that snapshot was taken.
+pub async fn reconcile(
+    pool: &DbPool,
+    api_key_id: Option<Uuid>,
+) -> Result<ReconciliationReport, AppError> {
+    let mut tx = pool.begin().await?;
+
+    sqlx::query("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ READ ONLY")
+        .execute(&mut *tx)
+        .await?;
+
+    // The snapshot is taken when this first statement starts
+    let (checked_at, accounts_checked): (DateTime<Utc>, i64) = sqlx::query_as(
+        r#"
+        SELECT statement_timestamp(), COUNT(*)
+        FROM accounts
+        WHERE ($1::uuid IS NULL OR api_key_id = $1)
+        "#,
+    )
+    .bind(api_key_id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    let mismatches = sqlx::query_as::<_, BalanceMismatch>(
+        r#"
+        SELECT
+            account_id,
+            api_key_id,
+            currency,
+            stored_balance_cents,
+            computed_balance_cents,
+            stored_balance_cents - computed_balance_cents AS drift_cents
+        FROM (
+            SELECT
+                a.id AS account_id,
+                a.api_key_id,
+                a.currency,
+                a.balance_cents AS stored_balance_cents,
+                (
+                    COALESCE((
+                        SELECT SUM(COALESCE(destination_amount_cents, amount_cents)) FROM transactions
+                        WHERE to_account_id = a.id AND status IN ('completed', 'reversed')
+                    ), 0)
+                    - COALESCE((
+                        SELECT SUM(amount_cents) FROM transactions
+                        WHERE from_account_id = a.id AND status IN ('completed', 'reversed')
+                    ), 0)
+                )::BIGINT AS computed_balance_cents
+            FROM accounts a
+            WHERE ($1::uuid IS NULL OR a.api_key_id = $1)
+        ) balances
+        WHERE stored_balance_cents <> computed_balance_cents
+        ORDER BY account_id
+        "#,
+    )
+    .bind(api_key_id)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(ReconciliationReport {
+        checked_at,
+        accounts_checked,
+        is_consistent: mismatches.is_empty(),
+        mismatches,
+    })
+}
+
+/// Spawn a background task that reconciles all accounts periodically.
+///
+/// Every mismatch is logged at WARN level with the account, business and
+/// drift so it can be alerted on. Failures of a run are logged and the
+/// task keeps going.
+pub fn spawn_reconciliation_task(pool: DbPool, interval: Duration) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+
+        loop {
+            ticker.tick().await;
+
+            match reconcile(&pool, None).await {
+                Ok(report) if report.is_consistent => {
+                    tracing::info!(
+                        accounts_checked = report.accounts_checked,
+                        "Balance reconciliation passed"
+                    );
+                }
+                Ok(report) => {
+                    for mismatch in &report.mismatches {
+                        tracing::warn!(
+                            account_id = %mismatch.account_id,
+                            api_key_id = %mismatch.api_key_id,
+                            stored_balance_cents = mismatch.stored_balance_cents,
+                            computed_balance_cents = mismatch.computed_balance_cents,
+                            drift_cents = mismatch.drift_cents,
+                            "Balance drift detected"
+                        );
+                    }
+                    tracing::warn!(
+                        accounts_checked = report.accounts_checked,
+                        mismatches = report.mismatches.len(),
+                        "Balance reconciliation found mismatches"
+                    );
+                }
+                Err(e) => {
+                    tracing::error!("Balance reconciliation failed: {:?}", e);
+                }
+            }
+        }
+    });
+}
@@ -2,40 +2,164 @@
 //!
 //! This service handles:
 //! - Atomic balance updates
-//! - Idempotency checking
+//! - Double-entry ledger postings (via `ledger_service`)
+//! - Race-free idempotency key reservation
 //! - Balance validation
+//! - Account status checks (frozen accounts can't send, closed accounts
+//!   can't send or receive)
+//! - Full and partial reversals
+//! - Authorization holds: capture, void and expiry
+//! - Status transitions (state machine) with a status history
 //! - Database transaction management
+//! - Paginated transaction history
 //!
 //! # Atomicity Guarantees
 //!
 //! All balance updates happen within PostgreSQL transactions.
 //! The database ensures all-or-nothing execution.
 
-use crate::{db::DbPool, error::AppError, models::transaction::Transaction};
+use std::time::Duration;
+
+use crate::{
+    db::DbPool,
+    error::AppError,
+    models::{
+        account::AccountStatus,
+        metadata::{self, Metadata},
+        pagination,
+        transaction::{
+            NewTransaction, Transaction, TransactionListQuery, TransactionStatus,
+            TransactionStatusChange, TransferRequest,
+        },
+    },
+};
+use chrono::{DateTime, Utc};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use sqlx::{PgConnection, types::Json};
 use uuid::Uuid;
 
-/// Check if a transaction with the given idempotency key already exists.
+/// How long a duplicate request waits for the original to finish before
+/// giving up with `RequestInProgress`.
+const IDEMPOTENCY_LOCK_TIMEOUT: &str = "5s";
+
+/// How long an authorization holds funds if not captured (7 days).
+const DEFAULT_AUTHORIZATION_TTL_SECS: i64 = 7 * 24 * 60 * 60;
+
+/// Longest allowed authorization hold (30 days).
+const MAX_AUTHORIZATION_TTL_SECS: i64 = 30 * 24 * 60 * 60;
+
+/// Maximum number of authorizations expired per database transaction.
+const EXPIRY_BATCH_SIZE: i64 = 100;
+
+/// Take the transaction-scoped advisory lock on `(api_key_id, operation, key)`.
+///
+/// Concurrent requests with the same key queue on this lock until the
+/// holder commits or rolls back. Shared with account creation, whose keys
+/// use their own `operation`.
+///
+/// # Errors
+///
+/// - `RequestInProgress`: The holder did not finish within the lock timeout
+pub async fn lock_idempotency_key(
+    conn: &mut PgConnection,
+    api_key_id: Uuid,
+    operation: &str,
+    key: &str,
+) -> Result<(), AppError> {
+    // Bound the wait for the advisory lock, then restore the default so
+    // account row locks later in the transaction are unaffected
+    sqlx::query("SELECT set_config('lock_timeout', $1, true)")
+        .bind(IDEMPOTENCY_LOCK_TIMEOUT)
+        .execute(&mut *conn)
+        .await?;
+
+    sqlx::query("SELECT pg_advisory_xact_lock(hashtextextended($1, 0))")
+        .bind(format!("{}:{}:{}", api_key_id, operation, key))
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| match e {
+            // lock_not_available: the original request is still running
+            sqlx::Error::Database(ref db) if db.code().as_deref() == Some("55P03") => {
+                AppError::RequestInProgress
+            }
+            e => e.into(),
+        })?;
+
+    sqlx::query("SET LOCAL lock_timeout TO DEFAULT")
+        .execute(&mut *conn)
+        .await?;
+
+    Ok(())
+}
+
+/// Reserve an idempotency key inside an open database transaction.
+///
+/// Takes the advisory lock of `lock_idempotency_key` before looking the
+/// key up. Concurrent requests with the same key queue on
+/// this lock, so the loser only runs its lookup after the winner committed
+/// (and then returns the winner's transaction) or rolled back (and then
+/// proceeds itself). The lock is released automatically at commit/rollback.
+///
+/// Keys are scoped per business (`api_key_id`) and per operation
+/// (`transaction_type`), so two businesses can use the same key.
 ///
 /// # Returns
 ///
 /// - `Ok(Some(transaction))` if a matching transaction exists
 /// - `Ok(None)` if no matching transaction or no key provided
-async fn check_idempotency(
-    pool: &DbPool,
+///
+/// # Errors
+///
+/// - `RequestInProgress`: The original request did not finish within the lock timeout
+/// - `IdempotencyKeyConflict`: The key was used with a different request payload
+async fn reserve_idempotency_key(
+    conn: &mut PgConnection,
+    api_key_id: Uuid,
+    operation: &str,
     idempotency_key: &Option<String>,
+    request_fingerprint: &str,
 ) -> Result<Option<Transaction>, AppError> {
     if let Some(key) = idempotency_key {
+        lock_idempotency_key(&mut *conn, api_key_id, operation, key).await?;
+
         let existing = sqlx::query_as::<_, Transaction>(
-            "SELECT * FROM transactions WHERE idempotency_key = $1",
+            r#"
+            SELECT * FROM transactions
+            WHERE api_key_id = $1 AND transaction_type = $2 AND idempotency_key = $3
+            "#,
         )
+        .bind(api_key_id)
+        .bind(operation)
         .bind(key)
-        .fetch_optional(pool)
+        .fetch_optional(&mut *conn)
         .await?;
+
+        // Transactions recorded before fingerprints existed cannot be compared
+        if let Some(transaction) = &existing
+            && transaction
+                .request_fingerprint
+                .as_deref()
+                .is_some_and(|stored| stored != request_fingerprint)
+        {
+            return Err(AppError::IdempotencyKeyConflict);
+        }
+
         return Ok(existing);
     }
     Ok(None)
 }
 
+/// Compute the fingerprint of a request payload for idempotency checks.
+///
+/// SHA-256 over the JSON serialization. `serde_json` maps are ordered by
+/// key, so the same fields always produce the same fingerprint.
+pub fn request_fingerprint(request: &serde_json::Value) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(request.to_string().as_bytes());
+    hex::encode(hasher.finalize())
+}
+
 /// Validate that the amount is positive.
 fn validate_positive_amount(amount_cents: i64) -> Result<(), AppError> {
     if amount_cents <= 0 {
@@ -46,31 +170,313 @@ fn validate_positive_amount(amount_cents: i64) -> Result<(), AppError> {
     Ok(())
 }
 
-/// Spawn async webhook notification (fire and forget).
-fn spawn_webhook_notification(pool: &DbPool, transaction: &Transaction, api_key_id: Uuid) {
-    let transaction_clone = transaction.clone();
-    let pool_clone = pool.clone();
-    tokio::spawn(async move {
-        if let Err(e) = super::webhook_service::notify_transaction_webhooks(
-            &pool_clone,
-            &transaction_clone,
+/// Direction money moves for an account locked by `lock_account`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FundsFlow {
+    /// Money leaves the account (or is held on it)
+    Outgoing,
+
+    /// Money enters the account
+    Incoming,
+}
+
+/// An account locked for a money movement.
+struct LockedAccount {
+    /// Balance minus open holds, in cents
+    available_cents: i64,
+
+    /// ISO 4217 currency code
+    currency: String,
+}
+
+/// Lock an account for a money movement inside an open database
+/// transaction and check that its status allows the movement.
+///
+/// The status is read under the same row lock as the balance, so a
+/// concurrent freeze or close either waits for the movement or is seen
+/// by it.
+///
+/// # Errors
+///
+/// - `AccountNotFound`: Account doesn't exist
+/// - `AccountFrozen`: Money would leave a frozen account
+/// - `AccountClosed`: Account is closed
+/// - `Database`: Database error occurred
+async fn lock_account(
+    conn: &mut PgConnection,
+    account_id: Uuid,
+    flow: FundsFlow,
+) -> Result<LockedAccount, AppError> {
+    let (available_cents, currency, status): (i64, String, AccountStatus) = sqlx::query_as(
+        "SELECT balance_cents - held_cents, currency, status FROM accounts WHERE id = $1 FOR UPDATE",
+    )
+    .bind(account_id)
+    .fetch_optional(&mut *conn)
+    .await?
+    .ok_or(AppError::AccountNotFound)?;
+
+    let allowed = match flow {
+        FundsFlow::Outgoing => status.can_send(),
+        FundsFlow::Incoming => status.can_receive(),
+    };
+    if !allowed {
+        return Err(match status {
+            AccountStatus::Closed => AppError::AccountClosed,
+            _ => AppError::AccountFrozen,
+        });
+    }
+
+    Ok(LockedAccount {
+        available_cents,
+        currency,
+    })
+}
+
+/// Lock accounts in id order, without checking their status.
+///
+/// Every operation touching two accounts takes both locks through this
+/// before anything else, so two operations on the same pair queue behind
+/// each other whichever way their money flows, instead of deadlocking.
+/// `lock_account` then reads and checks each account under the lock
+/// already held.
+async fn lock_accounts(conn: &mut PgConnection, account_ids: &[Uuid]) -> Result<(), AppError> {
+    sqlx::query("SELECT 1 FROM accounts WHERE id = ANY($1) ORDER BY id FOR UPDATE")
+        .bind(account_ids)
+        .execute(&mut *conn)
+        .await?;
+
+    Ok(())
+}
+
+/// Lock the accounts of a transaction (see `lock_accounts`).
+///
+/// Status changes take these locks on every path, not only when money
+/// moves: they keep their webhook events numbered in commit order for each
+/// account, like those of money movements.
+async fn lock_transaction_accounts(
+    conn: &mut PgConnection,
+    transaction: &Transaction,
+) -> Result<(), AppError> {
+    let account_ids: Vec<Uuid> = [transaction.from_account_id, transaction.to_account_id]
+        .into_iter()
+        .flatten()
+        .collect();
+
+    lock_accounts(conn, &account_ids).await
+}
+
+/// Insert a transaction row, its ledger postings and its webhook events.
+///
+/// The caller must already have applied the balance changes (or holds) on
+/// the same database connection, so the row, postings, status history,
+/// queued webhook events and balances commit together. Postings are only
+/// written for posted statuses; a pending transaction is posted when it
+/// completes.
+///
+/// The currency is taken from the source account, or the destination
+/// account when there is no source (credits). Callers moving money between
+/// two accounts check that their currencies match, or pass the FX quote
+/// converting between them.
+async fn insert_transaction(
+    conn: &mut PgConnection,
+    new: NewTransaction,
+) -> Result<Transaction, AppError> {
+    let transaction = sqlx::query_as::<_, Transaction>(
+        r#"
+        INSERT INTO transactions (
+            transaction_type,
+            from_account_id,
+            to_account_id,
+            amount_cents,
+            description,
+            metadata,
+            idempotency_key,
+            request_fingerprint,
             api_key_id,
+            reversed_transaction_id,
+            authorization_id,
+            status,
+            expires_at,
+            fx_quote_id,
+            destination_amount_cents,
+            destination_currency,
+            fx_rate_e12,
+            currency
         )
-        .await
+        VALUES (
+            $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17,
+            (SELECT currency FROM accounts WHERE id = COALESCE($2, $3))
+        )
+        RETURNING *
+        "#,
+    )
+    .bind(new.transaction_type)
+    .bind(new.from_account_id)
+    .bind(new.to_account_id)
+    .bind(new.amount_cents)
+    .bind(new.description)
+    .bind(Json(&new.metadata))
+    .bind(new.idempotency_key)
+    .bind(new.request_fingerprint)
+    .bind(new.api_key_id)
+    .bind(new.reversed_transaction_id)
+    .bind(new.authorization_id)
+    .bind(new.status)
+    .bind(new.expires_at)
+    .bind(new.fx_quote.as_ref().map(|q| q.id))
+    .bind(new.fx_quote.as_ref().map(|q| q.destination_amount_cents))
+    .bind(
+        new.fx_quote
+            .as_ref()
+            .map(|q| q.destination_currency.as_str()),
+    )
+    .bind(new.fx_quote.as_ref().map(|q| q.rate))
+    .fetch_one(&mut *conn)
+    .await
+    .map_err(|e| match e {
+        // Only reachable if a duplicate slipped past the advisory lock
+        sqlx::Error::Database(ref db)
+            if db.constraint() == Some("idx_transactions_idempotency") =>
         {
-            tracing::error!("Webhook notification failed: {:?}", e);
+            AppError::RequestInProgress
         }
-    });
+        e => e.into(),
+    })?;
+
+    // Write the double-entry postings alongside the balance change
+    if transaction.status.is_posted() {
+        super::ledger_service::record_postings(&mut *conn, &transaction).await?;
+    }
+
+    record_status_change(&mut *conn, transaction.id, None, transaction.status, None).await?;
+
+    // Queue webhook events in the outbox; delivered after commit
+    super::webhook_service::enqueue_transaction_events(&mut *conn, &transaction).await?;
+
+    Ok(transaction)
+}
+
+/// Append an entry to a transaction's status history.
+async fn record_status_change(
+    conn: &mut PgConnection,
+    transaction_id: Uuid,
+    from_status: Option<TransactionStatus>,
+    to_status: TransactionStatus,
+    reason: Option<&str>,
+) -> Result<(), AppError> {
+    sqlx::query(
+        r#"
+        INSERT INTO transaction_status_history (transaction_id, from_status, to_status, reason)
+        VALUES ($1, $2, $3, $4)
+        "#,
+    )
+    .bind(transaction_id)
+    .bind(from_status)
+    .bind(to_status)
+    .bind(reason)
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Move a locked transaction to a new status inside an open database
+/// transaction.
+///
+/// Enforces the transition table of `TransactionStatus`, records the change
+/// in the status history and queues a `transaction.{status}` webhook event.
+/// Balance changes that go with the transition are left to the caller.
+///
+/// # Errors
+///
+/// - `InvalidStatusTransition`: The transition table doesn't allow the move
+/// - `Database`: Database error occurred
+async fn transition_status(
+    conn: &mut PgConnection,
+    transaction: &Transaction,
+    next: TransactionStatus,
+    reason: Option<&str>,
+) -> Result<Transaction, AppError> {
+    if !transaction.status.can_transition_to(next) {
+        return Err(AppError::InvalidStatusTransition {
+            from: transaction.status,
+            to: next,
+        });
+    }
+
+    let updated = sqlx::query_as::<_, Transaction>(
+        "UPDATE transactions SET status = $2 WHERE id = $1 RETURNING *",
+    )
+    .bind(transaction.id)
+    .bind(next)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    record_status_change(
+        &mut *conn,
+        transaction.id,
+        Some(transaction.status),
+        next,
+        reason,
+    )
+    .await?;
+
+    super::webhook_service::enqueue_transaction_status_events(
+        &mut *conn,
+        &updated,
+        &format!("transaction.{}", next),
+    )
+    .await?;
+
+    Ok(updated)
+}
+
+/// Credit an account inside an open database transaction.
+///
+/// Shared by regular credits and opening balances so both update the
+/// balance of `new.to_account_id`, write the transaction row and its ledger
+/// postings identically. The caller owns the database transaction and
+/// commits it.
+///
+/// # Errors
+///
+/// - `AccountNotFound`: Account doesn't exist
+/// - `AccountClosed`: Account is closed
+/// - `Database`: Database error occurred
+async fn apply_credit(
+    conn: &mut PgConnection,
+    new: NewTransaction,
+) -> Result<Transaction, AppError> {
+    let account_id = new.to_account_id.ok_or(AppError::AccountNotFound)?;
+
+    // Lock the account (no other transaction can modify it) and update balance
+    lock_account(&mut *conn, account_id, FundsFlow::Incoming).await?;
+
+    sqlx::query(
+        r#"
+        UPDATE accounts
+        SET balance_cents = balance_cents + $1,
+            updated_at = NOW()
+        WHERE id = $2
+        "#,
+    )
+    .bind(new.amount_cents)
+    .bind(account_id)
+    .execute(&mut *conn)
+    .await?;
+
+    // Record the transaction
+    insert_transaction(conn, new).await
 }
 
 /// Execute a credit transaction (add money to account).
 ///
 /// # Process
 ///
-/// 1. Check for duplicate idempotency key
-/// 2. Start database transaction
+/// 1. Start database transaction
+/// 2. Reserve idempotency key (same business and operation)
 /// 3. Lock and update account balance
-/// 4. Record transaction
+/// 4. Record transaction and its ledger postings
 /// 5. Commit (or rollback on error)
 ///
 /// # Arguments
@@ -79,6 +485,7 @@ fn spawn_webhook_notification(pool: &DbPool, transaction: &Transaction, api_key_
 /// * `account_id` - Account to credit
 /// * `amount_cents` - Amount to add (must be positive)
 /// * `description` - Optional description
+/// * `metadata` - Client-defined key/value metadata
 /// * `idempotency_key` - Optional key for deduplication
 ///
 /// # Returns
@@ -88,110 +495,166 @@ fn spawn_webhook_notification(pool: &DbPool, transaction: &Transaction, api_key_
 /// # Errors
 ///
 /// - `AccountNotFound`: Account doesn't exist
-/// - `InvalidRequest`: Amount is zero or negative
+/// - `AccountClosed`: Account is closed
+/// - `InvalidRequest`: Amount is zero or negative, or metadata exceeds its limits
+/// - `IdempotencyKeyConflict`: Key reused with a different payload
+/// - `RequestInProgress`: A request with the same key is still running
 /// - `Database`: Database error occurred
 pub async fn execute_credit(
     pool: &DbPool,
     account_id: Uuid,
     amount_cents: i64,
     description: Option<String>,
+    metadata: Metadata,
     idempotency_key: Option<String>,
     api_key_id: Uuid,
 ) -> Result<Transaction, AppError> {
-    // Validate amount
+    // Validate amount and metadata
     validate_positive_amount(amount_cents)?;
+    metadata::validate(&metadata).map_err(AppError::InvalidRequest)?;
 
-    // Check for duplicate idempotency key
-    if let Some(existing) = check_idempotency(pool, &idempotency_key).await? {
-        return Ok(existing);
+    // Fingerprint the payload for idempotency checks (`metadata` only when
+    // set, so fingerprints of requests without it are unchanged)
+    let mut payload = json!({
+        "account_id": account_id,
+        "amount_cents": amount_cents,
+        "description": description,
+    });
+    if !metadata.is_empty() {
+        payload["metadata"] = json!(metadata);
     }
+    let fingerprint = request_fingerprint(&payload);
 
     // Start db transaction
     let mut tx = pool.begin().await?;
 
-    // Lock the account and update balance
-    // FOR UPDATE ensures no other transaction can modify this row
-    let updated_count = sqlx::query(
-        r#"
-        UPDATE accounts
-        SET balance_cents = balance_cents + $1,
-            updated_at = NOW()
-        WHERE id = $2
-        "#,
+    // Reserve the idempotency key (waits for a concurrent duplicate to finish)
+    if let Some(existing) = reserve_idempotency_key(
+        &mut tx,
+        api_key_id,
+        "credit",
+        &idempotency_key,
+        &fingerprint,
     )
-    .bind(amount_cents)
-    .bind(account_id)
-    .execute(&mut *tx)
     .await?
-    .rows_affected();
-
-    if updated_count == 0 {
-        tx.rollback().await?;
-        return Err(AppError::AccountNotFound);
+    {
+        return Ok(existing);
     }
 
-    // Record the transaction
-    let transaction = sqlx::query_as::<_, Transaction>(
-        r#"
-        INSERT INTO transactions (
-            transaction_type,
-            to_account_id,
+    // Apply the credit (balance, transaction row, ledger postings)
+    let transaction = apply_credit(
+        &mut tx,
+        NewTransaction {
+            transaction_type: "credit",
+            status: TransactionStatus::Completed,
+            from_account_id: None,
+            to_account_id: Some(account_id),
             amount_cents,
             description,
+            metadata,
             idempotency_key,
-            status
-        )
-        VALUES ('credit', $1, $2, $3, $4, 'completed')
-        RETURNING *
-        "#,
+            request_fingerprint: Some(fingerprint),
+            api_key_id,
+            reversed_transaction_id: None,
+            authorization_id: None,
+            expires_at: None,
+            fx_quote: None,
+        },
     )
-    .bind(account_id)
-    .bind(amount_cents)
-    .bind(description)
-    .bind(idempotency_key)
-    .fetch_one(&mut *tx)
     .await?;
 
     // Commit all changes atomically
     tx.commit().await?;
 
-    // Trigger webhook notifications asynchronously (don't block response)
-    spawn_webhook_notification(pool, &transaction, api_key_id);
-
     Ok(transaction)
 }
 
+/// Record an account's opening balance inside an open database transaction.
+///
+/// Goes through the same path as `execute_credit`, producing an
+/// `opening_balance` transaction booked against the `opening_balances`
+/// system account. Its webhook events are queued on the same connection;
+/// the caller commits the database transaction.
+///
+/// # Errors
+///
+/// - `InvalidRequest`: Amount is zero or negative
+/// - `AccountNotFound`: Account doesn't exist
+/// - `Database`: Database error occurred
+pub async fn record_opening_balance(
+    conn: &mut PgConnection,
+    account_id: Uuid,
+    amount_cents: i64,
+    api_key_id: Uuid,
+) -> Result<Transaction, AppError> {
+    validate_positive_amount(amount_cents)?;
+
+    apply_credit(
+        conn,
+        NewTransaction {
+            transaction_type: "opening_balance",
+            status: TransactionStatus::Completed,
+            from_account_id: None,
+            to_account_id: Some(account_id),
+            amount_cents,
+            description: Some("Opening balance".to_string()),
+            metadata: Metadata::new(),
+            idempotency_key: None,
+            request_fingerprint: None,
+            api_key_id,
+            reversed_transaction_id: None,
+            authorization_id: None,
+            expires_at: None,
+            fx_quote: None,
+        },
+    )
+    .await
+}
+
 /// Execute a debit transaction (remove money from account).
 pub async fn execute_debit(
     pool: &DbPool,
     account_id: Uuid,
     amount_cents: i64,
     description: Option<String>,
+    metadata: Metadata,
     idempotency_key: Option<String>,
     api_key_id: Uuid,
 ) -> Result<Transaction, AppError> {
-    // Validate amount
+    // Validate amount and metadata
     validate_positive_amount(amount_cents)?;
+    metadata::validate(&metadata).map_err(AppError::InvalidRequest)?;
 
-    // Check for duplicate idempotency key
-    if let Some(existing) = check_idempotency(pool, &idempotency_key).await? {
-        return Ok(existing);
+    // Fingerprint the payload for idempotency checks (`metadata` only when
+    // set, so fingerprints of requests without it are unchanged)
+    let mut payload = json!({
+        "account_id": account_id,
+        "amount_cents": amount_cents,
+        "description": description,
+    });
+    if !metadata.is_empty() {
+        payload["metadata"] = json!(metadata);
     }
+    let fingerprint = request_fingerprint(&payload);
 
     // Start database transaction
     let mut tx = pool.begin().await?;
 
-    // Lock account and check balance
-    let balance_cents: i64 =
-        sqlx::query_scalar("SELECT balance_cents FROM accounts WHERE id = $1 FOR UPDATE")
-            .bind(account_id)
-            .fetch_optional(&mut *tx)
+    // Reserve the idempotency key (waits for a concurrent duplicate to finish)
+    if let Some(existing) =
+        reserve_idempotency_key(&mut tx, api_key_id, "debit", &idempotency_key, &fingerprint)
             .await?
-            .ok_or(AppError::AccountNotFound)?;
+    {
+        return Ok(existing);
+    }
+
+    // Lock account and check available balance (held funds can't be spent)
+    let available_cents = lock_account(&mut tx, account_id, FundsFlow::Outgoing)
+        .await?
+        .available_cents;
 
     // Validate sufficient balance
-    if balance_cents < amount_cents {
-        tx.rollback().await?;
+    if available_cents < amount_cents {
         return Err(AppError::InsufficientBalance);
     }
 
@@ -209,49 +672,107 @@ pub async fn execute_debit(
     .execute(&mut *tx)
     .await?;
 
-    // Record transaction
-    let transaction = sqlx::query_as::<_, Transaction>(
-        r#"
-        INSERT INTO transactions (
-            transaction_type,
-            from_account_id,
+    // Record transaction and its ledger postings
+    let transaction = insert_transaction(
+        &mut tx,
+        NewTransaction {
+            transaction_type: "debit",
+            status: TransactionStatus::Completed,
+            from_account_id: Some(account_id),
+            to_account_id: None,
             amount_cents,
             description,
+            metadata,
             idempotency_key,
-            status
-        )
-        VALUES ('debit', $1, $2, $3, $4, 'completed')
-        RETURNING *
-        "#,
+            request_fingerprint: Some(fingerprint),
+            api_key_id,
+            reversed_transaction_id: None,
+            authorization_id: None,
+            expires_at: None,
+            fx_quote: None,
+        },
     )
-    .bind(account_id)
-    .bind(amount_cents)
-    .bind(description)
-    .bind(idempotency_key)
-    .fetch_one(&mut *tx)
     .await?;
 
     // Commit atomically
     tx.commit().await?;
 
-    // Trigger webhook notifications asynchronously
-    spawn_webhook_notification(pool, &transaction, api_key_id);
-
     Ok(transaction)
 }
 
-/// Execute a transfer transaction (move money between accounts).
-pub async fn execute_transfer(
-    pool: &DbPool,
+/// Move money between two (locked) accounts and release `released_cents`
+/// of the source account's hold.
+///
+/// `credited_cents` differs from `amount_cents` only for cross-currency
+/// transfers (each is in its own account's currency).
+async fn move_funds(
+    conn: &mut PgConnection,
     from_account_id: Uuid,
     to_account_id: Uuid,
     amount_cents: i64,
-    description: Option<String>,
-    idempotency_key: Option<String>,
+    credited_cents: i64,
+    released_cents: i64,
+) -> Result<(), AppError> {
+    sqlx::query(
+        r#"
+        UPDATE accounts
+        SET balance_cents = balance_cents - $1,
+            held_cents = held_cents - $2,
+            updated_at = NOW()
+        WHERE id = $3
+        "#,
+    )
+    .bind(amount_cents)
+    .bind(released_cents)
+    .bind(from_account_id)
+    .execute(&mut *conn)
+    .await?;
+
+    sqlx::query(
+        "UPDATE accounts SET balance_cents = balance_cents + $1, updated_at = NOW() WHERE id = $2",
+    )
+    .bind(credited_cents)
+    .bind(to_account_id)
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Execute a transfer transaction (move money between accounts).
+///
+/// With `pending` the amount is only held on the source account and the
+/// transfer is recorded as `pending`; `update_transaction_status` later
+/// completes it (moving the money) or fails/cancels it (releasing the hold). Besides
+/// `transaction.transfer`, a pending transfer queues `transaction.pending`,
+/// the first event of its status lifecycle.
+///
+/// Accounts of different currencies need a matching, unexpired and unused
+/// FX quote (`CurrencyMismatch` without one); the destination is credited
+/// the quoted amount and the transfer records amount, destination amount
+/// and rate.
+///
+/// The source account must be active (`AccountFrozen` / `AccountClosed`);
+/// the destination may be frozen but not closed.
+pub async fn execute_transfer(
+    pool: &DbPool,
+    request: TransferRequest,
     api_key_id: Uuid,
 ) -> Result<Transaction, AppError> {
-    // Validate amount
+    let TransferRequest {
+        from_account_id,
+        to_account_id,
+        amount_cents,
+        description,
+        metadata,
+        idempotency_key,
+        pending,
+        fx_quote_id,
+    } = request;
+
+    // Validate amount and metadata
     validate_positive_amount(amount_cents)?;
+    metadata::validate(&metadata).map_err(AppError::InvalidRequest)?;
 
     // Prevent transferring to same account
     if from_account_id == to_account_id {
@@ -260,99 +781,1885 @@ pub async fn execute_transfer(
         ));
     }
 
-    // Check for duplicate idempotency key
-    if let Some(existing) = check_idempotency(pool, &idempotency_key).await? {
-        return Ok(existing);
+    // Fingerprint the payload for idempotency checks (`pending`,
+    // `fx_quote_id` and `metadata` only when set, so fingerprints of plain
+    // transfers are unchanged)
+    let mut payload = json!({
+        "from_account_id": from_account_id,
+        "to_account_id": to_account_id,
+        "amount_cents": amount_cents,
+        "description": description,
+    });
+    if pending {
+        payload["pending"] = json!(true);
+    }
+    if let Some(quote_id) = fx_quote_id {
+        payload["fx_quote_id"] = json!(quote_id);
     }
+    if !metadata.is_empty() {
+        payload["metadata"] = json!(metadata);
+    }
+    let fingerprint = request_fingerprint(&payload);
 
     // Start database transaction
     let mut tx = pool.begin().await?;
 
-    // Lock source account and check balance
-    // FOR UPDATE prevents other transactions from modifying
-    let from_balance: i64 =
-        sqlx::query_scalar("SELECT balance_cents FROM accounts WHERE id = $1 FOR UPDATE")
-            .bind(from_account_id)
-            .fetch_optional(&mut *tx)
-            .await?
-            .ok_or(AppError::AccountNotFound)?;
+    // Reserve the idempotency key (waits for a concurrent duplicate to finish)
+    if let Some(existing) = reserve_idempotency_key(
+        &mut tx,
+        api_key_id,
+        "transfer",
+        &idempotency_key,
+        &fingerprint,
+    )
+    .await?
+    {
+        return Ok(existing);
+    }
+
+    // Lock both accounts in id order, so transfers between the same pair
+    // never deadlock
+    lock_accounts(&mut tx, &[from_account_id, to_account_id]).await?;
+
+    // Check the source account and its available balance
+    let LockedAccount {
+        available_cents: from_available,
+        currency: from_currency,
+    } = lock_account(&mut tx, from_account_id, FundsFlow::Outgoing).await?;
 
-    if from_balance < amount_cents {
-        tx.rollback().await?;
+    if from_available < amount_cents {
         return Err(AppError::InsufficientBalance);
     }
 
-    // Lock destination account
-    let to_exists: bool =
-        sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM accounts WHERE id = $1 FOR UPDATE)")
-            .bind(to_account_id)
-            .fetch_one(&mut *tx)
-            .await?;
+    // Check the destination account
+    let to_currency = lock_account(&mut tx, to_account_id, FundsFlow::Incoming)
+        .await?
+        .currency;
+
+    // Amounts are in minor units of the account currency; only a quote
+    // converts between them
+    let fx_quote = if from_currency == to_currency {
+        if fx_quote_id.is_some() {
+            return Err(AppError::InvalidRequest(
+                "fx_quote_id is only allowed between accounts of different currencies".to_string(),
+            ));
+        }
+        None
+    } else {
+        let Some(quote_id) = fx_quote_id else {
+            return Err(AppError::CurrencyMismatch {
+                from: from_currency,
+                to: to_currency,
+            });
+        };
+
+        let quote = super::fx_service::lock_quote(&mut tx, quote_id, api_key_id).await?;
+
+        if quote.source_currency != from_currency
+            || quote.destination_currency != to_currency
+            || quote.source_amount_cents != amount_cents
+        {
+            return Err(AppError::InvalidRequest(format!(
+                "FX quote is for {} {} to {}, not {} {} to {}",
+                quote.source_amount_cents,
+                quote.source_currency,
+                quote.destination_currency,
+                amount_cents,
+                from_currency,
+                to_currency
+            )));
+        }
+
+        Some(quote)
+    };
+    let credited_cents = fx_quote
+        .as_ref()
+        .map_or(amount_cents, |quote| quote.destination_amount_cents);
+
+    if pending {
+        // Hold the amount until the transfer completes, fails or is canceled
+        sqlx::query(
+            "UPDATE accounts SET held_cents = held_cents + $1, updated_at = NOW() WHERE id = $2",
+        )
+        .bind(amount_cents)
+        .bind(from_account_id)
+        .execute(&mut *tx)
+        .await?;
+    } else {
+        // Update both balances atomically
+        move_funds(
+            &mut tx,
+            from_account_id,
+            to_account_id,
+            amount_cents,
+            credited_cents,
+            0,
+        )
+        .await?;
+    }
+
+    // Record transaction and its ledger postings
+    let transaction = insert_transaction(
+        &mut tx,
+        NewTransaction {
+            transaction_type: "transfer",
+            status: if pending {
+                TransactionStatus::Pending
+            } else {
+                TransactionStatus::Completed
+            },
+            from_account_id: Some(from_account_id),
+            to_account_id: Some(to_account_id),
+            amount_cents,
+            description,
+            metadata,
+            idempotency_key,
+            request_fingerprint: Some(fingerprint),
+            api_key_id,
+            reversed_transaction_id: None,
+            authorization_id: None,
+            expires_at: None,
+            fx_quote,
+        },
+    )
+    .await?;
+
+    // Subscribers to the status lifecycle see it start, like its later moves
+    if pending {
+        super::webhook_service::enqueue_transaction_status_events(
+            &mut tx,
+            &transaction,
+            "transaction.pending",
+        )
+        .await?;
+    }
+
+    // Commit ALL changes atomically
+    tx.commit().await?;
+
+    Ok(transaction)
+}
+
+/// Reverse all or part of a transaction.
+///
+/// The reversal moves money back the way it came: the original's
+/// destination account is debited and its source account credited (a side
+/// without an account, such as the funding side of a credit, is booked
+/// against the same system account as the original).
+///
+/// # Process
+///
+/// 1. Start database transaction
+/// 2. Reserve idempotency key (same business and operation)
+/// 3. Lock the original transaction, serializing concurrent reversals of it
+/// 4. Check the amount against what has not been reversed yet
+/// 5. Lock accounts, check balance of the account money is taken from
+/// 6. Update balances, record the reversal and its mirrored ledger postings
+/// 7. Mark the original `reversed` once nothing is left to reverse
+/// 8. Commit (or rollback on error)
+///
+/// # Arguments
+///
+/// * `transaction_id` - Transaction to reverse
+/// * `amount_cents` - Amount to reverse, or `None` for the remaining amount
+///
+/// # Errors
+///
+/// - `TransactionNotFound`: Transaction doesn't exist or belongs to another business
+/// - `InvalidRequest`: Amount is zero or negative, or the transaction is a
+///   reversal, not completed (e.g. an authorization) or cross-currency
+/// - `ReversalExceedsRemaining`: Amount exceeds what has not been reversed yet
+/// - `InsufficientBalance`: Account money is taken back from has too little
+/// - `AccountFrozen` / `AccountClosed`: Account money is taken back from is
+///   frozen or closed, or the account it goes back to is closed
+/// - `IdempotencyKeyConflict`: Key reused with a different payload
+/// - `RequestInProgress`: A request with the same key is still running
+/// - `Database`: Database error occurred
+pub async fn execute_reversal(
+    pool: &DbPool,
+    transaction_id: Uuid,
+    amount_cents: Option<i64>,
+    description: Option<String>,
+    idempotency_key: Option<String>,
+    api_key_id: Uuid,
+) -> Result<Transaction, AppError> {
+    // Validate amount (if given)
+    if let Some(amount_cents) = amount_cents {
+        validate_positive_amount(amount_cents)?;
+    }
+
+    // Fingerprint the payload for idempotency checks
+    let fingerprint = request_fingerprint(&json!({
+        "transaction_id": transaction_id,
+        "amount_cents": amount_cents,
+        "description": description,
+    }));
+
+    // Start database transaction
+    let mut tx = pool.begin().await?;
+
+    // Reserve the idempotency key (waits for a concurrent duplicate to finish)
+    if let Some(existing) = reserve_idempotency_key(
+        &mut tx,
+        api_key_id,
+        "reversal",
+        &idempotency_key,
+        &fingerprint,
+    )
+    .await?
+    {
+        return Ok(existing);
+    }
+
+    // Lock the original so concurrent reversals see each other's amounts
+    let original = sqlx::query_as::<_, Transaction>(
+        "SELECT * FROM transactions WHERE id = $1 AND api_key_id = $2 FOR UPDATE",
+    )
+    .bind(transaction_id)
+    .bind(api_key_id)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or(AppError::TransactionNotFound)?;
+
+    if original.reversed_transaction_id.is_some() {
+        return Err(AppError::InvalidRequest(
+            "A reversal cannot be reversed".to_string(),
+        ));
+    }
+
+    // Only posted transactions moved money (authorizations: reverse their capture)
+    if !original.status.is_posted() {
+        return Err(AppError::InvalidRequest(
+            "Only completed transactions can be reversed".to_string(),
+        ));
+    }
+
+    // Amounts differ per side; send the money back with a new quote instead
+    if original.fx_quote_id.is_some() {
+        return Err(AppError::InvalidRequest(
+            "Cross-currency transfers can't be reversed".to_string(),
+        ));
+    }
+
+    // Cumulative reversals must never exceed the original amount
+    let reversed_cents: i64 = sqlx::query_scalar(
+        r#"
+        SELECT COALESCE(SUM(amount_cents), 0)::BIGINT
+        FROM transactions
+        WHERE reversed_transaction_id = $1
+        "#,
+    )
+    .bind(original.id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    let remaining_cents = original.amount_cents - reversed_cents;
+    let amount_cents = amount_cents.unwrap_or(remaining_cents);
+    if remaining_cents == 0 || amount_cents > remaining_cents {
+        return Err(AppError::ReversalExceedsRemaining(remaining_cents));
+    }
+
+    // Money flows back: the original's destination pays, its source receives
+    let from_account_id = original.to_account_id;
+    let to_account_id = original.from_account_id;
+
+    // Lock both accounts in id order before checking either, like transfers
+    lock_transaction_accounts(&mut tx, &original).await?;
+
+    // Check the paying account and its available balance
+    if let Some(account_id) = from_account_id {
+        let available_cents = lock_account(&mut tx, account_id, FundsFlow::Outgoing)
+            .await?
+            .available_cents;
+
+        if available_cents < amount_cents {
+            return Err(AppError::InsufficientBalance);
+        }
+
+        sqlx::query(
+            "UPDATE accounts SET balance_cents = balance_cents - $1, updated_at = NOW() WHERE id = $2",
+        )
+        .bind(amount_cents)
+        .bind(account_id)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    if let Some(account_id) = to_account_id {
+        lock_account(&mut tx, account_id, FundsFlow::Incoming).await?;
+
+        sqlx::query(
+            "UPDATE accounts SET balance_cents = balance_cents + $1, updated_at = NOW() WHERE id = $2",
+        )
+        .bind(amount_cents)
+        .bind(account_id)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    // Record the reversal and its ledger postings
+    let transaction = insert_transaction(
+        &mut tx,
+        NewTransaction {
+            transaction_type: "reversal",
+            status: TransactionStatus::Completed,
+            from_account_id,
+            to_account_id,
+            amount_cents,
+            description,
+            metadata: Metadata::new(),
+            idempotency_key,
+            request_fingerprint: Some(fingerprint),
+            api_key_id,
+            reversed_transaction_id: Some(original.id),
+            authorization_id: None,
+            expires_at: None,
+            fx_quote: None,
+        },
+    )
+    .await?;
+
+    // A fully reversed original moves to `reversed`
+    if amount_cents == remaining_cents {
+        transition_status(&mut tx, &original, TransactionStatus::Reversed, None).await?;
+    }
+
+    // Commit atomically
+    tx.commit().await?;
+
+    Ok(transaction)
+}
+
+/// Place a hold on an account (first phase of authorize/capture).
+///
+/// The hold reduces the account's available balance without changing its
+/// posted balance, and nothing is written to the ledger. The hold ends when
+/// the authorization is captured (`execute_capture`), voided
+/// (`void_authorization`) or expires (`expire_authorizations`).
+///
+/// # Process
+///
+/// 1. Start database transaction
+/// 2. Reserve idempotency key (same business and operation)
+/// 3. Lock account and check available balance
+/// 4. Increase the account's held amount
+/// 5. Record a `pending` authorization and queue its webhook events
+/// 6. Commit (or rollback on error)
+///
+/// # Errors
+///
+/// - `AccountNotFound`: Account doesn't exist
+/// - `InvalidRequest`: Amount is zero or negative, or expiry out of range
+/// - `InsufficientBalance`: Available balance is less than the amount
+/// - `AccountFrozen` / `AccountClosed`: Account is frozen or closed
+/// - `IdempotencyKeyConflict`: Key reused with a different payload
+/// - `RequestInProgress`: A request with the same key is still running
+/// - `Database`: Database error occurred
+pub async fn execute_authorization(
+    pool: &DbPool,
+    account_id: Uuid,
+    amount_cents: i64,
+    description: Option<String>,
+    idempotency_key: Option<String>,
+    expires_in_seconds: Option<i64>,
+    api_key_id: Uuid,
+) -> Result<Transaction, AppError> {
+    // Validate amount and expiry
+    validate_positive_amount(amount_cents)?;
+
+    let expires_in_seconds = expires_in_seconds.unwrap_or(DEFAULT_AUTHORIZATION_TTL_SECS);
+    if !(1..=MAX_AUTHORIZATION_TTL_SECS).contains(&expires_in_seconds) {
+        return Err(AppError::InvalidRequest(format!(
+            "expires_in_seconds must be between 1 and {}",
+            MAX_AUTHORIZATION_TTL_SECS
+        )));
+    }
+
+    // Fingerprint the payload for idempotency checks
+    let fingerprint = request_fingerprint(&json!({
+        "account_id": account_id,
+        "amount_cents": amount_cents,
+        "description": description,
+        "expires_in_seconds": expires_in_seconds,
+    }));
+
+    // Start database transaction
+    let mut tx = pool.begin().await?;
+
+    // Reserve the idempotency key (waits for a concurrent duplicate to finish)
+    if let Some(existing) = reserve_idempotency_key(
+        &mut tx,
+        api_key_id,
+        "authorization",
+        &idempotency_key,
+        &fingerprint,
+    )
+    .await?
+    {
+        return Ok(existing);
+    }
 
-    if !to_exists {
-        tx.rollback().await?;
-        return Err(AppError::AccountNotFound);
+    // Lock account and check available balance
+    let available_cents = lock_account(&mut tx, account_id, FundsFlow::Outgoing)
+        .await?
+        .available_cents;
+
+    if available_cents < amount_cents {
+        return Err(AppError::InsufficientBalance);
     }
 
-    // Update both balances atomically
+    // Hold the funds; the posted balance is unchanged
     sqlx::query(
-        "UPDATE accounts SET balance_cents = balance_cents - $1, updated_at = NOW() WHERE id = $2",
+        "UPDATE accounts SET held_cents = held_cents + $1, updated_at = NOW() WHERE id = $2",
     )
     .bind(amount_cents)
-    .bind(from_account_id)
+    .bind(account_id)
     .execute(&mut *tx)
     .await?;
 
+    // Record the authorization (no ledger postings until captured)
+    let authorization = insert_transaction(
+        &mut tx,
+        NewTransaction {
+            transaction_type: "authorization",
+            status: TransactionStatus::Pending,
+            from_account_id: Some(account_id),
+            to_account_id: None,
+            amount_cents,
+            description,
+            metadata: Metadata::new(),
+            idempotency_key,
+            request_fingerprint: Some(fingerprint),
+            api_key_id,
+            reversed_transaction_id: None,
+            authorization_id: None,
+            expires_at: Some(Utc::now() + chrono::Duration::seconds(expires_in_seconds)),
+            fx_quote: None,
+        },
+    )
+    .await?;
+
+    // Commit atomically
+    tx.commit().await?;
+
+    Ok(authorization)
+}
+
+/// Lock an authorization of the business that is still holding funds.
+///
+/// # Errors
+///
+/// - `TransactionNotFound`: Not an authorization of this business
+/// - `AuthorizationNotPending`: Already captured, voided or expired, or past
+///   its expiry (released by the next expiry run)
+async fn lock_pending_authorization(
+    conn: &mut PgConnection,
+    authorization_id: Uuid,
+    api_key_id: Uuid,
+) -> Result<Transaction, AppError> {
+    let authorization = sqlx::query_as::<_, Transaction>(
+        r#"
+        SELECT * FROM transactions
+        WHERE id = $1 AND api_key_id = $2 AND transaction_type = 'authorization'
+        FOR UPDATE
+        "#,
+    )
+    .bind(authorization_id)
+    .bind(api_key_id)
+    .fetch_optional(&mut *conn)
+    .await?
+    .ok_or(AppError::TransactionNotFound)?;
+
+    if authorization.status != TransactionStatus::Pending {
+        return Err(AppError::AuthorizationNotPending(authorization.status));
+    }
+    if authorization
+        .expires_at
+        .is_some_and(|expires_at| expires_at <= Utc::now())
+    {
+        return Err(AppError::AuthorizationNotPending(
+            TransactionStatus::Expired,
+        ));
+    }
+
+    Ok(authorization)
+}
+
+/// Capture an authorization (second phase of authorize/capture).
+///
+/// Posts up to the authorized amount as a `capture` transaction (booked like
+/// a debit) and releases the whole hold, so a partial capture frees the
+/// uncaptured rest. An authorization is captured at most once.
+///
+/// # Process
+///
+/// 1. Start database transaction
+/// 2. Reserve idempotency key (same business and operation)
+/// 3. Lock the authorization and check it is still holding funds
+/// 4. Release the hold and debit the captured amount
+/// 5. Mark the authorization `captured`, record the capture and its ledger
+///    postings
+/// 6. Commit (or rollback on error)
+///
+/// # Errors
+///
+/// - `TransactionNotFound`: Not an authorization of this business
+/// - `InvalidRequest`: Amount is zero, negative or above the authorized amount
+/// - `AuthorizationNotPending`: Already captured, voided or expired
+/// - `AccountFrozen`: Account was frozen after the authorization
+/// - `IdempotencyKeyConflict`: Key reused with a different payload
+/// - `RequestInProgress`: A request with the same key is still running
+/// - `Database`: Database error occurred
+pub async fn execute_capture(
+    pool: &DbPool,
+    authorization_id: Uuid,
+    amount_cents: Option<i64>,
+    description: Option<String>,
+    idempotency_key: Option<String>,
+    api_key_id: Uuid,
+) -> Result<Transaction, AppError> {
+    // Validate amount (if given)
+    if let Some(amount_cents) = amount_cents {
+        validate_positive_amount(amount_cents)?;
+    }
+
+    // Fingerprint the payload for idempotency checks
+    let fingerprint = request_fingerprint(&json!({
+        "authorization_id": authorization_id,
+        "amount_cents": amount_cents,
+        "description": description,
+    }));
+
+    // Start database transaction
+    let mut tx = pool.begin().await?;
+
+    // Reserve the idempotency key (waits for a concurrent duplicate to finish)
+    if let Some(existing) = reserve_idempotency_key(
+        &mut tx,
+        api_key_id,
+        "capture",
+        &idempotency_key,
+        &fingerprint,
+    )
+    .await?
+    {
+        return Ok(existing);
+    }
+
+    let authorization = lock_pending_authorization(&mut tx, authorization_id, api_key_id).await?;
+
+    let amount_cents = amount_cents.unwrap_or(authorization.amount_cents);
+    if amount_cents > authorization.amount_cents {
+        return Err(AppError::InvalidRequest(format!(
+            "Capture amount exceeds the authorized amount of {} cents",
+            authorization.amount_cents
+        )));
+    }
+
+    let account_id = authorization
+        .from_account_id
+        .ok_or(AppError::AccountNotFound)?;
+
+    lock_account(&mut tx, account_id, FundsFlow::Outgoing).await?;
+
+    // Release the full hold and post the captured amount; the hold
+    // guarantees the balance covers it
     sqlx::query(
-        "UPDATE accounts SET balance_cents = balance_cents + $1, updated_at = NOW() WHERE id = $2",
+        r#"
+        UPDATE accounts
+        SET balance_cents = balance_cents - $1,
+            held_cents = held_cents - $2,
+            updated_at = NOW()
+        WHERE id = $3
+        "#,
     )
     .bind(amount_cents)
-    .bind(to_account_id)
+    .bind(authorization.amount_cents)
+    .bind(account_id)
     .execute(&mut *tx)
     .await?;
 
-    // Record transaction
-    let transaction = sqlx::query_as::<_, Transaction>(
-        r#"
-        INSERT INTO transactions (
-            transaction_type,
-            from_account_id,
-            to_account_id,
+    transition_status(&mut tx, &authorization, TransactionStatus::Captured, None).await?;
+
+    // Record the capture and its ledger postings
+    let transaction = insert_transaction(
+        &mut tx,
+        NewTransaction {
+            transaction_type: "capture",
+            status: TransactionStatus::Completed,
+            from_account_id: Some(account_id),
+            to_account_id: None,
             amount_cents,
             description,
+            metadata: Metadata::new(),
             idempotency_key,
-            status
-        )
-        VALUES ('transfer', $1, $2, $3, $4, $5, 'completed')
-        RETURNING *
-        "#,
+            request_fingerprint: Some(fingerprint),
+            api_key_id,
+            reversed_transaction_id: None,
+            authorization_id: Some(authorization.id),
+            expires_at: None,
+            fx_quote: None,
+        },
     )
-    .bind(from_account_id)
-    .bind(to_account_id)
-    .bind(amount_cents)
-    .bind(description)
-    .bind(idempotency_key)
-    .fetch_one(&mut *tx)
     .await?;
 
-    // Commit ALL changes atomically
+    // Commit atomically
     tx.commit().await?;
 
-    // Trigger webhook notifications asynchronously
-    spawn_webhook_notification(pool, &transaction, api_key_id);
-
     Ok(transaction)
 }
 
-/// Get transaction by ID.
-pub async fn get_transaction_by_id(
-    pool: &DbPool,
-    transaction_id: Uuid,
-) -> Result<Option<Transaction>, AppError> {
-    let transaction = sqlx::query_as::<_, Transaction>("SELECT * FROM transactions WHERE id = $1")
-        .bind(transaction_id)
-        .fetch_optional(pool)
-        .await?;
+/// Release the hold of an authorization inside an open database transaction.
+///
+/// Moves the (locked, pending) authorization to `status`, which records the
+/// change and queues a `transaction.{status}` webhook event.
+async fn release_authorization(
+    conn: &mut PgConnection,
+    authorization: &Transaction,
+    status: TransactionStatus,
+) -> Result<Transaction, AppError> {
+    let account_id = authorization
+        .from_account_id
+        .ok_or(AppError::AccountNotFound)?;
 
-    Ok(transaction)
+    sqlx::query(
+        "UPDATE accounts SET held_cents = held_cents - $1, updated_at = NOW() WHERE id = $2",
+    )
+    .bind(authorization.amount_cents)
+    .bind(account_id)
+    .execute(&mut *conn)
+    .await?;
+
+    transition_status(conn, authorization, status, None).await
+}
+
+/// Void an authorization, releasing its hold without posting anything.
+///
+/// Voiding an already voided authorization returns it unchanged, so the
+/// call is safe to retry.
+///
+/// # Errors
+///
+/// - `TransactionNotFound`: Not an authorization of this business
+/// - `AuthorizationNotPending`: Already captured or expired
+/// - `Database`: Database error occurred
+pub async fn void_authorization(
+    pool: &DbPool,
+    authorization_id: Uuid,
+    api_key_id: Uuid,
+) -> Result<Transaction, AppError> {
+    let mut tx = pool.begin().await?;
+
+    let authorization =
+        match lock_pending_authorization(&mut tx, authorization_id, api_key_id).await {
+            Ok(authorization) => authorization,
+            Err(AppError::AuthorizationNotPending(TransactionStatus::Voided)) => {
+                return get_transaction_by_id(pool, authorization_id)
+                    .await?
+                    .ok_or(AppError::TransactionNotFound);
+            }
+            Err(e) => return Err(e),
+        };
+
+    let voided = release_authorization(&mut tx, &authorization, TransactionStatus::Voided).await?;
+
+    tx.commit().await?;
+
+    Ok(voided)
+}
+
+/// Release the holds of authorizations past their expiry.
+///
+/// Works in batches of `EXPIRY_BATCH_SIZE`, each in its own database
+/// transaction. Authorizations locked by a concurrent capture or void are
+/// skipped; the capture or void decides their fate.
+///
+/// # Returns
+///
+/// The number of authorizations expired.
+pub async fn expire_authorizations(pool: &DbPool) -> Result<u64, AppError> {
+    let mut expired_count = 0;
+
+    loop {
+        let mut tx = pool.begin().await?;
+
+        let authorizations = sqlx::query_as::<_, Transaction>(
+            r#"
+            SELECT * FROM transactions
+            WHERE transaction_type = 'authorization'
+              AND status = 'pending'
+              AND expires_at <= NOW()
+            ORDER BY expires_at
+            LIMIT $1
+            FOR UPDATE SKIP LOCKED
+            "#,
+        )
+        .bind(EXPIRY_BATCH_SIZE)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        for authorization in &authorizations {
+            release_authorization(&mut tx, authorization, TransactionStatus::Expired).await?;
+        }
+
+        tx.commit().await?;
+
+        expired_count += authorizations.len() as u64;
+        if (authorizations.len() as i64) < EXPIRY_BATCH_SIZE {
+            return Ok(expired_count);
+        }
+    }
+}
+
+/// Spawn a background task that expires stale authorizations periodically.
+///
+/// Failures of a run are logged and the task keeps going.
+pub fn spawn_authorization_expiry_task(pool: DbPool, interval: Duration) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+
+        loop {
+            ticker.tick().await;
+
+            match expire_authorizations(&pool).await {
+                Ok(0) => {}
+                Ok(expired) => {
+                    tracing::info!(expired, "Released expired authorization holds");
+                }
+                Err(e) => {
+                    tracing::error!("Authorization expiry failed: {:?}", e);
+                }
+            }
+        }
+    });
+}
+
+/// Change the status of a transaction (e.g. approve a pending transfer).
+///
+/// # Effects
+///
+/// - `pending` ⇄ `processing`: status only; funds stay held
+/// - → `completed`: moves the held amount to the destination account and
+///   writes the ledger postings
+/// - → `failed` / `canceled`: releases the held amount
+///
+/// Every change is recorded in the status history and sends a
+/// `transaction.{status}` webhook event.
+///
+/// # Errors
+///
+/// - `TransactionNotFound`: Transaction doesn't exist or belongs to another business
+/// - `InvalidRequest`: Status can't be set through this call, or the
+///   transaction is an authorization (use capture or void)
+/// - `InvalidStatusTransition`: The transition table doesn't allow the change
+/// - `AccountFrozen` / `AccountClosed`: Completing a transfer out of a frozen
+///   or closed account, or into a closed one (fail or cancel it instead)
+/// - `Database`: Database error occurred
+pub async fn update_transaction_status(
+    pool: &DbPool,
+    transaction_id: Uuid,
+    next: TransactionStatus,
+    reason: Option<String>,
+    api_key_id: Uuid,
+) -> Result<Transaction, AppError> {
+    // Reversed, captured, voided and expired follow from their own operations
+    if !matches!(
+        next,
+        TransactionStatus::Pending
+            | TransactionStatus::Processing
+            | TransactionStatus::Completed
+            | TransactionStatus::Failed
+            | TransactionStatus::Canceled
+    ) {
+        return Err(AppError::InvalidRequest(format!(
+            "Status '{}' cannot be set directly",
+            next
+        )));
+    }
+
+    let mut tx = pool.begin().await?;
+
+    let transaction = sqlx::query_as::<_, Transaction>(
+        "SELECT * FROM transactions WHERE id = $1 AND api_key_id = $2 FOR UPDATE",
+    )
+    .bind(transaction_id)
+    .bind(api_key_id)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or(AppError::TransactionNotFound)?;
+
+    if transaction.transaction_type == "authorization" {
+        return Err(AppError::InvalidRequest(
+            "Authorizations change status through capture and void".to_string(),
+        ));
+    }
+
+    // Checked before touching balances; `transition_status` checks again
+    if !transaction.status.can_transition_to(next) {
+        return Err(AppError::InvalidStatusTransition {
+            from: transaction.status,
+            to: next,
+        });
+    }
+
+    let releases_funds = transaction.status.holds_funds() && !next.holds_funds();
+
+    // Lock both accounts on every path, so the webhook events queued below
+    // are numbered in commit order for each account (see
+    // `webhook_service::enqueue_event`)
+    lock_transaction_accounts(&mut tx, &transaction).await?;
+
+    if releases_funds && next == TransactionStatus::Completed {
+        let from_account_id = transaction
+            .from_account_id
+            .ok_or(AppError::AccountNotFound)?;
+        let to_account_id = transaction.to_account_id.ok_or(AppError::AccountNotFound)?;
+
+        lock_account(&mut tx, from_account_id, FundsFlow::Outgoing).await?;
+        lock_account(&mut tx, to_account_id, FundsFlow::Incoming).await?;
+    }
+
+    if releases_funds {
+        let from_account_id = transaction
+            .from_account_id
+            .ok_or(AppError::AccountNotFound)?;
+
+        if next == TransactionStatus::Completed {
+            let to_account_id = transaction.to_account_id.ok_or(AppError::AccountNotFound)?;
+
+            move_funds(
+                &mut tx,
+                from_account_id,
+                to_account_id,
+                transaction.amount_cents,
+                transaction.credited_amount_cents(),
+                transaction.amount_cents,
+            )
+            .await?;
+        } else {
+            sqlx::query(
+                "UPDATE accounts SET held_cents = held_cents - $1, updated_at = NOW() WHERE id = $2",
+            )
+            .bind(transaction.amount_cents)
+            .bind(from_account_id)
+            .execute(&mut *tx)
+            .await?;
+        }
+    }
+
+    let updated = transition_status(&mut tx, &transaction, next, reason.as_deref()).await?;
+
+    // Post the money movement now that it happened
+    if updated.status.is_posted() {
+        super::ledger_service::record_postings(&mut tx, &updated).await?;
+    }
+
+    tx.commit().await?;
+
+    Ok(updated)
+}
+
+/// Get the status history of a transaction, oldest first.
+///
+/// # Errors
+///
+/// - `TransactionNotFound`: Transaction doesn't exist or belongs to another business
+/// - `Database`: Database error occurred
+pub async fn get_status_history(
+    pool: &DbPool,
+    transaction_id: Uuid,
+    api_key_id: Uuid,
+) -> Result<Vec<TransactionStatusChange>, AppError> {
+    let exists: bool = sqlx::query_scalar(
+        "SELECT EXISTS(SELECT 1 FROM transactions WHERE id = $1 AND api_key_id = $2)",
+    )
+    .bind(transaction_id)
+    .bind(api_key_id)
+    .fetch_one(pool)
+    .await?;
+
+    if !exists {
+        return Err(AppError::TransactionNotFound);
+    }
+
+    let history = sqlx::query_as::<_, TransactionStatusChange>(
+        r#"
+        SELECT * FROM transaction_status_history
+        WHERE transaction_id = $1
+        ORDER BY created_at, id
+        "#,
+    )
+    .bind(transaction_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(history)
+}
+
+/// Get transaction by ID.
+pub async fn get_transaction_by_id(
+    pool: &DbPool,
+    transaction_id: Uuid,
+) -> Result<Option<Transaction>, AppError> {
+    let transaction = sqlx::query_as::<_, Transaction>("SELECT * FROM transactions WHERE id = $1")
+        .bind(transaction_id)
+        .fetch_optional(pool)
+        .await?;
+
+    Ok(transaction)
+}
+
+/// List the transactions of an account, newest first, with keyset pagination.
+///
+/// # Pagination
+///
+/// Uses keyset pagination on `(created_at, id)` rather than OFFSET, so pages
+/// stay stable while new transactions are written and deep pages stay cheap:
+/// outgoing and incoming transactions are each read from their own index
+/// (`idx_transactions_from_created` / `idx_transactions_to_created`) up to
+/// one page, then merged.
+///
+/// One extra row is fetched to determine whether another page exists.
+///
+/// # Returns
+///
+/// The transactions on this page and whether more transactions follow.
+///
+/// # Errors
+///
+/// - `InvalidRequest`: Limit out of range, contradictory filters,
+///   `metadata_value` without `metadata_key`, or a `starting_after` that is
+///   not a transaction of the account
+/// - `Database`: Database error occurred
+pub async fn list_account_transactions(
+    pool: &DbPool,
+    account_id: Uuid,
+    query: &TransactionListQuery,
+) -> Result<(Vec<Transaction>, bool), AppError> {
+    let limit = pagination::page_limit(query.limit).map_err(AppError::InvalidRequest)?;
+
+    if let (Some(min), Some(max)) = (query.min_amount_cents, query.max_amount_cents)
+        && min > max
+    {
+        return Err(AppError::InvalidRequest(
+            "min_amount_cents must not exceed max_amount_cents".to_string(),
+        ));
+    }
+
+    if let (Some(after), Some(before)) = (query.created_after, query.created_before)
+        && after >= before
+    {
+        return Err(AppError::InvalidRequest(
+            "created_after must be earlier than created_before".to_string(),
+        ));
+    }
+
+    if query.metadata_value.is_some() && query.metadata_key.is_none() {
+        return Err(AppError::InvalidRequest(
+            "metadata_value requires metadata_key".to_string(),
+        ));
+    }
+
+    // An unknown cursor would otherwise silently return an empty last page
+    let cursor: Option<(DateTime<Utc>, Uuid)> = match query.starting_after {
+        Some(cursor) => Some(
+            sqlx::query_as(
+                r#"
+                SELECT created_at, id FROM transactions
+                WHERE id = $1 AND (from_account_id = $2 OR to_account_id = $2)
+                "#,
+            )
+            .bind(cursor)
+            .bind(account_id)
+            .fetch_optional(pool)
+            .await?
+            .ok_or_else(|| {
+                AppError::InvalidRequest(
+                    "starting_after is not a transaction of this account".to_string(),
+                )
+            })?,
+        ),
+        None => None,
+    };
+
+    // One keyset scan per side of the account, each walking its
+    // (account, created_at, id) index backwards and stopping after a page,
+    // merged into one page. Transfers never have the same account on both
+    // sides, so no transaction appears twice. Amount filters compare the
+    // amount in the account's currency: sent on the outgoing side, received
+    // on the incoming one (they differ for cross-currency transfers).
+    //
+    // Optional filters are bound as NULL when absent so the statement stays
+    // static; the cursor instead falls back to a bound past every row, so it
+    // always stays an index condition.
+    let mut transactions = sqlx::query_as::<_, Transaction>(
+        r#"
+        (
+            SELECT * FROM transactions
+            WHERE from_account_id = $1
+              AND ($2::text IS NULL OR $2 = 'outgoing')
+              AND ($3::text IS NULL OR transaction_type = $3)
+              AND ($4::timestamptz IS NULL OR created_at >= $4)
+              AND ($5::timestamptz IS NULL OR created_at < $5)
+              AND ($6::bigint IS NULL OR amount_cents >= $6)
+              AND ($7::bigint IS NULL OR amount_cents <= $7)
+              AND ($8::text IS NULL OR status = $8)
+              AND ($9::text IS NULL OR metadata ? $9)
+              AND ($10::text IS NULL OR metadata @> jsonb_build_object($9::text, $10::text))
+              AND (created_at, id) < (
+                  COALESCE($11::timestamptz, 'infinity'),
+                  COALESCE($12::uuid, 'ffffffff-ffff-ffff-ffff-ffffffffffff')
+              )
+            ORDER BY created_at DESC, id DESC
+            LIMIT $13
+        )
+        UNION ALL
+        (
+            SELECT * FROM transactions
+            WHERE to_account_id = $1
+              AND ($2::text IS NULL OR $2 = 'incoming')
+              AND ($3::text IS NULL OR transaction_type = $3)
+              AND ($4::timestamptz IS NULL OR created_at >= $4)
+              AND ($5::timestamptz IS NULL OR created_at < $5)
+              AND ($6::bigint IS NULL OR COALESCE(destination_amount_cents, amount_cents) >= $6)
+              AND ($7::bigint IS NULL OR COALESCE(destination_amount_cents, amount_cents) <= $7)
+              AND ($8::text IS NULL OR status = $8)
+              AND ($9::text IS NULL OR metadata ? $9)
+              AND ($10::text IS NULL OR metadata @> jsonb_build_object($9::text, $10::text))
+              AND (created_at, id) < (
+                  COALESCE($11::timestamptz, 'infinity'),
+                  COALESCE($12::uuid, 'ffffffff-ffff-ffff-ffff-ffffffffffff')
+              )
+            ORDER BY created_at DESC, id DESC
+            LIMIT $13
+        )
+        ORDER BY created_at DESC, id DESC
+        LIMIT $13
+        "#,
+    )
+    .bind(account_id)
+    .bind(query.direction.map(|d| d.as_str()))
+    .bind(&query.transaction_type)
+    .bind(query.created_after)
+    .bind(query.created_before)
+    .bind(query.min_amount_cents)
+    .bind(query.max_amount_cents)
+    .bind(query.status)
+    .bind(&query.metadata_key)
+    .bind(&query.metadata_value)
+    .bind(cursor.map(|(created_at, _)| created_at))
+    .bind(cursor.map(|(_, id)| id))
+    .bind(limit + 1)
+    .fetch_all(pool)
+    .await?;
+
+    let has_more = transactions.len() as i64 > limit;
+    transactions.truncate(limit as usize);
+
+    Ok((transactions, has_more))
+}
+
+#[cfg(all(test, feature = "db-tests"))]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        models::fx::CreateFxQuoteRequest,
+        services::{
+            fx_service,
+            test_support::{balances, create_account, create_business, create_webhook_endpoint},
+        },
+    };
+
+    fn transfer_request(from: Uuid, to: Uuid, amount_cents: i64, pending: bool) -> TransferRequest {
+        serde_json::from_value(json!({
+            "from_account_id": from,
+            "to_account_id": to,
+            "amount_cents": amount_cents,
+            "pending": pending,
+        }))
+        .unwrap()
+    }
+
+    /// Sequence number of the webhook event of a given type for a transaction.
+    async fn event_sequence(pool: &DbPool, transaction_id: Uuid, event_type: &str) -> i64 {
+        sqlx::query_scalar(
+            "SELECT sequence FROM webhook_events WHERE transaction_id = $1 AND event_type = $2",
+        )
+        .bind(transaction_id)
+        .bind(event_type)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+    }
+
+    /// Wait until another session is waiting for a lock held by `pid`.
+    async fn wait_until_blocked_by(pool: &DbPool, pid: i32) {
+        for _ in 0..100 {
+            let blocked: bool = sqlx::query_scalar(
+                "SELECT EXISTS(SELECT 1 FROM pg_stat_activity WHERE $1 = ANY(pg_blocking_pids(pid)))",
+            )
+            .bind(pid)
+            .fetch_one(pool)
+            .await
+            .unwrap();
+
+            if blocked {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+
+        panic!("no session waited for a lock held by {}", pid);
+    }
+
+    #[sqlx::test]
+    async fn status_change_events_follow_commit_order_per_account(pool: DbPool) {
+        let business = create_business(&pool).await;
+        let from = create_account(&pool, business, "USD", 10_000).await;
+        let to = create_account(&pool, business, "USD", 0).await;
+        create_webhook_endpoint(&pool, business).await;
+
+        let transfer = execute_transfer(&pool, transfer_request(from, to, 1_000, true), business)
+            .await
+            .unwrap();
+
+        // A credit to the destination account is in flight
+        let mut credit_tx = pool.begin().await.unwrap();
+        lock_account(&mut credit_tx, to, FundsFlow::Incoming)
+            .await
+            .unwrap();
+        let credit_pid: i32 = sqlx::query_scalar("SELECT pg_backend_pid()")
+            .fetch_one(&mut *credit_tx)
+            .await
+            .unwrap();
+
+        let status_change = tokio::spawn({
+            let pool = pool.clone();
+            async move {
+                update_transaction_status(
+                    &pool,
+                    transfer.id,
+                    TransactionStatus::Processing,
+                    None,
+                    business,
+                )
+                .await
+            }
+        });
+
+        // The status change must wait for the credit's lock, and so cannot
+        // queue its event before the credit commits
+        wait_until_blocked_by(&pool, credit_pid).await;
+        assert!(!status_change.is_finished());
+
+        let credit = record_opening_balance(&mut credit_tx, to, 500, business)
+            .await
+            .unwrap();
+        credit_tx.commit().await.unwrap();
+
+        status_change.await.unwrap().unwrap();
+
+        assert!(
+            event_sequence(&pool, credit.id, "transaction.opening_balance").await
+                < event_sequence(&pool, transfer.id, "transaction.processing").await
+        );
+    }
+
+    async fn credit(
+        pool: &DbPool,
+        account_id: Uuid,
+        amount_cents: i64,
+        idempotency_key: &str,
+        api_key_id: Uuid,
+    ) -> Result<Transaction, AppError> {
+        execute_credit(
+            pool,
+            account_id,
+            amount_cents,
+            None,
+            Metadata::new(),
+            Some(idempotency_key.to_string()),
+            api_key_id,
+        )
+        .await
+    }
+
+    #[sqlx::test]
+    async fn idempotency_keys_are_scoped_per_business(pool: DbPool) {
+        let first_business = create_business(&pool).await;
+        let second_business = create_business(&pool).await;
+        let first_account = create_account(&pool, first_business, "USD", 0).await;
+        let second_account = create_account(&pool, second_business, "USD", 0).await;
+
+        let first = credit(&pool, first_account, 500, "order-1", first_business)
+            .await
+            .unwrap();
+        let second = credit(&pool, second_account, 500, "order-1", second_business)
+            .await
+            .unwrap();
+        assert_ne!(first.id, second.id);
+
+        // A replay within the business returns the original transaction
+        let replay = credit(&pool, first_account, 500, "order-1", first_business)
+            .await
+            .unwrap();
+        assert_eq!(replay.id, first.id);
+
+        assert_eq!(balances(&pool, first_account).await, (500, 0));
+    }
+
+    #[sqlx::test]
+    async fn idempotency_key_reused_with_another_payload_conflicts(pool: DbPool) {
+        let business = create_business(&pool).await;
+        let account = create_account(&pool, business, "USD", 0).await;
+
+        credit(&pool, account, 500, "order-1", business)
+            .await
+            .unwrap();
+
+        let result = credit(&pool, account, 700, "order-1", business).await;
+        assert!(matches!(result, Err(AppError::IdempotencyKeyConflict)));
+    }
+
+    #[sqlx::test]
+    async fn concurrent_duplicates_create_one_transaction(pool: DbPool) {
+        let business = create_business(&pool).await;
+        let account = create_account(&pool, business, "USD", 0).await;
+
+        let duplicates: Vec<_> = (0..4)
+            .map(|_| {
+                let pool = pool.clone();
+                tokio::spawn(async move { credit(&pool, account, 500, "order-1", business).await })
+            })
+            .collect();
+
+        let mut ids = Vec::new();
+        for duplicate in duplicates {
+            ids.push(duplicate.await.unwrap().unwrap().id);
+        }
+
+        assert!(ids.iter().all(|id| *id == ids[0]));
+        assert_eq!(balances(&pool, account).await, (500, 0));
+    }
+
+    #[sqlx::test]
+    async fn duplicate_gives_up_while_original_is_running(pool: DbPool) {
+        let business = create_business(&pool).await;
+        let account = create_account(&pool, business, "USD", 0).await;
+
+        // The original request holds the key and doesn't finish in time
+        let mut original_tx = pool.begin().await.unwrap();
+        lock_idempotency_key(&mut original_tx, business, "credit", "order-1")
+            .await
+            .unwrap();
+
+        let result = credit(&pool, account, 500, "order-1", business).await;
+        assert!(matches!(result, Err(AppError::RequestInProgress)));
+
+        original_tx.rollback().await.unwrap();
+        credit(&pool, account, 500, "order-1", business)
+            .await
+            .unwrap();
+    }
+
+    async fn reverse(
+        pool: &DbPool,
+        transaction_id: Uuid,
+        amount_cents: Option<i64>,
+        api_key_id: Uuid,
+    ) -> Result<Transaction, AppError> {
+        execute_reversal(pool, transaction_id, amount_cents, None, None, api_key_id).await
+    }
+
+    #[sqlx::test]
+    async fn partial_reversals_never_exceed_the_original(pool: DbPool) {
+        let business = create_business(&pool).await;
+        let from = create_account(&pool, business, "USD", 10_000).await;
+        let to = create_account(&pool, business, "USD", 0).await;
+
+        let transfer = execute_transfer(&pool, transfer_request(from, to, 1_000, false), business)
+            .await
+            .unwrap();
+
+        reverse(&pool, transfer.id, Some(600), business)
+            .await
+            .unwrap();
+
+        let result = reverse(&pool, transfer.id, Some(500), business).await;
+        assert!(matches!(
+            result,
+            Err(AppError::ReversalExceedsRemaining(400))
+        ));
+
+        // Partially reversed: still completed
+        let original = get_transaction_by_id(&pool, transfer.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(original.status, TransactionStatus::Completed);
+        assert_eq!(balances(&pool, from).await, (9_600, 0));
+        assert_eq!(balances(&pool, to).await, (400, 0));
+    }
+
+    #[sqlx::test]
+    async fn full_reversal_marks_the_original_reversed(pool: DbPool) {
+        let business = create_business(&pool).await;
+        let from = create_account(&pool, business, "USD", 10_000).await;
+        let to = create_account(&pool, business, "USD", 0).await;
+
+        let transfer = execute_transfer(&pool, transfer_request(from, to, 1_000, false), business)
+            .await
+            .unwrap();
+
+        reverse(&pool, transfer.id, Some(300), business)
+            .await
+            .unwrap();
+
+        // Without an amount, the remaining 700 is reversed
+        let reversal = reverse(&pool, transfer.id, None, business).await.unwrap();
+        assert_eq!(reversal.amount_cents, 700);
+        assert_eq!(reversal.reversed_transaction_id, Some(transfer.id));
+
+        let original = get_transaction_by_id(&pool, transfer.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(original.status, TransactionStatus::Reversed);
+        assert_eq!(balances(&pool, from).await, (10_000, 0));
+        assert_eq!(balances(&pool, to).await, (0, 0));
+
+        let result = reverse(&pool, transfer.id, None, business).await;
+        assert!(matches!(result, Err(AppError::ReversalExceedsRemaining(0))));
+    }
+
+    #[sqlx::test]
+    async fn completing_a_pending_transfer_moves_the_held_funds(pool: DbPool) {
+        let business = create_business(&pool).await;
+        let from = create_account(&pool, business, "USD", 10_000).await;
+        let to = create_account(&pool, business, "USD", 0).await;
+
+        let transfer = execute_transfer(&pool, transfer_request(from, to, 1_000, true), business)
+            .await
+            .unwrap();
+        assert_eq!(balances(&pool, from).await, (10_000, 1_000));
+
+        let updated = update_transaction_status(
+            &pool,
+            transfer.id,
+            TransactionStatus::Completed,
+            None,
+            business,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(updated.status, TransactionStatus::Completed);
+        assert_eq!(balances(&pool, from).await, (9_000, 0));
+        assert_eq!(balances(&pool, to).await, (1_000, 0));
+    }
+
+    #[sqlx::test]
+    async fn failing_or_canceling_a_pending_transfer_releases_the_hold(pool: DbPool) {
+        let business = create_business(&pool).await;
+        let from = create_account(&pool, business, "USD", 10_000).await;
+        let to = create_account(&pool, business, "USD", 0).await;
+
+        for next in [TransactionStatus::Failed, TransactionStatus::Canceled] {
+            let transfer =
+                execute_transfer(&pool, transfer_request(from, to, 1_000, true), business)
+                    .await
+                    .unwrap();
+            assert_eq!(balances(&pool, from).await, (10_000, 1_000));
+
+            let updated = update_transaction_status(&pool, transfer.id, next, None, business)
+                .await
+                .unwrap();
+
+            assert_eq!(updated.status, next);
+            assert_eq!(balances(&pool, from).await, (10_000, 0), "{}", next);
+            assert_eq!(balances(&pool, to).await, (0, 0), "{}", next);
+        }
+    }
+
+    #[sqlx::test]
+    async fn rejects_transitions_outside_the_table(pool: DbPool) {
+        let business = create_business(&pool).await;
+        let from = create_account(&pool, business, "USD", 10_000).await;
+        let to = create_account(&pool, business, "USD", 0).await;
+
+        let transfer = execute_transfer(&pool, transfer_request(from, to, 1_000, true), business)
+            .await
+            .unwrap();
+        update_transaction_status(
+            &pool,
+            transfer.id,
+            TransactionStatus::Failed,
+            None,
+            business,
+        )
+        .await
+        .unwrap();
+
+        for next in [TransactionStatus::Completed, TransactionStatus::Pending] {
+            let result = update_transaction_status(&pool, transfer.id, next, None, business).await;
+            assert!(
+                matches!(
+                    result,
+                    Err(AppError::InvalidStatusTransition {
+                        from: TransactionStatus::Failed,
+                        to,
+                    }) if to == next
+                ),
+                "failed -> {}",
+                next
+            );
+        }
+
+        // Nothing moved after the hold was released
+        assert_eq!(balances(&pool, from).await, (10_000, 0));
+        assert_eq!(balances(&pool, to).await, (0, 0));
+    }
+
+    /// Quote `source_amount_cents` USD in EUR at 0.92.
+    async fn usd_to_eur_quote(pool: &DbPool, api_key_id: Uuid, source_amount_cents: i64) -> Uuid {
+        sqlx::query(
+            "INSERT INTO fx_rates (source_currency, destination_currency, rate_e12) \
+             VALUES ('USD', 'EUR', 920000000000) ON CONFLICT DO NOTHING",
+        )
+        .execute(pool)
+        .await
+        .unwrap();
+
+        let request: CreateFxQuoteRequest = serde_json::from_value(json!({
+            "source_currency": "USD",
+            "destination_currency": "EUR",
+            "source_amount_cents": source_amount_cents,
+        }))
+        .unwrap();
+
+        fx_service::create_quote(pool, api_key_id, request)
+            .await
+            .unwrap()
+            .id
+    }
+
+    fn fx_transfer_request(
+        from: Uuid,
+        to: Uuid,
+        amount_cents: i64,
+        quote: Uuid,
+    ) -> TransferRequest {
+        serde_json::from_value(json!({
+            "from_account_id": from,
+            "to_account_id": to,
+            "amount_cents": amount_cents,
+            "fx_quote_id": quote,
+        }))
+        .unwrap()
+    }
+
+    #[sqlx::test]
+    async fn fx_transfer_credits_the_quoted_amount_once(pool: DbPool) {
+        let business = create_business(&pool).await;
+        let from = create_account(&pool, business, "USD", 10_000).await;
+        let to = create_account(&pool, business, "EUR", 0).await;
+        let quote = usd_to_eur_quote(&pool, business, 1_000).await;
+
+        let transfer =
+            execute_transfer(&pool, fx_transfer_request(from, to, 1_000, quote), business)
+                .await
+                .unwrap();
+
+        assert_eq!(transfer.fx_quote_id, Some(quote));
+        assert_eq!(transfer.destination_amount_cents, Some(920));
+        assert_eq!(balances(&pool, from).await, (9_000, 0));
+        assert_eq!(balances(&pool, to).await, (920, 0));
+
+        let result =
+            execute_transfer(&pool, fx_transfer_request(from, to, 1_000, quote), business).await;
+        assert!(matches!(result, Err(AppError::FxQuoteAlreadyUsed)));
+        assert_eq!(balances(&pool, from).await, (9_000, 0));
+        assert_eq!(balances(&pool, to).await, (920, 0));
+
+        // Each side filters by the amount in its own currency
+        let matches = async |account_id: Uuid, min: i64, max: i64| {
+            let query: TransactionListQuery = serde_json::from_value(json!({
+                "min_amount_cents": min,
+                "max_amount_cents": max,
+            }))
+            .unwrap();
+            let (page, _) = list_account_transactions(&pool, account_id, &query)
+                .await
+                .unwrap();
+            page.into_iter()
+                .map(|transaction| transaction.id)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(matches(to, 920, 920).await, vec![transfer.id]);
+        assert!(matches(to, 1_000, 1_000).await.is_empty());
+        assert_eq!(matches(from, 1_000, 1_000).await, vec![transfer.id]);
+        assert!(matches(from, 920, 920).await.is_empty());
+    }
+
+    #[sqlx::test]
+    async fn fx_transfer_rejects_an_expired_quote(pool: DbPool) {
+        let business = create_business(&pool).await;
+        let from = create_account(&pool, business, "USD", 10_000).await;
+        let to = create_account(&pool, business, "EUR", 0).await;
+        let quote = usd_to_eur_quote(&pool, business, 1_000).await;
+
+        sqlx::query("UPDATE fx_quotes SET expires_at = NOW() - INTERVAL '1 second' WHERE id = $1")
+            .bind(quote)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let result =
+            execute_transfer(&pool, fx_transfer_request(from, to, 1_000, quote), business).await;
+        assert!(matches!(result, Err(AppError::FxQuoteExpired)));
+        assert_eq!(balances(&pool, from).await, (10_000, 0));
+        assert_eq!(balances(&pool, to).await, (0, 0));
+    }
+
+    /// Every page of an account's history, following `starting_after`.
+    async fn list_all_pages(
+        pool: &DbPool,
+        account_id: Uuid,
+        query: serde_json::Value,
+    ) -> Vec<Uuid> {
+        let mut ids = Vec::new();
+
+        loop {
+            let mut page_query = query.clone();
+            page_query["starting_after"] = json!(ids.last());
+            let page_query: TransactionListQuery = serde_json::from_value(page_query).unwrap();
+
+            let (page, has_more) = list_account_transactions(pool, account_id, &page_query)
+                .await
+                .unwrap();
+            ids.extend(page.iter().map(|transaction| transaction.id));
+
+            if !has_more {
+                return ids;
+            }
+        }
+    }
+
+    #[sqlx::test]
+    async fn pages_merge_incoming_and_outgoing_newest_first(pool: DbPool) {
+        let business = create_business(&pool).await;
+        let account = create_account(&pool, business, "USD", 10_000).await;
+        let other = create_account(&pool, business, "USD", 10_000).await;
+
+        let mut outgoing = Vec::new();
+        let mut incoming = Vec::new();
+        for amount_cents in 1..=5 {
+            let sent = execute_transfer(
+                &pool,
+                transfer_request(account, other, amount_cents, false),
+                business,
+            )
+            .await
+            .unwrap();
+            outgoing.push(sent.id);
+
+            let received = execute_transfer(
+                &pool,
+                transfer_request(other, account, amount_cents, false),
+                business,
+            )
+            .await
+            .unwrap();
+            incoming.push(received.id);
+        }
+
+        let all = list_all_pages(&pool, account, json!({})).await;
+        let one_by_one = list_all_pages(&pool, account, json!({ "limit": 1 })).await;
+        let by_three = list_all_pages(&pool, account, json!({ "limit": 3 })).await;
+
+        // Ten transfers and the opening balance, newest first, each once
+        let expected: Vec<Uuid> = sqlx::query_scalar(
+            r#"
+            SELECT id FROM transactions
+            WHERE from_account_id = $1 OR to_account_id = $1
+            ORDER BY created_at DESC, id DESC
+            "#,
+        )
+        .bind(account)
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        assert_eq!(expected.len(), 11);
+        assert_eq!(all, expected);
+        assert_eq!(one_by_one, expected);
+        assert_eq!(by_three, expected);
+
+        outgoing.reverse();
+        incoming.reverse();
+        assert_eq!(
+            list_all_pages(
+                &pool,
+                account,
+                json!({ "direction": "outgoing", "limit": 2 })
+            )
+            .await,
+            outgoing
+        );
+        assert_eq!(
+            list_all_pages(
+                &pool,
+                account,
+                json!({ "direction": "incoming", "transaction_type": "transfer", "limit": 2 })
+            )
+            .await,
+            incoming
+        );
+    }
+
+    #[sqlx::test]
+    async fn two_account_operations_lock_in_id_order(pool: DbPool) {
+        let business = create_business(&pool).await;
+        let first = create_account(&pool, business, "USD", 10_000).await;
+        let second = create_account(&pool, business, "USD", 10_000).await;
+        let (low, high) = (first.min(second), first.max(second));
+
+        let original = execute_transfer(&pool, transfer_request(low, high, 1_000, false), business)
+            .await
+            .unwrap();
+
+        // Reversing low -> high and transferring high -> low both move money
+        // out of the higher account first
+        for name in ["reversal", "transfer"] {
+            // A transfer from the lower account has taken its first lock
+            let mut transfer_tx = pool.begin().await.unwrap();
+            lock_accounts(&mut transfer_tx, &[low]).await.unwrap();
+            let transfer_pid: i32 = sqlx::query_scalar("SELECT pg_backend_pid()")
+                .fetch_one(&mut *transfer_tx)
+                .await
+                .unwrap();
+
+            let operation = tokio::spawn({
+                let pool = pool.clone();
+                async move {
+                    match name {
+                        "reversal" => {
+                            execute_reversal(&pool, original.id, Some(100), None, None, business)
+                                .await
+                        }
+                        _ => {
+                            execute_transfer(
+                                &pool,
+                                transfer_request(high, low, 100, false),
+                                business,
+                            )
+                            .await
+                        }
+                    }
+                }
+            });
+            wait_until_blocked_by(&pool, transfer_pid).await;
+
+            // Waiting for the lower account, the operation holds no lock on
+            // the higher one the transfer needs next
+            let mut probe = pool.begin().await.unwrap();
+            let free = sqlx::query("SELECT 1 FROM accounts WHERE id = $1 FOR UPDATE NOWAIT")
+                .bind(high)
+                .execute(&mut *probe)
+                .await;
+            assert!(free.is_ok(), "{} locked the higher account first", name);
+            drop(probe);
+
+            lock_accounts(&mut transfer_tx, &[high]).await.unwrap();
+            transfer_tx.commit().await.unwrap();
+
+            operation.await.unwrap().unwrap();
+        }
+    }
+
+    async fn authorize(
+        pool: &DbPool,
+        account_id: Uuid,
+        amount_cents: i64,
+        api_key_id: Uuid,
+    ) -> Result<Transaction, AppError> {
+        execute_authorization(pool, account_id, amount_cents, None, None, None, api_key_id).await
+    }
+
+    async fn capture(
+        pool: &DbPool,
+        authorization_id: Uuid,
+        amount_cents: Option<i64>,
+        api_key_id: Uuid,
+    ) -> Result<Transaction, AppError> {
+        execute_capture(pool, authorization_id, amount_cents, None, None, api_key_id).await
+    }
+
+    async fn debit(
+        pool: &DbPool,
+        account_id: Uuid,
+        amount_cents: i64,
+        api_key_id: Uuid,
+    ) -> Result<Transaction, AppError> {
+        execute_debit(
+            pool,
+            account_id,
+            amount_cents,
+            None,
+            Metadata::new(),
+            None,
+            api_key_id,
+        )
+        .await
+    }
+
+    async fn status_of(pool: &DbPool, transaction_id: Uuid) -> TransactionStatus {
+        get_transaction_by_id(pool, transaction_id)
+            .await
+            .unwrap()
+            .unwrap()
+            .status
+    }
+
+    #[sqlx::test]
+    async fn partial_capture_releases_the_rest_of_the_hold(pool: DbPool) {
+        let business = create_business(&pool).await;
+        let account = create_account(&pool, business, "USD", 10_000).await;
+
+        let authorization = authorize(&pool, account, 3_000, business).await.unwrap();
+        assert_eq!(authorization.status, TransactionStatus::Pending);
+        assert_eq!(balances(&pool, account).await, (10_000, 3_000));
+
+        let result = capture(&pool, authorization.id, Some(3_001), business).await;
+        assert!(matches!(result, Err(AppError::InvalidRequest(_))));
+
+        let captured = capture(&pool, authorization.id, Some(1_200), business)
+            .await
+            .unwrap();
+        assert_eq!(captured.amount_cents, 1_200);
+        assert_eq!(captured.authorization_id, Some(authorization.id));
+        assert_eq!(balances(&pool, account).await, (8_800, 0));
+        assert_eq!(
+            status_of(&pool, authorization.id).await,
+            TransactionStatus::Captured
+        );
+
+        // Captured at most once, and no longer voidable
+        let result = capture(&pool, authorization.id, Some(100), business).await;
+        assert!(matches!(
+            result,
+            Err(AppError::AuthorizationNotPending(
+                TransactionStatus::Captured
+            ))
+        ));
+        let result = void_authorization(&pool, authorization.id, business).await;
+        assert!(matches!(
+            result,
+            Err(AppError::AuthorizationNotPending(
+                TransactionStatus::Captured
+            ))
+        ));
+        assert_eq!(balances(&pool, account).await, (8_800, 0));
+    }
+
+    #[sqlx::test]
+    async fn holds_are_never_overspent(pool: DbPool) {
+        let business = create_business(&pool).await;
+        let account = create_account(&pool, business, "USD", 10_000).await;
+
+        let authorization = authorize(&pool, account, 7_000, business).await.unwrap();
+
+        // Only the 3_000 not held is available to debits and new holds
+        let result = debit(&pool, account, 3_001, business).await;
+        assert!(matches!(result, Err(AppError::InsufficientBalance)));
+        let result = authorize(&pool, account, 3_001, business).await;
+        assert!(matches!(result, Err(AppError::InsufficientBalance)));
+
+        debit(&pool, account, 3_000, business).await.unwrap();
+        assert_eq!(balances(&pool, account).await, (7_000, 7_000));
+
+        // The schema backs the check: the balance can't drop below the holds
+        let result =
+            sqlx::query("UPDATE accounts SET balance_cents = balance_cents - 1 WHERE id = $1")
+                .bind(account)
+                .execute(&pool)
+                .await;
+        assert!(result.is_err());
+
+        // The hold still covers a full capture
+        capture(&pool, authorization.id, None, business)
+            .await
+            .unwrap();
+        assert_eq!(balances(&pool, account).await, (0, 0));
+    }
+
+    #[sqlx::test]
+    async fn void_releases_the_hold_once(pool: DbPool) {
+        let business = create_business(&pool).await;
+        let account = create_account(&pool, business, "USD", 10_000).await;
+
+        let authorization = authorize(&pool, account, 3_000, business).await.unwrap();
+
+        let voided = void_authorization(&pool, authorization.id, business)
+            .await
+            .unwrap();
+        assert_eq!(voided.status, TransactionStatus::Voided);
+        assert_eq!(balances(&pool, account).await, (10_000, 0));
+
+        // A retried void returns the voided authorization and releases nothing more
+        let retried = void_authorization(&pool, authorization.id, business)
+            .await
+            .unwrap();
+        assert_eq!(retried.id, authorization.id);
+        assert_eq!(retried.status, TransactionStatus::Voided);
+        assert_eq!(balances(&pool, account).await, (10_000, 0));
+
+        let result = capture(&pool, authorization.id, None, business).await;
+        assert!(matches!(
+            result,
+            Err(AppError::AuthorizationNotPending(TransactionStatus::Voided))
+        ));
+    }
+
+    #[sqlx::test]
+    async fn stale_holds_expire(pool: DbPool) {
+        let business = create_business(&pool).await;
+        let account = create_account(&pool, business, "USD", 10_000).await;
+
+        let stale = authorize(&pool, account, 3_000, business).await.unwrap();
+        let live = authorize(&pool, account, 1_000, business).await.unwrap();
+        sqlx::query(
+            "UPDATE transactions SET expires_at = NOW() - INTERVAL '1 second' WHERE id = $1",
+        )
+        .bind(stale.id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        // Past its expiry, a hold can't be captured even before the task runs
+        let result = capture(&pool, stale.id, None, business).await;
+        assert!(matches!(
+            result,
+            Err(AppError::AuthorizationNotPending(
+                TransactionStatus::Expired
+            ))
+        ));
+
+        assert_eq!(expire_authorizations(&pool).await.unwrap(), 1);
+        assert_eq!(status_of(&pool, stale.id).await, TransactionStatus::Expired);
+        assert_eq!(status_of(&pool, live.id).await, TransactionStatus::Pending);
+        assert_eq!(balances(&pool, account).await, (10_000, 1_000));
+
+        // Nothing is left to expire, and an expired hold can't be voided
+        assert_eq!(expire_authorizations(&pool).await.unwrap(), 0);
+        let result = void_authorization(&pool, stale.id, business).await;
+        assert!(matches!(
+            result,
+            Err(AppError::AuthorizationNotPending(
+                TransactionStatus::Expired
+            ))
+        ));
+        assert_eq!(balances(&pool, account).await, (10_000, 1_000));
+    }
 }
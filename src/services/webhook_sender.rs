@@ -0,0 +1,277 @@
+//! Shared HTTP client and delivery limits for webhooks.
+//!
+//! One `WebhookSender` is created at startup and shared by the dispatcher
+//! and the test-ping endpoint. It provides:
+//! - A single pooled `reqwest::Client` (built by `WebhookUrlGuard`, so the
+//!   SSRF checks apply), instead of a new client and connection per webhook
+//! - A global limit on in-flight webhook requests, bounding sockets and
+//!   memory no matter how many events are due
+//! - A per-endpoint limit on in-flight requests, so one slow receiver can't
+//!   take all global slots
+//! - A per-endpoint token bucket, so a burst of events (e.g. a month-end
+//!   batch run) reaches a receiver at a bounded rate
+//!
+//! Limits are per process; with several server instances each applies its
+//! own. Endpoint state is created on first use and dropped again by
+//! `evict_idle_endpoints` once the endpoint is idle, so deleted or disabled
+//! endpoints don't accumulate.
+
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use uuid::Uuid;
+
+use crate::services::webhook_url_guard::WebhookUrlGuard;
+
+/// How long to defer an event whose endpoint has no free concurrency slot.
+const CONCURRENCY_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// Settings for the webhook HTTP client and delivery limits.
+#[derive(Debug, Clone)]
+pub struct WebhookSenderSettings {
+    /// Total time allowed for one delivery attempt
+    pub request_timeout: Duration,
+
+    /// Time allowed to establish a connection
+    pub connect_timeout: Duration,
+
+    /// Maximum in-flight webhook requests across all endpoints
+    pub max_concurrency: usize,
+
+    /// Maximum in-flight webhook requests per endpoint
+    pub endpoint_max_concurrency: usize,
+
+    /// Sustained requests per second per endpoint (0 = unlimited)
+    pub endpoint_rate_per_sec: f64,
+
+    /// Requests per endpoint that may be sent at once before the rate applies
+    pub endpoint_burst: u32,
+}
+
+/// Shared webhook HTTP client with concurrency and rate limits.
+///
+/// Cheap to clone; shared through `AppState` and the dispatcher.
+#[derive(Clone)]
+pub struct WebhookSender {
+    client: reqwest::Client,
+    url_guard: WebhookUrlGuard,
+    settings: Arc<WebhookSenderSettings>,
+    global: Arc<Semaphore>,
+    endpoints: Arc<Mutex<HashMap<Uuid, EndpointLimits>>>,
+}
+
+impl fmt::Debug for WebhookSender {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WebhookSender")
+            .field("settings", &self.settings)
+            .field("available_permits", &self.available_permits())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Slots held while a webhook request is in flight.
+///
+/// Dropping the permit frees the global and the endpoint slot.
+pub struct DeliveryPermit {
+    _global: OwnedSemaphorePermit,
+    _endpoint: OwnedSemaphorePermit,
+}
+
+/// Concurrency slots and rate limit state of one endpoint.
+struct EndpointLimits {
+    semaphore: Arc<Semaphore>,
+    bucket: TokenBucket,
+}
+
+/// Token bucket refilled continuously at `rate` tokens per second.
+struct TokenBucket {
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl TokenBucket {
+    /// Whether the bucket has refilled to `burst`, i.e. is in its initial state.
+    fn is_full(&self, rate: f64, burst: f64) -> bool {
+        rate <= 0.0 || self.tokens + self.refilled_at.elapsed().as_secs_f64() * rate >= burst
+    }
+
+    /// Take one token, or return how long until one is available.
+    fn try_take(&mut self, rate: f64, burst: f64) -> Result<(), Duration> {
+        if rate <= 0.0 {
+            return Ok(());
+        }
+
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.refilled_at).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * rate).min(burst);
+        self.refilled_at = now;
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - self.tokens) / rate))
+        }
+    }
+}
+
+impl WebhookSender {
+    /// Build the shared client.
+    ///
+    /// Expects settings checked by `Config::validate`: positive limits and
+    /// burst, and a rate of 0 or a finite positive number.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the HTTP client cannot be built (e.g. no TLS
+    /// backend).
+    pub fn new(
+        url_guard: WebhookUrlGuard,
+        settings: WebhookSenderSettings,
+    ) -> reqwest::Result<Self> {
+        let client = url_guard
+            .client_builder()
+            .timeout(settings.request_timeout)
+            .connect_timeout(settings.connect_timeout)
+            // Keep no more idle connections per receiver than can be in use
+            .pool_max_idle_per_host(settings.endpoint_max_concurrency)
+            .build()?;
+
+        Ok(Self {
+            client,
+            url_guard,
+            global: Arc::new(Semaphore::new(settings.max_concurrency)),
+            settings: Arc::new(settings),
+            endpoints: Arc::new(Mutex::new(HashMap::new())),
+        })
+    }
+
+    /// The shared HTTP client.
+    pub fn client(&self) -> &reqwest::Client {
+        &self.client
+    }
+
+    /// SSRF checks applied before every request.
+    pub fn url_guard(&self) -> &WebhookUrlGuard {
+        &self.url_guard
+    }
+
+    /// Total time allowed for one delivery attempt.
+    pub fn request_timeout(&self) -> Duration {
+        self.settings.request_timeout
+    }
+
+    /// Maximum in-flight webhook requests per endpoint.
+    pub fn endpoint_max_concurrency(&self) -> usize {
+        self.settings.endpoint_max_concurrency
+    }
+
+    /// Number of global slots currently free.
+    pub fn available_permits(&self) -> usize {
+        self.global.available_permits()
+    }
+
+    /// Take a global and an endpoint slot and a rate limit token without
+    /// waiting.
+    ///
+    /// # Returns
+    ///
+    /// The permit, or how long to wait before trying again if a slot or
+    /// token isn't available. Nothing is consumed on failure.
+    pub fn try_acquire(&self, endpoint_id: Uuid) -> Result<DeliveryPermit, Duration> {
+        let global = self
+            .global
+            .clone()
+            .try_acquire_owned()
+            .map_err(|_| CONCURRENCY_RETRY_DELAY)?;
+
+        let mut endpoints = self.endpoints.lock().unwrap_or_else(|e| e.into_inner());
+        let limits = self.endpoint_limits(&mut endpoints, endpoint_id);
+
+        let endpoint = limits
+            .semaphore
+            .clone()
+            .try_acquire_owned()
+            .map_err(|_| CONCURRENCY_RETRY_DELAY)?;
+
+        limits.bucket.try_take(
+            self.settings.endpoint_rate_per_sec,
+            f64::from(self.settings.endpoint_burst),
+        )?;
+
+        Ok(DeliveryPermit {
+            _global: global,
+            _endpoint: endpoint,
+        })
+    }
+
+    /// Wait for a global and an endpoint slot.
+    ///
+    /// Skips the rate limit; used for one-off requests such as test pings.
+    pub async fn acquire(&self, endpoint_id: Uuid) -> DeliveryPermit {
+        let endpoint_semaphore = {
+            let mut endpoints = self.endpoints.lock().unwrap_or_else(|e| e.into_inner());
+            self.endpoint_limits(&mut endpoints, endpoint_id)
+                .semaphore
+                .clone()
+        };
+
+        // Semaphores are never closed, so acquiring can't fail
+        let global = self
+            .global
+            .clone()
+            .acquire_owned()
+            .await
+            .expect("webhook semaphore closed");
+        let endpoint = endpoint_semaphore
+            .acquire_owned()
+            .await
+            .expect("webhook semaphore closed");
+
+        DeliveryPermit {
+            _global: global,
+            _endpoint: endpoint,
+        }
+    }
+
+    /// Drop the limits of endpoints with no request in flight or waiting and
+    /// a full bucket.
+    ///
+    /// Such an entry is indistinguishable from the one `endpoint_limits`
+    /// would create, so removing it changes no limit. Called periodically
+    /// by the dispatcher.
+    pub fn evict_idle_endpoints(&self) {
+        let rate = self.settings.endpoint_rate_per_sec;
+        let burst = f64::from(self.settings.endpoint_burst);
+
+        let mut endpoints = self.endpoints.lock().unwrap_or_else(|e| e.into_inner());
+        endpoints.retain(|_, limits| {
+            // In-flight permits and callers waiting in `acquire` hold clones
+            let idle =
+                Arc::strong_count(&limits.semaphore) == 1 && limits.bucket.is_full(rate, burst);
+            !idle
+        });
+    }
+
+    /// Limits of an endpoint, created on first use with a full bucket.
+    fn endpoint_limits<'a>(
+        &self,
+        endpoints: &'a mut HashMap<Uuid, EndpointLimits>,
+        endpoint_id: Uuid,
+    ) -> &'a mut EndpointLimits {
+        endpoints
+            .entry(endpoint_id)
+            .or_insert_with(|| EndpointLimits {
+                semaphore: Arc::new(Semaphore::new(self.settings.endpoint_max_concurrency)),
+                bucket: TokenBucket {
+                    tokens: f64::from(self.settings.endpoint_burst),
+                    refilled_at: Instant::now(),
+                },
+            })
+    }
+}
@@ -1,42 +1,121 @@
 //! Webhook service for managing endpoints and sending events.
 //!
 //! This module handles webhook endpoint registration, event delivery,
-//! and HMAC signature generation for secure webhook verification.
+//! and timestamped HMAC signatures (via `webhook_signature`) for secure
+//! webhook verification.
+//!
+//! # Delivery Guarantees
+//!
+//! `webhook_events` is a transactional outbox: events are queued in the same
+//! database transaction as the money movement, so a committed transaction
+//! always has its events. A background dispatcher delivers them at least
+//! once, retrying failures with exponential backoff and jitter until the
+//! receiver answers 2xx or the event runs out of attempts and is marked
+//! `dead`. Receivers should deduplicate on `X-Webhook-Event-Id`.
+//!
+//! # Endpoint Health
+//!
+//! Every attempt updates the endpoint's failure streak. An endpoint that
+//! keeps failing for long enough is disabled automatically (so we stop
+//! paying for timeouts against dead URLs) and the business's other
+//! endpoints receive a `webhook_endpoint.disabled` event.
+
+use std::time::Duration;
 
 use crate::db::DbPool;
 use crate::error::AppError;
+use crate::models::account::Account;
+use crate::models::pagination;
 use crate::models::transaction::Transaction;
 use crate::models::webhook::{
-    NewWebhookEvent, WebhookEndpoint, WebhookEndpointRequest, WebhookEndpointResponse,
-    WebhookPayload,
+    ALL_EVENTS, EVENT_TYPES, NewWebhookEvent, RotateSecretRequest, UpdateWebhookEndpointRequest,
+    WebhookData, WebhookDelivery, WebhookEndpoint, WebhookEndpointRequest, WebhookEndpointResponse,
+    WebhookEvent, WebhookEventListQuery, WebhookPayload, WebhookTestResponse,
 };
-use hmac::{Hmac, Mac};
-use sha2::Sha256;
+use crate::services::{webhook_sender::WebhookSender, webhook_url_guard::WebhookUrlGuard};
+use chrono::Utc;
+use rust_transaction_web_server::webhook_signature;
+use sqlx::PgConnection;
+use tokio::time::MissedTickBehavior;
 use uuid::Uuid;
 
-type HmacSha256 = Hmac<Sha256>;
+/// Maximum number of events claimed per dispatcher run.
+const DISPATCH_BATCH_SIZE: i64 = 50;
+
+/// How much longer than the request timeout a claimed event is hidden from
+/// other dispatchers (the lease), covering DNS, queuing and recording.
+const DELIVERY_LEASE_MARGIN: Duration = Duration::from_secs(55);
+
+/// Grace period of the old secret after a rotation (24 hours).
+const DEFAULT_SECRET_GRACE_PERIOD_SECS: i64 = 24 * 60 * 60;
+
+/// Longest allowed grace period after a rotation (7 days).
+const MAX_SECRET_GRACE_PERIOD_SECS: i64 = 7 * 24 * 60 * 60;
+
+/// `disabled_reason` of endpoints disabled through the API.
+const MANUAL_DISABLE_REASON: &str = "Disabled via API";
+
+/// Maximum number of bytes of the receiver's response body that are read and stored.
+const RESPONSE_BODY_LIMIT: usize = 4096;
+
+/// `sequence` of test pings. Real events are numbered from 1, so pings
+/// don't leave gaps in the numbers receivers see for their accounts.
+const TEST_EVENT_SEQUENCE: i64 = 0;
+
+/// How long a test ping waits for a free delivery slot of its endpoint.
+const TEST_SLOT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Settings for the background webhook dispatcher.
+#[derive(Debug, Clone)]
+pub struct DispatcherSettings {
+    /// How often the outbox is polled for due events
+    pub poll_interval: Duration,
+
+    /// Attempts before an event is marked `dead`
+    pub max_attempts: i32,
+
+    /// Delay before the first retry (doubled for every further attempt)
+    pub retry_base: Duration,
+
+    /// Upper bound for the retry delay
+    pub retry_max: Duration,
+
+    /// Consecutive failed attempts before an endpoint is disabled (0 = never)
+    pub disable_after_failures: i32,
+
+    /// Minimum duration of the failure streak before an endpoint is disabled
+    pub disable_after: Duration,
+
+    /// Shared HTTP client and delivery limits
+    pub sender: WebhookSender,
+}
 
 /// Create a new webhook endpoint.
 ///
 /// # Process
 ///
-/// 1. Validate URL format
+/// 1. Validate URL format and subscribed event types
 /// 2. Generate cryptographically secure secret (32 bytes)
 /// 3. Store endpoint in database
 /// 4. Return endpoint with secret (only shown once)
 ///
 /// # Security
 ///
-/// - HTTPS is required for production endpoints
-/// - HTTP localhost is allowed for testing
+/// - HTTPS is required (HTTP only for allowlisted development hosts)
+/// - URLs resolving to private, loopback, link-local or metadata addresses
+///   are rejected (see `webhook_url_guard`)
 /// - Secret is 64 hex characters (32 bytes of randomness)
 pub async fn create_webhook_endpoint(
     pool: &DbPool,
+    url_guard: &WebhookUrlGuard,
     api_key_id: Uuid,
     request: WebhookEndpointRequest,
 ) -> Result<WebhookEndpointResponse, AppError> {
-    // Validate URL
-    validate_webhook_url(&request.url)?;
+    // Validate URL (including where it resolves to)
+    url_guard.validate(&request.url).await?;
+
+    // Validate subscriptions
+    let enabled_events = validate_enabled_events(request.enabled_events)?;
 
     // Generate secure random secret (32 bytes = 64 hex chars)
     let secret = generate_secret();
@@ -44,14 +123,16 @@ pub async fn create_webhook_endpoint(
     // Insert into database
     let endpoint = sqlx::query_as::<_, WebhookEndpoint>(
         r#"
-        INSERT INTO webhook_endpoints (api_key_id, url, secret)
-        VALUES ($1, $2, $3)
+        INSERT INTO webhook_endpoints (api_key_id, url, secret, enabled_events, ordered_delivery)
+        VALUES ($1, $2, $3, $4, $5)
         RETURNING *
         "#,
     )
     .bind(api_key_id)
     .bind(&request.url)
     .bind(&secret)
+    .bind(&enabled_events)
+    .bind(request.ordered_delivery)
     .fetch_one(pool)
     .await?;
 
@@ -65,13 +146,17 @@ pub async fn create_webhook_endpoint(
 ///
 /// - Filters by api_key_id (authenticated business only)
 /// - Does NOT return secrets
-/// - Only returns active endpoints by default
+/// - Returns active and disabled endpoints, but not deleted ones
 pub async fn list_webhook_endpoints(
     pool: &DbPool,
     api_key_id: Uuid,
 ) -> Result<Vec<WebhookEndpointResponse>, AppError> {
     let endpoints = sqlx::query_as::<_, WebhookEndpoint>(
-        "SELECT * FROM webhook_endpoints WHERE api_key_id = $1 AND is_active = true ORDER BY created_at DESC",
+        r#"
+        SELECT * FROM webhook_endpoints
+        WHERE api_key_id = $1 AND (is_active = true OR disabled_at IS NOT NULL)
+        ORDER BY created_at DESC
+        "#,
     )
     .bind(api_key_id)
     .fetch_all(pool)
@@ -86,7 +171,8 @@ pub async fn list_webhook_endpoints(
 /// # Process
 ///
 /// 1. Verify endpoint exists and belongs to authenticated business
-/// 2. Set is_active = false (preserve event history)
+/// 2. Set is_active = false and clear any disabled state (preserve event
+///    history)
 ///
 /// # Security
 ///
@@ -97,7 +183,11 @@ pub async fn delete_webhook_endpoint(
     endpoint_id: Uuid,
 ) -> Result<(), AppError> {
     let result = sqlx::query(
-        "UPDATE webhook_endpoints SET is_active = false WHERE id = $1 AND api_key_id = $2",
+        r#"
+        UPDATE webhook_endpoints
+        SET is_active = false, disabled_at = NULL, disabled_reason = NULL
+        WHERE id = $1 AND api_key_id = $2
+        "#,
     )
     .bind(endpoint_id)
     .bind(api_key_id)
@@ -111,160 +201,1063 @@ pub async fn delete_webhook_endpoint(
     Ok(())
 }
 
-/// Send webhook notification for a transaction to all registered endpoints.
+/// Update a webhook endpoint: enable/disable it or switch ordered delivery.
 ///
 /// # Process
 ///
-/// 1. Fetch all active webhook endpoints for the transaction's API key
-/// 2. For each endpoint, send webhook with signed payload
-/// 3. Log all delivery attempts
+/// Omitted fields are left unchanged.
 ///
-/// # Error Handling
+/// - Enabling clears `disabled_at`/`disabled_reason` and, if the endpoint
+///   was disabled, resets its failure streak. Events still pending from
+///   before it was disabled are picked up by the dispatcher again.
+/// - Disabling stops deliveries and new events for the endpoint, keeping
+///   the reason of an earlier automatic disable
+/// - Switching ordered delivery applies to pending events right away
 ///
-/// - Individual webhook failures are logged but don't fail the overall operation
-/// - Transaction success is independent of webhook delivery
-pub async fn notify_transaction_webhooks(
+/// # Errors
+///
+/// - `InvalidRequest`: No field to update
+/// - `WebhookNotFound`: Endpoint doesn't exist, is deleted or belongs to
+///   another business
+pub async fn update_webhook_endpoint(
     pool: &DbPool,
-    transaction: &Transaction,
     api_key_id: Uuid,
-) -> Result<(), AppError> {
-    // Fetch active webhook endpoints for this API key
-    let endpoints = sqlx::query_as::<_, WebhookEndpoint>(
-        "SELECT * FROM webhook_endpoints WHERE api_key_id = $1 AND is_active = true",
+    endpoint_id: Uuid,
+    request: UpdateWebhookEndpointRequest,
+) -> Result<WebhookEndpointResponse, AppError> {
+    if request.is_active.is_none() && request.ordered_delivery.is_none() {
+        return Err(AppError::InvalidRequest(
+            "At least one of is_active or ordered_delivery is required".to_string(),
+        ));
+    }
+
+    // NULL parameters leave the column unchanged
+    let endpoint = sqlx::query_as::<_, WebhookEndpoint>(
+        r#"
+        UPDATE webhook_endpoints
+        SET consecutive_failures = CASE WHEN $3::boolean AND NOT is_active
+                THEN 0 ELSE consecutive_failures END,
+            failing_since = CASE WHEN $3::boolean AND NOT is_active
+                THEN NULL ELSE failing_since END,
+            disabled_at = CASE WHEN $3::boolean THEN NULL
+                WHEN NOT $3::boolean THEN COALESCE(disabled_at, NOW())
+                ELSE disabled_at END,
+            disabled_reason = CASE WHEN $3::boolean THEN NULL
+                WHEN NOT $3::boolean THEN COALESCE(disabled_reason, $5)
+                ELSE disabled_reason END,
+            is_active = COALESCE($3::boolean, is_active),
+            ordered_delivery = COALESCE($4::boolean, ordered_delivery)
+        WHERE id = $1 AND api_key_id = $2
+          AND (is_active = true OR disabled_at IS NOT NULL)
+        RETURNING *
+        "#,
     )
+    .bind(endpoint_id)
     .bind(api_key_id)
+    .bind(request.is_active)
+    .bind(request.ordered_delivery)
+    .bind(MANUAL_DISABLE_REASON)
+    .fetch_optional(pool)
+    .await?
+    .ok_or(AppError::WebhookNotFound)?;
+
+    Ok(endpoint.into())
+}
+
+/// Rotate the secret of a webhook endpoint.
+///
+/// # Process
+///
+/// 1. Validate the grace period
+/// 2. Generate a new secret (32 bytes)
+/// 3. Move the current secret to `previous_secret`, valid for the grace period
+/// 4. Return endpoint with the new secret (only shown once)
+///
+/// During the grace period every webhook is signed with both secrets, so
+/// receivers can switch to the new secret at their own pace. Rotating again
+/// within the grace period discards the older previous secret.
+///
+/// # Errors
+///
+/// - `InvalidRequest`: Grace period out of range
+/// - `WebhookNotFound`: Endpoint doesn't exist, is deleted or belongs to
+///   another business
+pub async fn rotate_webhook_secret(
+    pool: &DbPool,
+    api_key_id: Uuid,
+    endpoint_id: Uuid,
+    request: RotateSecretRequest,
+) -> Result<WebhookEndpointResponse, AppError> {
+    let grace_period_seconds = request
+        .grace_period_seconds
+        .unwrap_or(DEFAULT_SECRET_GRACE_PERIOD_SECS);
+    if !(0..=MAX_SECRET_GRACE_PERIOD_SECS).contains(&grace_period_seconds) {
+        return Err(AppError::InvalidRequest(format!(
+            "grace_period_seconds must be between 0 and {}",
+            MAX_SECRET_GRACE_PERIOD_SECS
+        )));
+    }
+
+    let secret = generate_secret();
+
+    let endpoint = sqlx::query_as::<_, WebhookEndpoint>(
+        r#"
+        UPDATE webhook_endpoints
+        SET previous_secret = secret,
+            previous_secret_expires_at = NOW() + make_interval(secs => $4),
+            secret = $3
+        WHERE id = $1 AND api_key_id = $2 AND is_active = true
+        RETURNING *
+        "#,
+    )
+    .bind(endpoint_id)
+    .bind(api_key_id)
+    .bind(&secret)
+    .bind(grace_period_seconds as f64)
+    .fetch_optional(pool)
+    .await?
+    .ok_or(AppError::WebhookNotFound)?;
+
+    Ok(WebhookEndpointResponse::from(endpoint).with_secret(secret))
+}
+
+/// List the delivery log of a webhook endpoint, newest first.
+///
+/// Deleted (inactive) endpoints keep their history, so their events can
+/// still be listed.
+///
+/// # Pagination
+///
+/// Keyset pagination on `(created_at, id)`, served by
+/// `idx_webhook_events_endpoint`. One extra row is fetched to determine
+/// whether another page exists.
+///
+/// # Returns
+///
+/// The events on this page and whether more events follow.
+///
+/// # Errors
+///
+/// - `WebhookNotFound`: Endpoint doesn't exist or belongs to another business
+/// - `InvalidRequest`: Limit out of range
+pub async fn list_webhook_events(
+    pool: &DbPool,
+    api_key_id: Uuid,
+    endpoint_id: Uuid,
+    query: &WebhookEventListQuery,
+) -> Result<(Vec<WebhookEvent>, bool), AppError> {
+    let limit = pagination::page_limit(query.limit).map_err(AppError::InvalidRequest)?;
+
+    // Verify endpoint belongs to authenticated business
+    sqlx::query_scalar::<_, Uuid>(
+        "SELECT id FROM webhook_endpoints WHERE id = $1 AND api_key_id = $2",
+    )
+    .bind(endpoint_id)
+    .bind(api_key_id)
+    .fetch_optional(pool)
+    .await?
+    .ok_or(AppError::WebhookNotFound)?;
+
+    let mut events = sqlx::query_as::<_, WebhookEvent>(
+        r#"
+        SELECT * FROM webhook_events
+        WHERE webhook_endpoint_id = $1
+          AND ($2::text IS NULL OR status = $2)
+          AND ($3::uuid IS NULL OR (created_at, id) < (
+               SELECT created_at, id FROM webhook_events
+               WHERE id = $3 AND webhook_endpoint_id = $1
+          ))
+        ORDER BY created_at DESC, id DESC
+        LIMIT $4
+        "#,
+    )
+    .bind(endpoint_id)
+    .bind(query.status.map(|s| s.as_str()))
+    .bind(query.starting_after)
+    .bind(limit + 1)
     .fetch_all(pool)
     .await?;
 
-    // Send webhook to each endpoint
-    for endpoint in endpoints {
-        if let Err(e) = send_webhook(pool, &endpoint, transaction).await {
-            tracing::error!("Failed to send webhook to {}: {:?}", endpoint.url, e);
-            // Continue to next endpoint even if one fails
-        }
-    }
+    let has_more = events.len() as i64 > limit;
+    events.truncate(limit as usize);
 
-    Ok(())
+    Ok((events, has_more))
 }
 
-/// Send a single webhook with HMAC signature.
+/// Get a single webhook event.
+///
+/// # Security
+///
+/// - Verifies ownership through the event's endpoint
+///
+/// # Errors
+///
+/// - `WebhookEventNotFound`: Event doesn't exist or belongs to another business
+pub async fn get_webhook_event(
+    pool: &DbPool,
+    api_key_id: Uuid,
+    event_id: Uuid,
+) -> Result<WebhookEvent, AppError> {
+    sqlx::query_as::<_, WebhookEvent>(
+        r#"
+        SELECT e.*
+        FROM webhook_events e
+        JOIN webhook_endpoints ep ON ep.id = e.webhook_endpoint_id
+        WHERE e.id = $1 AND ep.api_key_id = $2
+        "#,
+    )
+    .bind(event_id)
+    .bind(api_key_id)
+    .fetch_optional(pool)
+    .await?
+    .ok_or(AppError::WebhookEventNotFound)
+}
+
+/// Queue a webhook event for redelivery.
 ///
 /// # Process
 ///
-/// 1. Build webhook payload
-/// 2. Generate HMAC-SHA256 signature
-/// 3. Send HTTP POST with signature header
-/// 4. Record event in database
+/// 1. Verify the event belongs to the authenticated business
+/// 2. Reset it to `pending` with a fresh retry schedule, due immediately
+/// 3. The dispatcher resends the stored payload, signed with the
+///    endpoint's current secret at send time
 ///
-/// # Headers Sent
+/// Works for `delivered` and `dead` events, so support can resend an event
+/// a receiver says it never got. `attempt_count` keeps counting, so the
+/// full attempt history is preserved; the new retry schedule starts from
+/// `attempts_before_redelivery`.
 ///
-/// - `Content-Type: application/json`
-/// - `X-Webhook-Signature: sha256=<hex>`
-/// - `X-Webhook-Event-Id: <uuid>`
+/// `pending` events are rejected: one may be in flight (claimed events stay
+/// `pending` under a lease), and making it due again would send it twice
+/// at once. The status check and the reset are one statement, so a
+/// concurrent claim can't slip in between.
 ///
-/// # Timeout
+/// # Errors
 ///
-/// 5 seconds per webhook (prevents hanging on slow endpoints)
-async fn send_webhook(
+/// - `WebhookEventNotFound`: Event doesn't exist or belongs to another business
+/// - `WebhookEventPending`: Event is waiting for or in a delivery attempt
+/// - `InvalidRequest`: The event's endpoint has been deleted or is disabled
+pub async fn redeliver_webhook_event(
     pool: &DbPool,
-    endpoint: &WebhookEndpoint,
-    transaction: &Transaction,
-) -> Result<(), AppError> {
-    let event_id = Uuid::new_v4();
+    api_key_id: Uuid,
+    event_id: Uuid,
+) -> Result<WebhookEvent, AppError> {
+    let event = get_webhook_event(pool, api_key_id, event_id).await?;
 
-    // Build payload
-    let payload = WebhookPayload::new(event_id, transaction.clone());
-    let payload_json = serde_json::to_string(&payload)
-        .map_err(|e| AppError::InvalidRequest(format!("Failed to serialize payload: {}", e)))?;
+    let endpoint_active: bool =
+        sqlx::query_scalar("SELECT is_active FROM webhook_endpoints WHERE id = $1")
+            .bind(event.webhook_endpoint_id)
+            .fetch_one(pool)
+            .await?;
 
-    // Generate HMAC signature
-    let signature = generate_signature(&endpoint.secret, &payload_json);
+    if !endpoint_active {
+        return Err(AppError::InvalidRequest(
+            "Cannot redeliver events of a deleted or disabled webhook endpoint".to_string(),
+        ));
+    }
 
-    // Send HTTP POST
-    let client = reqwest::Client::builder()
-        .timeout(std::time::Duration::from_secs(5))
-        .build()
-        .map_err(|e| AppError::InvalidRequest(format!("HTTP client error: {}", e)))?;
+    sqlx::query_as::<_, WebhookEvent>(
+        r#"
+        UPDATE webhook_events
+        SET status = 'pending',
+            attempts_before_redelivery = attempt_count,
+            next_attempt_at = NOW(),
+            delivered_at = NULL
+        WHERE id = $1 AND status <> 'pending'
+        RETURNING *
+        "#,
+    )
+    .bind(event.id)
+    .fetch_optional(pool)
+    .await?
+    .ok_or(AppError::WebhookEventPending)
+}
 
-    let response = client
-        .post(&endpoint.url)
-        .header("Content-Type", "application/json")
-        .header("X-Webhook-Signature", &signature)
-        .header("X-Webhook-Event-Id", event_id.to_string())
-        .body(payload_json.clone())
-        .send()
-        .await;
+/// Send a `webhook.test` event to an endpoint and wait for the response.
+///
+/// # Process
+///
+/// 1. Verify the endpoint belongs to the authenticated business
+/// 2. Sign and send a `webhook.test` event through `send_webhook`, the same
+///    path (SSRF checks, headers, signature) as real deliveries
+/// 3. Record the attempt in `webhook_events`: `delivered` on a 2xx,
+///    otherwise `dead`
+///
+/// Test events are sent regardless of the endpoint's subscriptions, are
+/// never retried and don't count towards the endpoint's failure streak.
+/// They carry `sequence` 0 instead of taking a number from the sequence
+/// shared with real events. They wait up to `TEST_SLOT_TIMEOUT` for a free
+/// concurrency slot but skip the rate limit. Disabled endpoints can be
+/// tested before they are re-enabled.
+///
+/// # Errors
+///
+/// - `WebhookNotFound`: Endpoint doesn't exist, is deleted or belongs to
+///   another business
+/// - `WebhookEndpointBusy`: No delivery slot became free in time
+pub async fn send_test_webhook(
+    pool: &DbPool,
+    sender: &WebhookSender,
+    api_key_id: Uuid,
+    endpoint_id: Uuid,
+) -> Result<WebhookTestResponse, AppError> {
+    let endpoint = sqlx::query_as::<_, WebhookEndpoint>(
+        r#"
+        SELECT * FROM webhook_endpoints
+        WHERE id = $1 AND api_key_id = $2
+          AND (is_active = true OR disabled_at IS NOT NULL)
+        "#,
+    )
+    .bind(endpoint_id)
+    .bind(api_key_id)
+    .fetch_optional(pool)
+    .await?
+    .ok_or(AppError::WebhookNotFound)?;
 
-    // Record event in database
-    let (status, body) = match response {
-        Ok(resp) => {
-            let status = resp.status().as_u16() as i32;
-            let body = resp.text().await.ok();
-            (Some(status), body)
-        }
-        Err(e) => {
-            let error_msg = format!("Request failed: {}", e);
-            tracing::error!("{}", error_msg);
-            (None, Some(error_msg))
-        }
+    let event_id = Uuid::new_v4();
+    let event_type = "webhook.test";
+    let payload = serde_json::to_value(WebhookPayload::new(
+        event_id,
+        event_type.to_string(),
+        TEST_EVENT_SEQUENCE,
+        WebhookData::WebhookEndpoint(endpoint.clone().into()),
+    ))
+    .map_err(|e| AppError::InvalidRequest(format!("Failed to serialize payload: {}", e)))?;
+
+    // Only sign with the previous secret while its grace period lasts
+    let previous_secret = endpoint
+        .previous_secret
+        .filter(|_| endpoint.previous_secret_expires_at > Some(Utc::now()));
+
+    let delivery = WebhookDelivery {
+        event_id,
+        webhook_endpoint_id: endpoint.id,
+        url: endpoint.url,
+        secret: endpoint.secret,
+        previous_secret,
+        payload,
+        attempt_count: 0,
+        attempts_before_redelivery: 0,
     };
 
-    // Create webhook event record
-    let payload_value = serde_json::from_str::<serde_json::Value>(&payload_json)
-        .map_err(|e| AppError::InvalidRequest(format!("Failed to parse payload: {}", e)))?;
+    let permit = tokio::time::timeout(
+        TEST_SLOT_TIMEOUT,
+        sender.acquire(delivery.webhook_endpoint_id),
+    )
+    .await
+    .map_err(|_| AppError::WebhookEndpointBusy)?;
+    let started = std::time::Instant::now();
+    let (response_status, response_body) = send_webhook(sender, &delivery).await;
+    let latency = started.elapsed();
+    drop(permit);
 
+    let success = response_status.is_some_and(|status| (200..300).contains(&status));
     let event = NewWebhookEvent::new(
         event_id,
-        endpoint.id,
-        transaction.id,
-        payload_value,
-        status,
-        body,
+        delivery.webhook_endpoint_id,
+        event_type.to_string(),
+        None,
+        TEST_EVENT_SEQUENCE,
+        Vec::new(),
+        delivery.payload,
     );
 
-    // Store event record
     sqlx::query(
         r#"
         INSERT INTO webhook_events (
-            id,
-            webhook_endpoint_id,
-            transaction_id,
-            payload,
-            response_status,
-            response_body
+            id, webhook_endpoint_id, event_type, transaction_id, sequence, account_ids, payload,
+            status, attempt_count, last_attempt_at, delivered_at,
+            response_status, response_body
+        )
+        VALUES (
+            $1, $2, $3, $4, $5, $6, $7,
+            $8, 1, NOW(), CASE WHEN $8 = 'delivered' THEN NOW() END,
+            $9, $10
         )
-        VALUES ($1, $2, $3, $4, $5, $6)
         "#,
     )
     .bind(event.id)
     .bind(event.webhook_endpoint_id)
+    .bind(event.event_type)
     .bind(event.transaction_id)
+    .bind(event.sequence)
+    .bind(event.account_ids)
     .bind(event.payload)
-    .bind(event.response_status)
-    .bind(event.response_body)
+    .bind(if success { "delivered" } else { "dead" })
+    .bind(response_status)
+    .bind(&response_body)
+    .execute(pool)
+    .await?;
+
+    Ok(WebhookTestResponse {
+        event_id,
+        success,
+        response_status,
+        response_body,
+        latency_ms: latency.as_millis() as u64,
+    })
+}
+
+/// Queue the webhook events for a transaction inside an open database transaction.
+///
+/// The event type is `transaction.{transaction_type}`. Must be called with
+/// the same connection that moved the money, so the events commit (or roll
+/// back) together with the transaction.
+pub async fn enqueue_transaction_events(
+    conn: &mut PgConnection,
+    transaction: &Transaction,
+) -> Result<(), AppError> {
+    enqueue_transaction_status_events(
+        conn,
+        transaction,
+        &format!("transaction.{}", transaction.transaction_type),
+    )
+    .await
+}
+
+/// Queue webhook events of the given type for a change of an existing
+/// transaction (e.g. `transaction.voided`) inside an open database
+/// transaction.
+pub async fn enqueue_transaction_status_events(
+    conn: &mut PgConnection,
+    transaction: &Transaction,
+    event_type: &str,
+) -> Result<(), AppError> {
+    enqueue_event(
+        conn,
+        transaction.api_key_id,
+        event_type,
+        Some(transaction.id),
+        &[transaction.from_account_id, transaction.to_account_id]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>(),
+        WebhookData::Transaction(transaction.clone().into()),
+    )
+    .await
+}
+
+/// Queue the `account.created` webhook events inside an open database
+/// transaction.
+pub async fn enqueue_account_created_events(
+    conn: &mut PgConnection,
+    account: &Account,
+) -> Result<(), AppError> {
+    enqueue_event(
+        conn,
+        account.api_key_id,
+        "account.created",
+        None,
+        &[account.id],
+        WebhookData::Account(account.clone().into()),
+    )
+    .await
+}
+
+/// Queue the `account.status_changed` webhook events inside an open
+/// database transaction.
+pub async fn enqueue_account_status_changed_events(
+    conn: &mut PgConnection,
+    account: &Account,
+) -> Result<(), AppError> {
+    enqueue_event(
+        conn,
+        account.api_key_id,
+        "account.status_changed",
+        None,
+        &[account.id],
+        WebhookData::Account(account.clone().into()),
+    )
+    .await
+}
+
+/// Disable an endpoint after sustained delivery failures.
+///
+/// Runs in one database transaction: the endpoint is disabled and a
+/// `webhook_endpoint.disabled` event is queued for the business's other
+/// active endpoints. Does nothing if the endpoint is no longer active
+/// (e.g. another delivery task disabled it first).
+async fn disable_failing_endpoint(
+    pool: &DbPool,
+    endpoint: &WebhookEndpoint,
+) -> Result<(), AppError> {
+    let reason = format!(
+        "Disabled after {} consecutive failed delivery attempts since {}",
+        endpoint.consecutive_failures,
+        endpoint
+            .failing_since
+            .map(|since| since.to_rfc3339())
+            .unwrap_or_default()
+    );
+
+    let mut tx = pool.begin().await?;
+
+    let disabled = sqlx::query_as::<_, WebhookEndpoint>(
+        r#"
+        UPDATE webhook_endpoints
+        SET is_active = false, disabled_at = NOW(), disabled_reason = $2
+        WHERE id = $1 AND is_active = true
+        RETURNING *
+        "#,
+    )
+    .bind(endpoint.id)
+    .bind(&reason)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    let Some(disabled) = disabled else {
+        return Ok(());
+    };
+
+    // The endpoint is inactive now, so it doesn't get its own notification
+    enqueue_event(
+        &mut tx,
+        disabled.api_key_id,
+        "webhook_endpoint.disabled",
+        None,
+        &[],
+        WebhookData::WebhookEndpoint(disabled.clone().into()),
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    tracing::warn!(
+        webhook_endpoint_id = %disabled.id,
+        consecutive_failures = disabled.consecutive_failures,
+        "Webhook endpoint disabled after sustained delivery failures"
+    );
+
+    Ok(())
+}
+
+/// Queue an event for every active endpoint of a business subscribed to it.
+///
+/// Inserts one `pending` event per endpoint; the dispatcher delivers them
+/// after the surrounding database transaction commits.
+///
+/// # Ordering
+///
+/// All copies of the event share one `sequence` number. Callers lock the
+/// accounts the event is about before queuing it (money movements and
+/// status changes alike), so for any one account the sequence increases in
+/// commit order. `account_ids` records those accounts, for endpoints with
+/// ordered delivery.
+async fn enqueue_event(
+    conn: &mut PgConnection,
+    api_key_id: Uuid,
+    event_type: &str,
+    transaction_id: Option<Uuid>,
+    account_ids: &[Uuid],
+    data: WebhookData,
+) -> Result<(), AppError> {
+    // Fetch active webhook endpoints for this API key
+    let endpoints: Vec<WebhookEndpoint> = sqlx::query_as::<_, WebhookEndpoint>(
+        "SELECT * FROM webhook_endpoints WHERE api_key_id = $1 AND is_active = true",
+    )
+    .bind(api_key_id)
+    .fetch_all(&mut *conn)
+    .await?
+    .into_iter()
+    .filter(|endpoint| endpoint.subscribes_to(event_type))
+    .collect();
+
+    if endpoints.is_empty() {
+        return Ok(());
+    }
+
+    let sequence: i64 = sqlx::query_scalar("SELECT nextval('webhook_event_sequence')")
+        .fetch_one(&mut *conn)
+        .await?;
+
+    for endpoint in endpoints {
+        let event_id = Uuid::new_v4();
+
+        // Build payload once; retries resend exactly the same body
+        let payload = serde_json::to_value(WebhookPayload::new(
+            event_id,
+            event_type.to_string(),
+            sequence,
+            data.clone(),
+        ))
+        .map_err(|e| AppError::InvalidRequest(format!("Failed to serialize payload: {}", e)))?;
+
+        let event = NewWebhookEvent::new(
+            event_id,
+            endpoint.id,
+            event_type.to_string(),
+            transaction_id,
+            sequence,
+            account_ids.to_vec(),
+            payload,
+        );
+
+        sqlx::query(
+            r#"
+            INSERT INTO webhook_events (
+                id, webhook_endpoint_id, event_type, transaction_id, sequence, account_ids, payload
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#,
+        )
+        .bind(event.id)
+        .bind(event.webhook_endpoint_id)
+        .bind(event.event_type)
+        .bind(event.transaction_id)
+        .bind(event.sequence)
+        .bind(event.account_ids)
+        .bind(event.payload)
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Spawn the background dispatcher that delivers queued webhook events.
+///
+/// Every `poll_interval` the dispatcher claims due events and starts
+/// delivering them, until the outbox has no more due events or all global
+/// delivery slots are in use. Deliveries run in parallel in their own tasks,
+/// bounded by the sender's limits. Failures of a run are logged and the task
+/// keeps going; undelivered events stay in the outbox.
+pub fn spawn_webhook_dispatcher(pool: DbPool, settings: DispatcherSettings) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(settings.poll_interval);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            ticker.tick().await;
+
+            loop {
+                match dispatch_due_events(&pool, &settings).await {
+                    // A full batch means more events may be due right away
+                    Ok(true) => continue,
+                    Ok(false) => break,
+                    Err(e) => {
+                        tracing::error!("Webhook dispatch failed: {:?}", e);
+                        break;
+                    }
+                }
+            }
+
+            settings.sender.evict_idle_endpoints();
+        }
+    });
+}
+
+/// Claim one batch of due events and start delivering them.
+///
+/// Only as many events are claimed as there are free global slots. Each
+/// event then needs a slot and a rate limit token of its endpoint; events
+/// that can't get one are deferred without using up an attempt.
+///
+/// Returns whether the batch was full, i.e. more events may be due.
+async fn dispatch_due_events(
+    pool: &DbPool,
+    settings: &DispatcherSettings,
+) -> Result<bool, AppError> {
+    let capacity = settings
+        .sender
+        .available_permits()
+        .min(DISPATCH_BATCH_SIZE as usize) as i64;
+    if capacity == 0 {
+        return Ok(false);
+    }
+
+    let deliveries = claim_due_events(pool, &settings.sender, capacity).await?;
+    let claimed = deliveries.len() as i64;
+
+    let mut deferred_ids = Vec::new();
+    let mut deferred_secs = Vec::new();
+    for delivery in deliveries {
+        match settings.sender.try_acquire(delivery.webhook_endpoint_id) {
+            Ok(permit) => {
+                let pool = pool.clone();
+                let settings = settings.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = deliver(&pool, &settings, delivery).await {
+                        tracing::error!("Failed to record webhook attempt: {:?}", e);
+                    }
+                    drop(permit);
+                });
+            }
+            Err(retry_in) => {
+                deferred_ids.push(delivery.event_id);
+                deferred_secs.push(retry_in.as_secs_f64());
+            }
+        }
+    }
+
+    if !deferred_ids.is_empty() {
+        tracing::debug!(
+            deferred = deferred_ids.len(),
+            "Webhook events deferred by concurrency or rate limits"
+        );
+        defer_events(pool, &deferred_ids, &deferred_secs).await?;
+    }
+
+    Ok(claimed == capacity)
+}
+
+/// Release claimed events without an attempt, due again after the given
+/// delays (in seconds). `attempt_count` is left unchanged.
+async fn defer_events(
+    pool: &DbPool,
+    event_ids: &[Uuid],
+    delays_secs: &[f64],
+) -> Result<(), AppError> {
+    sqlx::query(
+        r#"
+        UPDATE webhook_events e
+        SET next_attempt_at = NOW() + make_interval(secs => d.delay_secs)
+        FROM UNNEST($1::uuid[], $2::float8[]) AS d(id, delay_secs)
+        WHERE e.id = d.id
+        "#,
+    )
+    .bind(event_ids)
+    .bind(delays_secs)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Claim a batch of due `pending` events.
+///
+/// # Concurrency
+///
+/// - `FOR UPDATE SKIP LOCKED` lets several dispatchers (or server
+///   instances) poll the outbox without claiming the same event
+/// - Claiming pushes `next_attempt_at` forward by the request timeout plus
+///   `DELIVERY_LEASE_MARGIN` and commits immediately, so no row lock is
+///   held during HTTP calls
+/// - If the process dies mid-delivery, the event becomes due again once the
+///   lease expires (at-least-once delivery)
+///
+/// # Fairness
+///
+/// At most `endpoint_max_concurrency` events are taken per endpoint (via
+/// `idx_webhook_events_endpoint_due`), so a backlog for one endpoint can't
+/// fill the batch and starve the others.
+///
+/// # Ordered Delivery
+///
+/// For endpoints with `ordered_delivery`, an event is skipped while an
+/// earlier (lower `sequence`) event of the same endpoint that shares an
+/// account is still `pending`, including one that is in flight or waiting
+/// for a retry. Events without accounts are never held back.
+///
+/// Events of deactivated endpoints are left in the outbox.
+async fn claim_due_events(
+    pool: &DbPool,
+    sender: &WebhookSender,
+    limit: i64,
+) -> Result<Vec<WebhookDelivery>, AppError> {
+    let lease = sender.request_timeout() + DELIVERY_LEASE_MARGIN;
+
+    let deliveries = sqlx::query_as::<_, WebhookDelivery>(
+        r#"
+        WITH candidates AS (
+            SELECT e.id
+            FROM webhook_endpoints ep
+            CROSS JOIN LATERAL (
+                SELECT ev.id
+                FROM webhook_events ev
+                WHERE ev.webhook_endpoint_id = ep.id
+                  AND ev.status = 'pending'
+                  AND ev.next_attempt_at <= NOW()
+                  AND NOT (ep.ordered_delivery AND EXISTS (
+                      SELECT 1
+                      FROM webhook_events prev
+                      WHERE prev.webhook_endpoint_id = ep.id
+                        AND prev.status = 'pending'
+                        AND prev.sequence < ev.sequence
+                        AND prev.account_ids && ev.account_ids
+                  ))
+                ORDER BY ev.next_attempt_at
+                LIMIT $3
+            ) e
+            WHERE ep.is_active = true
+        ),
+        due AS (
+            SELECT e.id
+            FROM webhook_events e
+            WHERE e.id IN (SELECT id FROM candidates)
+              AND e.status = 'pending'
+              AND e.next_attempt_at <= NOW()
+            ORDER BY e.next_attempt_at
+            LIMIT $1
+            FOR UPDATE OF e SKIP LOCKED
+        )
+        UPDATE webhook_events e
+        SET next_attempt_at = NOW() + make_interval(secs => $2)
+        FROM due, webhook_endpoints ep
+        WHERE e.id = due.id
+          AND ep.id = e.webhook_endpoint_id
+        RETURNING
+            e.id AS event_id,
+            e.webhook_endpoint_id,
+            ep.url,
+            ep.secret,
+            CASE WHEN ep.previous_secret_expires_at > NOW() THEN ep.previous_secret END
+                AS previous_secret,
+            e.payload,
+            e.attempt_count,
+            e.attempts_before_redelivery
+        "#,
+    )
+    .bind(limit)
+    .bind(lease.as_secs_f64())
+    .bind(sender.endpoint_max_concurrency() as i64)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(deliveries)
+}
+
+/// Make one delivery attempt for a claimed event and record the outcome.
+///
+/// # Outcomes
+///
+/// - 2xx response: event is marked `delivered`
+/// - Any other response or a network error, attempts left: event stays
+///   `pending` and is retried after `retry_delay`
+/// - Any other response or a network error, no attempts left: event is
+///   marked `dead`
+async fn deliver(
+    pool: &DbPool,
+    settings: &DispatcherSettings,
+    delivery: WebhookDelivery,
+) -> Result<(), AppError> {
+    let (response_status, response_body) = send_webhook(&settings.sender, &delivery).await;
+    let attempt_count = delivery.attempt_count + 1;
+    // Attempts of the current retry schedule (restarted by redelivery)
+    let scheduled_attempts = attempt_count - delivery.attempts_before_redelivery;
+
+    let succeeded = response_status.is_some_and(|status| (200..300).contains(&status));
+    let (status, retry_in) = if succeeded {
+        ("delivered", Duration::ZERO)
+    } else if scheduled_attempts >= settings.max_attempts {
+        tracing::error!(
+            event_id = %delivery.event_id,
+            webhook_endpoint_id = %delivery.webhook_endpoint_id,
+            attempt_count,
+            "Webhook event is dead after exhausting all delivery attempts"
+        );
+        ("dead", Duration::ZERO)
+    } else {
+        let retry_in = retry_delay(settings, scheduled_attempts);
+        tracing::warn!(
+            event_id = %delivery.event_id,
+            webhook_endpoint_id = %delivery.webhook_endpoint_id,
+            attempt_count,
+            response_status,
+            retry_in_secs = retry_in.as_secs(),
+            "Webhook delivery failed, will retry"
+        );
+        ("pending", retry_in)
+    };
+
+    sqlx::query(
+        r#"
+        UPDATE webhook_events
+        SET status = $2,
+            attempt_count = $3,
+            last_attempt_at = NOW(),
+            next_attempt_at = NOW() + make_interval(secs => $4),
+            delivered_at = CASE WHEN $2 = 'delivered' THEN NOW() END,
+            response_status = $5,
+            response_body = $6
+        WHERE id = $1
+        "#,
+    )
+    .bind(delivery.event_id)
+    .bind(status)
+    .bind(attempt_count)
+    .bind(retry_in.as_secs_f64())
+    .bind(response_status)
+    .bind(response_body)
     .execute(pool)
     .await?;
 
+    record_endpoint_outcome(pool, settings, delivery.webhook_endpoint_id, succeeded).await
+}
+
+/// Update the failure streak of an endpoint after a delivery attempt.
+///
+/// A success resets the streak. A failure extends it, and the endpoint is
+/// disabled once the streak has reached both `disable_after_failures`
+/// attempts and `disable_after` in duration, so a brief outage of a busy
+/// endpoint doesn't disable it.
+async fn record_endpoint_outcome(
+    pool: &DbPool,
+    settings: &DispatcherSettings,
+    endpoint_id: Uuid,
+    succeeded: bool,
+) -> Result<(), AppError> {
+    if succeeded {
+        sqlx::query(
+            r#"
+            UPDATE webhook_endpoints
+            SET consecutive_failures = 0, failing_since = NULL
+            WHERE id = $1 AND consecutive_failures > 0
+            "#,
+        )
+        .bind(endpoint_id)
+        .execute(pool)
+        .await?;
+
+        return Ok(());
+    }
+
+    let endpoint = sqlx::query_as::<_, WebhookEndpoint>(
+        r#"
+        UPDATE webhook_endpoints
+        SET consecutive_failures = consecutive_failures + 1,
+            failing_since = COALESCE(failing_since, NOW())
+        WHERE id = $1
+        RETURNING *
+        "#,
+    )
+    .bind(endpoint_id)
+    .fetch_one(pool)
+    .await?;
+
+    let failing_for = endpoint
+        .failing_since
+        .and_then(|since| (Utc::now() - since).to_std().ok())
+        .unwrap_or_default();
+
+    if settings.disable_after_failures > 0
+        && endpoint.is_active
+        && endpoint.consecutive_failures >= settings.disable_after_failures
+        && failing_for >= settings.disable_after
+    {
+        disable_failing_endpoint(pool, &endpoint).await?;
+    }
+
     Ok(())
 }
 
-/// Generate HMAC-SHA256 signature for webhook payload.
+/// Delay before retrying an event that has failed `attempt_count` times.
+///
+/// Exponential (`retry_base * 2^(attempt_count - 1)`, capped at
+/// `retry_max`) with equal jitter: half of the delay is fixed and half is
+/// random, so events that failed together (e.g. during a receiver outage)
+/// are spread out instead of retrying in lockstep.
+fn retry_delay(settings: &DispatcherSettings, attempt_count: i32) -> Duration {
+    let exponent = (attempt_count - 1).clamp(0, 30) as u32;
+    let delay = settings
+        .retry_base
+        .saturating_mul(2u32.pow(exponent))
+        .min(settings.retry_max);
+
+    let half = delay / 2;
+    half + half.mul_f64(rand::random::<f64>())
+}
+
+/// Send a single webhook with HMAC signature.
 ///
-/// # Format
+/// # Headers Sent
 ///
-/// `sha256=<hex_encoded_hmac>`
+/// - `Content-Type: application/json`
+/// - `X-Webhook-Signature: t=<unix_seconds>,v1=<hex>` (see `webhook_signature`);
+///   one `v1` per secret while a rotation's grace period lasts
+/// - `X-Webhook-Event-Id: <uuid>`
 ///
-/// # Verification
+/// The signature is computed at send time, so every attempt carries a
+/// current timestamp and passes the receiver's tolerance check.
 ///
-/// Clients should:
-/// 1. Extract signature from `X-Webhook-Signature` header
-/// 2. Compute HMAC-SHA256(secret, request_body)
-/// 3. Compare using constant-time comparison
-fn generate_signature(secret: &str, payload: &str) -> String {
-    let mut mac = HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC key length is valid");
-    mac.update(payload.as_bytes());
-    let result = mac.finalize();
-    format!("sha256={}", hex::encode(result.into_bytes()))
+/// # SSRF Protection
+///
+/// The URL is re-checked before every attempt and hostnames are resolved
+/// through the guard's resolver, so an endpoint whose DNS now points at a
+/// private address is refused. Redirects are not followed.
+///
+/// # Client
+///
+/// Uses the sender's shared, pooled client and its timeouts
+/// (`WEBHOOK_TIMEOUT_SECS`, default 5 seconds). Callers hold a
+/// `DeliveryPermit` from the sender while this runs.
+///
+/// # Returns
+///
+/// The HTTP status and response body, or no status and the error message
+/// if the request failed.
+async fn send_webhook(
+    sender: &WebhookSender,
+    delivery: &WebhookDelivery,
+) -> (Option<i32>, Option<String>) {
+    // Re-check the URL; the endpoint may predate the current rules
+    let target = url::Url::parse(&delivery.url)
+        .map_err(|e| e.to_string())
+        .and_then(|url| sender.url_guard().check_target(&url).map(|_| ()));
+    if let Err(reason) = target {
+        return (None, Some(format!("Blocked webhook URL: {}", reason)));
+    }
+
+    let payload_json = delivery.payload.to_string();
+
+    // Sign timestamp and body together so captured requests can't be replayed
+    // During a secret rotation, sign with both secrets (current first)
+    let mut secrets = vec![delivery.secret.as_str()];
+    secrets.extend(delivery.previous_secret.as_deref());
+
+    let signature = webhook_signature::signature_header(
+        &secrets,
+        Utc::now().timestamp(),
+        payload_json.as_bytes(),
+    );
+
+    // Send HTTP POST
+    let response = sender
+        .client()
+        .post(&delivery.url)
+        .header("Content-Type", "application/json")
+        .header(webhook_signature::SIGNATURE_HEADER, &signature)
+        .header("X-Webhook-Event-Id", delivery.event_id.to_string())
+        .body(payload_json)
+        .send()
+        .await;
+
+    match response {
+        Ok(resp) => {
+            let status = resp.status().as_u16() as i32;
+            let body = read_response_body(resp).await;
+            (Some(status), body)
+        }
+        Err(e) => {
+            // Include the causes (e.g. a blocked DNS resolution) in the log
+            let mut message = format!("Request failed: {}", e);
+            let mut source = std::error::Error::source(&e);
+            while let Some(cause) = source {
+                message.push_str(&format!(": {}", cause));
+                source = cause.source();
+            }
+            (None, Some(message))
+        }
+    }
+}
+
+/// Read at most `RESPONSE_BODY_LIMIT` bytes of a receiver's response body.
+///
+/// The rest of the body is never downloaded, so a receiver streaming a huge
+/// or endless response can't tie up a delivery slot or fill the database.
+async fn read_response_body(mut resp: reqwest::Response) -> Option<String> {
+    let mut body = Vec::new();
+    while body.len() < RESPONSE_BODY_LIMIT {
+        match resp.chunk().await {
+            Ok(Some(chunk)) => {
+                let take = chunk.len().min(RESPONSE_BODY_LIMIT - body.len());
+                body.extend_from_slice(&chunk[..take]);
+            }
+            Ok(None) => break,
+            Err(_) if body.is_empty() => return None,
+            Err(_) => break,
+        }
+    }
+    Some(String::from_utf8_lossy(&body).into_owned())
 }
 
 /// Generate cryptographically secure random secret.
@@ -277,42 +1270,184 @@ fn generate_secret() -> String {
     hex::encode(bytes)
 }
 
-/// Validate webhook URL format.
+/// Validate the event types an endpoint subscribes to.
 ///
 /// # Rules
 ///
-/// - Must be valid URL
-/// - Must be HTTPS (HTTP localhost allowed for development)
-/// - Maximum 2048 characters
-fn validate_webhook_url(url: &str) -> Result<(), AppError> {
-    if url.len() > 2048 {
-        return Err(AppError::InvalidWebhookUrl(
-            "URL exceeds 2048 characters".to_string(),
+/// - At least one entry
+/// - Each entry is a known event type, `*` or `category.*` for a known
+///   category (e.g. `transaction.*`)
+///
+/// # Returns
+///
+/// The entries with duplicates removed, in their original order.
+fn validate_enabled_events(enabled_events: Vec<String>) -> Result<Vec<String>, AppError> {
+    if enabled_events.is_empty() {
+        return Err(AppError::InvalidRequest(
+            "enabled_events must not be empty".to_string(),
         ));
     }
 
-    // Parse URL
-    let parsed = url::Url::parse(url)
-        .map_err(|_| AppError::InvalidWebhookUrl("Invalid URL format".to_string()))?;
-
-    // Check scheme
-    match parsed.scheme() {
-        "https" => Ok(()),
-        "http" => {
-            // Allow HTTP for localhost/127.0.0.1 (testing)
-            if parsed.host_str() == Some("localhost")
-                || parsed.host_str() == Some("127.0.0.1")
-                || parsed.host_str() == Some("0.0.0.0")
-            {
-                Ok(())
-            } else {
-                Err(AppError::InvalidWebhookUrl(
-                    "HTTP is only allowed for localhost. Use HTTPS for production.".to_string(),
-                ))
-            }
+    let mut validated: Vec<String> = Vec::with_capacity(enabled_events.len());
+    for pattern in enabled_events {
+        let is_known = pattern == ALL_EVENTS
+            || match pattern.strip_suffix(".*") {
+                Some(category) => EVENT_TYPES
+                    .iter()
+                    .any(|t| t.split_once('.').is_some_and(|(c, _)| c == category)),
+                None => EVENT_TYPES.contains(&pattern.as_str()),
+            };
+
+        if !is_known {
+            return Err(AppError::InvalidRequest(format!(
+                "Unknown event type '{}'",
+                pattern
+            )));
+        }
+
+        if !validated.contains(&pattern) {
+            validated.push(pattern);
+        }
+    }
+
+    Ok(validated)
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{Router, body::Bytes, http::HeaderMap, routing::post};
+    use rust_transaction_web_server::webhook_signature::{DEFAULT_TOLERANCE, SignatureError};
+    use serde_json::json;
+    use tokio::sync::mpsc;
+
+    use super::*;
+    use crate::services::webhook_sender::WebhookSenderSettings;
+
+    /// Start a receiver on localhost that answers 200 and passes on the
+    /// headers and body of every request it gets.
+    async fn spawn_receiver() -> (String, mpsc::UnboundedReceiver<(HeaderMap, Bytes)>) {
+        let (requests_tx, requests) = mpsc::unbounded_channel();
+        let app = Router::new().route(
+            "/hooks",
+            post(move |headers: HeaderMap, body: Bytes| async move {
+                requests_tx.send((headers, body)).unwrap();
+            }),
+        );
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hooks", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        (url, requests)
+    }
+
+    fn test_sender() -> WebhookSender {
+        WebhookSender::new(
+            WebhookUrlGuard::new(["127.0.0.1".to_string()]),
+            WebhookSenderSettings {
+                request_timeout: Duration::from_secs(5),
+                connect_timeout: Duration::from_secs(2),
+                max_concurrency: 4,
+                endpoint_max_concurrency: 1,
+                endpoint_rate_per_sec: 10.0,
+                endpoint_burst: 20,
+            },
+        )
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn delivered_webhooks_verify_with_the_endpoint_secret() {
+        let (url, mut requests) = spawn_receiver().await;
+        let delivery = WebhookDelivery {
+            event_id: Uuid::new_v4(),
+            webhook_endpoint_id: Uuid::new_v4(),
+            url,
+            secret: "whsec_current".to_string(),
+            previous_secret: None,
+            payload: json!({"event_type": "transaction.credit", "sequence": 1}),
+            attempt_count: 0,
+            attempts_before_redelivery: 0,
+        };
+
+        let (status, _) = send_webhook(&test_sender(), &delivery).await;
+        assert_eq!(status, Some(200));
+
+        let (headers, body) = requests.recv().await.unwrap();
+        let signature = headers[webhook_signature::SIGNATURE_HEADER]
+            .to_str()
+            .unwrap();
+
+        // The receiver verifies the raw body it got, at its current time
+        assert_eq!(
+            webhook_signature::verify("whsec_current", signature, &body, DEFAULT_TOLERANCE),
+            Ok(())
+        );
+        assert_eq!(
+            webhook_signature::verify("whsec_other", signature, &body, DEFAULT_TOLERANCE),
+            Err(SignatureError::NoMatchingSignature)
+        );
+        assert_eq!(
+            headers["x-webhook-event-id"].to_str().unwrap(),
+            delivery.event_id.to_string()
+        );
+    }
+
+    #[cfg(feature = "db-tests")]
+    mod database {
+        use super::*;
+        use crate::services::test_support::{create_business, create_webhook_endpoint};
+
+        /// A business with an endpoint delivering to a local receiver.
+        async fn endpoint_with_receiver(
+            pool: &DbPool,
+        ) -> (Uuid, Uuid, mpsc::UnboundedReceiver<(HeaderMap, Bytes)>) {
+            let business = create_business(pool).await;
+            let endpoint = create_webhook_endpoint(pool, business).await;
+            let (url, requests) = spawn_receiver().await;
+
+            sqlx::query("UPDATE webhook_endpoints SET url = $2 WHERE id = $1")
+                .bind(endpoint)
+                .bind(url)
+                .execute(pool)
+                .await
+                .unwrap();
+
+            (business, endpoint, requests)
+        }
+
+        #[sqlx::test]
+        async fn test_pings_take_no_sequence_number(pool: DbPool) {
+            let (business, endpoint, mut requests) = endpoint_with_receiver(&pool).await;
+            let next_sequence = || async {
+                sqlx::query_scalar::<_, i64>("SELECT nextval('webhook_event_sequence')")
+                    .fetch_one(&pool)
+                    .await
+                    .unwrap()
+            };
+
+            let before = next_sequence().await;
+            let response = send_test_webhook(&pool, &test_sender(), business, endpoint)
+                .await
+                .unwrap();
+            assert!(response.success);
+            assert_eq!(next_sequence().await, before + 1);
+
+            let (_, body) = requests.recv().await.unwrap();
+            let payload: serde_json::Value = serde_json::from_slice(&body).unwrap();
+            assert_eq!(payload["sequence"], json!(TEST_EVENT_SEQUENCE));
+        }
+
+        #[sqlx::test]
+        async fn test_ping_gives_up_while_endpoint_is_busy(pool: DbPool) {
+            let (business, endpoint, _requests) = endpoint_with_receiver(&pool).await;
+            let sender = test_sender();
+
+            // The endpoint's only slot is taken by a delivery in flight
+            let _in_flight = sender.acquire(endpoint).await;
+
+            let result = send_test_webhook(&pool, &sender, business, endpoint).await;
+            assert!(matches!(result, Err(AppError::WebhookEndpointBusy)));
         }
-        _ => Err(AppError::InvalidWebhookUrl(
-            "URL must use HTTP or HTTPS".to_string(),
-        )),
     }
 }
@@ -0,0 +1,391 @@
+//! SSRF protection for webhook URLs.
+//!
+//! Webhook URLs are supplied by API clients, so without checks the server
+//! could be made to POST to internal services: cloud metadata endpoints,
+//! hosts on private networks or admin ports on localhost. This module:
+//! - Validates URLs at registration, resolving hostnames and rejecting any
+//!   that resolve to a non-public address
+//! - Provides a DNS resolver for the webhook HTTP client that repeats the
+//!   check at send time, so a hostname re-pointed after registration
+//!   (DNS rebinding) is still blocked
+//! - Builds the webhook HTTP client with redirects and proxies disabled,
+//!   so the checked address is the one actually connected to
+//!
+//! # Allowlist
+//!
+//! Hosts listed in `WEBHOOK_ALLOWED_HOSTS` skip the address check and may
+//! use plain HTTP. Intended for local development (e.g. `localhost`).
+
+use std::{
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    sync::Arc,
+};
+
+use reqwest::{
+    dns::{Addrs, Name, Resolve, Resolving},
+    redirect,
+};
+use url::{Host, Url};
+
+use crate::error::AppError;
+
+/// Maximum length of a webhook URL.
+const MAX_URL_LENGTH: usize = 2048;
+
+/// Blocked IPv4 ranges as (network, prefix length).
+const BLOCKED_IPV4_RANGES: &[(Ipv4Addr, u8)] = &[
+    (Ipv4Addr::new(0, 0, 0, 0), 8),       // "This network" / unspecified
+    (Ipv4Addr::new(10, 0, 0, 0), 8),      // Private
+    (Ipv4Addr::new(100, 64, 0, 0), 10),   // Carrier-grade NAT
+    (Ipv4Addr::new(127, 0, 0, 0), 8),     // Loopback
+    (Ipv4Addr::new(169, 254, 0, 0), 16),  // Link-local (incl. cloud metadata)
+    (Ipv4Addr::new(172, 16, 0, 0), 12),   // Private
+    (Ipv4Addr::new(192, 0, 0, 0), 24),    // IETF protocol assignments
+    (Ipv4Addr::new(192, 0, 2, 0), 24),    // Documentation
+    (Ipv4Addr::new(192, 88, 99, 0), 24),  // 6to4 relay anycast
+    (Ipv4Addr::new(192, 168, 0, 0), 16),  // Private
+    (Ipv4Addr::new(198, 18, 0, 0), 15),   // Benchmarking
+    (Ipv4Addr::new(198, 51, 100, 0), 24), // Documentation
+    (Ipv4Addr::new(203, 0, 113, 0), 24),  // Documentation
+    (Ipv4Addr::new(224, 0, 0, 0), 4),     // Multicast
+    (Ipv4Addr::new(240, 0, 0, 0), 4),     // Reserved and broadcast
+];
+
+/// Blocked IPv6 ranges as (network, prefix length).
+///
+/// IPv4-mapped and NAT64 addresses are checked against the IPv4 ranges
+/// separately. The other transition mechanisms that embed an IPv4 address
+/// (IPv4-compatible, 6to4, Teredo) are blocked outright: they are
+/// deprecated or relay-based and can tunnel to a private IPv4 target.
+const BLOCKED_IPV6_RANGES: &[(Ipv6Addr, u8)] = &[
+    (Ipv6Addr::UNSPECIFIED, 128),                         // Unspecified
+    (Ipv6Addr::LOCALHOST, 128),                           // Loopback
+    (Ipv6Addr::new(0, 0, 0, 0, 0, 0, 0, 0), 96),          // IPv4-compatible (deprecated)
+    (Ipv6Addr::new(0x100, 0, 0, 0, 0, 0, 0, 0), 64),      // Discard-only
+    (Ipv6Addr::new(0x2001, 0, 0, 0, 0, 0, 0, 0), 32),     // Teredo
+    (Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 0), 32), // Documentation
+    (Ipv6Addr::new(0x2002, 0, 0, 0, 0, 0, 0, 0), 16),     // 6to4
+    (Ipv6Addr::new(0xfc00, 0, 0, 0, 0, 0, 0, 0), 7),      // Unique local (incl. cloud metadata)
+    (Ipv6Addr::new(0xfe80, 0, 0, 0, 0, 0, 0, 0), 10),     // Link-local
+    (Ipv6Addr::new(0xfec0, 0, 0, 0, 0, 0, 0, 0), 10),     // Site-local (deprecated)
+    (Ipv6Addr::new(0xff00, 0, 0, 0, 0, 0, 0, 0), 8),      // Multicast
+];
+
+/// Whether an address is private, loopback, link-local or otherwise not a
+/// public unicast address, and so must never be a webhook target.
+pub fn is_blocked_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_blocked_ipv4(ip),
+        IpAddr::V6(ip) => {
+            // IPv4-mapped (::ffff:a.b.c.d) and NAT64 (64:ff9b::a.b.c.d)
+            // addresses reach the embedded IPv4 address
+            let segments = ip.segments();
+            let embedded_ipv4 = ip.to_ipv4_mapped().or_else(|| {
+                (segments[..6] == [0x64, 0xff9b, 0, 0, 0, 0]).then(|| {
+                    let [.., a, b, c, d] = ip.octets();
+                    Ipv4Addr::new(a, b, c, d)
+                })
+            });
+
+            if let Some(ipv4) = embedded_ipv4 {
+                return is_blocked_ipv4(ipv4);
+            }
+
+            let bits = u128::from(ip);
+            BLOCKED_IPV6_RANGES
+                .iter()
+                .any(|(network, prefix)| prefix_matches(bits, u128::from(*network), *prefix, 128))
+        }
+    }
+}
+
+fn is_blocked_ipv4(ip: Ipv4Addr) -> bool {
+    let bits = u128::from(u32::from(ip));
+    BLOCKED_IPV4_RANGES
+        .iter()
+        .any(|(network, prefix)| prefix_matches(bits, u128::from(u32::from(*network)), *prefix, 32))
+}
+
+/// Whether the first `prefix` bits of two `width`-bit addresses are equal.
+fn prefix_matches(addr: u128, network: u128, prefix: u8, width: u32) -> bool {
+    let shift = width - u32::from(prefix);
+    addr >> shift == network >> shift
+}
+
+/// Validates webhook URLs and builds the HTTP client used to deliver them.
+///
+/// Cheap to clone; shared through `AppState`.
+#[derive(Debug, Clone, Default)]
+pub struct WebhookUrlGuard {
+    /// Lowercased hosts that skip the address check
+    allowed_hosts: Arc<[String]>,
+}
+
+impl WebhookUrlGuard {
+    /// Create a guard with the given allowlisted hosts (empty entries ignored).
+    pub fn new(allowed_hosts: impl IntoIterator<Item = String>) -> Self {
+        Self {
+            allowed_hosts: allowed_hosts
+                .into_iter()
+                .map(|host| host.trim().to_ascii_lowercase())
+                .filter(|host| !host.is_empty())
+                .collect(),
+        }
+    }
+
+    /// Whether a host is in the allowlist.
+    fn is_allowed_host(&self, host: &str) -> bool {
+        let host = host.trim_start_matches('[').trim_end_matches(']');
+        self.allowed_hosts
+            .iter()
+            .any(|allowed| allowed.eq_ignore_ascii_case(host))
+    }
+
+    /// Validate a webhook URL at registration time.
+    ///
+    /// # Rules
+    ///
+    /// - Must be a valid URL of at most 2048 characters
+    /// - Must be HTTPS (HTTP only for allowlisted hosts)
+    /// - IP literals must be public addresses
+    /// - Hostnames must resolve, and every resolved address must be public
+    ///
+    /// Allowlisted hosts skip the address checks.
+    pub async fn validate(&self, url: &str) -> Result<(), AppError> {
+        if url.len() > MAX_URL_LENGTH {
+            return Err(AppError::InvalidWebhookUrl(format!(
+                "URL exceeds {} characters",
+                MAX_URL_LENGTH
+            )));
+        }
+
+        // Parse URL
+        let parsed = Url::parse(url)
+            .map_err(|_| AppError::InvalidWebhookUrl("Invalid URL format".to_string()))?;
+
+        // IP literals and allowlisted hosts are fully checked here
+        let Some((domain, port)) = self
+            .check_target(&parsed)
+            .map_err(AppError::InvalidWebhookUrl)?
+        else {
+            return Ok(());
+        };
+
+        let addrs: Vec<SocketAddr> = tokio::net::lookup_host((domain, port))
+            .await
+            .map_err(|_| {
+                AppError::InvalidWebhookUrl(format!("Could not resolve host '{}'", domain))
+            })?
+            .collect();
+
+        if addrs.is_empty() {
+            return Err(AppError::InvalidWebhookUrl(format!(
+                "Could not resolve host '{}'",
+                domain
+            )));
+        }
+
+        if addrs.iter().any(|addr| is_blocked_ip(addr.ip())) {
+            return Err(AppError::InvalidWebhookUrl(
+                "URL resolves to a private or reserved address".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Check the parts of a URL that don't need DNS.
+    ///
+    /// Called at registration and again before every delivery. Hostnames
+    /// are checked by `validate` at registration and by the client's
+    /// resolver at send time.
+    ///
+    /// # Returns
+    ///
+    /// The hostname and port whose resolved addresses still need checking,
+    /// or `None` for IP literals and allowlisted hosts.
+    pub fn check_target<'a>(&self, url: &'a Url) -> Result<Option<(&'a str, u16)>, String> {
+        let scheme = url.scheme();
+        if scheme != "https" && scheme != "http" {
+            return Err("URL must use HTTP or HTTPS".to_string());
+        }
+
+        let host = url
+            .host()
+            .ok_or_else(|| "URL must have a host".to_string())?;
+        let port = url.port_or_known_default().unwrap_or(443);
+
+        if self.is_allowed_host(url.host_str().unwrap_or_default()) {
+            return Ok(None);
+        }
+
+        if scheme == "http" {
+            return Err(
+                "HTTP is only allowed for allowlisted development hosts. Use HTTPS.".to_string(),
+            );
+        }
+
+        let ip = match host {
+            Host::Ipv4(ip) => IpAddr::V4(ip),
+            Host::Ipv6(ip) => IpAddr::V6(ip),
+            Host::Domain(domain) => return Ok(Some((domain, port))),
+        };
+
+        if is_blocked_ip(ip) {
+            return Err("URL points to a private or reserved address".to_string());
+        }
+
+        Ok(None)
+    }
+
+    /// Start building the HTTP client used to deliver webhooks.
+    ///
+    /// - Hostnames are resolved by `GuardedResolver`, which rejects blocked
+    ///   addresses at connect time
+    /// - Redirects are not followed (a 3xx counts as a failed attempt)
+    /// - System proxies are ignored, so resolution happens locally
+    ///
+    /// Timeouts and pooling are left to the caller (see `WebhookSender`).
+    pub fn client_builder(&self) -> reqwest::ClientBuilder {
+        reqwest::Client::builder()
+            .redirect(redirect::Policy::none())
+            .no_proxy()
+            .dns_resolver(Arc::new(GuardedResolver {
+                guard: self.clone(),
+            }))
+    }
+}
+
+/// DNS resolver that refuses hostnames resolving to blocked addresses.
+///
+/// Resolution happens right before connecting, so the addresses checked are
+/// the addresses used.
+struct GuardedResolver {
+    guard: WebhookUrlGuard,
+}
+
+impl Resolve for GuardedResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let guard = self.guard.clone();
+
+        Box::pin(async move {
+            let host = name.as_str();
+
+            // Port is filled in by the connector
+            let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host, 0)).await?.collect();
+
+            if !guard.is_allowed_host(host)
+                && let Some(blocked) = addrs.iter().find(|addr| is_blocked_ip(addr.ip()))
+            {
+                return Err(format!(
+                    "host '{}' resolves to blocked address {}",
+                    host,
+                    blocked.ip()
+                )
+                .into());
+            }
+
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn blocked(ip: &str) -> bool {
+        is_blocked_ip(ip.parse().unwrap())
+    }
+
+    /// `check_target` with the remaining hostname owned.
+    fn check(guard: &WebhookUrlGuard, url: &str) -> Result<Option<(String, u16)>, String> {
+        let url = Url::parse(url).unwrap();
+        guard
+            .check_target(&url)
+            .map(|target| target.map(|(host, port)| (host.to_string(), port)))
+    }
+
+    #[test]
+    fn allows_public_addresses() {
+        assert!(!blocked("8.8.8.8"));
+        assert!(!blocked("172.32.0.1"));
+        assert!(!blocked("2606:4700:4700::1111"));
+        assert!(!blocked("::ffff:8.8.8.8"));
+        assert!(!blocked("64:ff9b::8.8.8.8"));
+    }
+
+    #[test]
+    fn blocks_private_and_reserved_ipv4() {
+        assert!(blocked("10.1.2.3"));
+        assert!(blocked("127.0.0.1"));
+        assert!(blocked("169.254.169.254"));
+        assert!(blocked("172.16.0.1"));
+        assert!(blocked("100.64.0.1"));
+        assert!(blocked("192.168.1.1"));
+        assert!(blocked("224.0.0.1"));
+        assert!(blocked("255.255.255.255"));
+    }
+
+    #[test]
+    fn blocks_non_global_ipv6() {
+        assert!(blocked("::"));
+        assert!(blocked("::1"));
+        assert!(blocked("fc00::1"));
+        assert!(blocked("fd00:ec2::254"));
+        assert!(blocked("fe80::1"));
+        assert!(blocked("ff02::1"));
+        assert!(blocked("2001:db8::1"));
+    }
+
+    #[test]
+    fn blocks_ipv6_embedding_private_ipv4() {
+        // IPv4-mapped and NAT64: checked against the IPv4 ranges
+        assert!(blocked("::ffff:127.0.0.1"));
+        assert!(blocked("::ffff:169.254.169.254"));
+        assert!(blocked("64:ff9b::a9fe:a9fe"));
+        assert!(blocked("64:ff9b::192.168.0.1"));
+
+        // IPv4-compatible, 6to4 and Teredo: blocked whatever they embed
+        assert!(blocked("::127.0.0.1"));
+        assert!(blocked("::8.8.8.8"));
+        assert!(blocked("2002:a9fe:a9fe::1"));
+        assert!(blocked("2002:808:808::1"));
+        assert!(blocked("2001:0:4136:e378:8000:63bf:3fff:fdd2"));
+    }
+
+    #[test]
+    fn requires_https_except_for_allowlisted_hosts() {
+        let guard = WebhookUrlGuard::new(["localhost".to_string()]);
+        assert_eq!(check(&guard, "http://localhost:3000/hook"), Ok(None));
+        assert!(check(&guard, "http://example.com/hook").is_err());
+        assert!(check(&guard, "ftp://example.com/hook").is_err());
+        assert_eq!(
+            check(&guard, "https://example.com:8443/hook"),
+            Ok(Some(("example.com".to_string(), 8443)))
+        );
+    }
+
+    #[test]
+    fn rejects_blocked_ip_literals() {
+        let guard = WebhookUrlGuard::default();
+        assert_eq!(check(&guard, "https://8.8.8.8/hook"), Ok(None));
+        assert_eq!(
+            check(&guard, "https://[2606:4700:4700::1111]/hook"),
+            Ok(None)
+        );
+        assert!(check(&guard, "https://127.0.0.1/hook").is_err());
+        assert!(check(&guard, "https://[::ffff:127.0.0.1]/hook").is_err());
+        assert!(check(&guard, "https://[64:ff9b::a9fe:a9fe]/hook").is_err());
+        assert!(check(&guard, "https://[2002:a9fe:a9fe::1]/hook").is_err());
+    }
+
+    #[tokio::test]
+    async fn rejects_hostnames_resolving_to_blocked_addresses() {
+        let result = WebhookUrlGuard::default()
+            .validate("https://localhost/hook")
+            .await;
+
+        assert!(matches!(result, Err(AppError::InvalidWebhookUrl(_))));
+    }
+}
@@ -0,0 +1,41 @@
+//! Shared application state.
+//!
+//! Handlers extract the part they need (`State<DbPool>`,
+//! `State<WebhookUrlGuard>`, `State<WebhookSender>`) through the `FromRef` implementations below,
+//! so most handlers don't need to know about `AppState` at all.
+
+use axum::extract::FromRef;
+
+use crate::db::DbPool;
+use crate::services::{webhook_sender::WebhookSender, webhook_url_guard::WebhookUrlGuard};
+
+/// State shared with all handlers.
+#[derive(Clone)]
+pub struct AppState {
+    /// Database connection pool
+    pub pool: DbPool,
+
+    /// SSRF checks for webhook URLs
+    pub webhook_url_guard: WebhookUrlGuard,
+
+    /// Shared webhook HTTP client and delivery limits
+    pub webhook_sender: WebhookSender,
+}
+
+impl FromRef<AppState> for DbPool {
+    fn from_ref(state: &AppState) -> Self {
+        state.pool.clone()
+    }
+}
+
+impl FromRef<AppState> for WebhookUrlGuard {
+    fn from_ref(state: &AppState) -> Self {
+        state.webhook_url_guard.clone()
+    }
+}
+
+impl FromRef<AppState> for WebhookSender {
+    fn from_ref(state: &AppState) -> Self {
+        state.webhook_sender.clone()
+    }
+}
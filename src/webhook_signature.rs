@@ -0,0 +1,274 @@
+//! Timestamped HMAC-SHA256 webhook signatures.
+//!
+//! Every webhook carries an `X-Webhook-Signature` header of the form:
+//!
+//! ```text
+//! X-Webhook-Signature: t=1735000000,v1=5257a869e7ecebeda32affa62cdca3fa51cad7e77a0e56ff536d0ce8e108d8bd
+//! ```
+//!
+//! - `t`: Unix timestamp (seconds) when the request was signed
+//! - `v1`: Hex-encoded `HMAC-SHA256(secret, "{t}.{raw_body}")`
+//!
+//! Because the timestamp is part of the signed message, a captured request
+//! cannot be replayed once it falls outside the tolerance window
+//! (`DEFAULT_TOLERANCE`, 5 minutes). A header may carry more than one `v1`
+//! entry (e.g. one per secret while a secret is being rotated); a request is
+//! valid if any of them matches.
+//!
+//! This module is part of the library target so that receivers written in
+//! Rust can reuse it.
+//!
+//! # Example
+//!
+//! ```
+//! use rust_transaction_web_server::webhook_signature::{self, DEFAULT_TOLERANCE};
+//!
+//! let secret = "whsec_test";
+//! let body = br#"{"event_type":"transaction.completed"}"#;
+//!
+//! // Sender side
+//! let header = webhook_signature::signature_header(&[secret], 1_735_000_000, body);
+//!
+//! // Receiver side (`verify` uses the current time instead of a fixed one)
+//! assert!(
+//!     webhook_signature::verify_at(secret, &header, body, DEFAULT_TOLERANCE, 1_735_000_060)
+//!         .is_ok()
+//! );
+//! ```
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Name of the HTTP header carrying the signature.
+pub const SIGNATURE_HEADER: &str = "X-Webhook-Signature";
+
+/// Maximum accepted age (and clock skew) of a signature timestamp.
+pub const DEFAULT_TOLERANCE: Duration = Duration::from_secs(300);
+
+/// Reasons a signature header is rejected.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum SignatureError {
+    /// Header is not a comma-separated list of `key=value` pairs with exactly
+    /// one `t` and at least one `v1`.
+    #[error("Malformed signature header")]
+    MalformedHeader,
+
+    /// Timestamp is further from the current time than the tolerance.
+    #[error("Signature timestamp is outside the tolerance window")]
+    TimestampOutsideTolerance,
+
+    /// No `v1` signature matches the payload.
+    #[error("No signature matches the payload")]
+    NoMatchingSignature,
+}
+
+/// Compute the hex-encoded `v1` signature of a payload.
+pub fn sign(secret: &str, timestamp: i64, payload: &[u8]) -> String {
+    hex::encode(mac_for(secret, timestamp, payload).finalize().into_bytes())
+}
+
+/// Build the full `X-Webhook-Signature` header value (`t=...,v1=...`).
+///
+/// One `v1` entry is emitted per secret, in the given order.
+pub fn signature_header(secrets: &[&str], timestamp: i64, payload: &[u8]) -> String {
+    let mut header = format!("t={}", timestamp);
+    for secret in secrets {
+        header.push_str(",v1=");
+        header.push_str(&sign(secret, timestamp, payload));
+    }
+    header
+}
+
+/// Verify a signature header against the current time.
+///
+/// `payload` must be the raw request body, exactly as received.
+pub fn verify(
+    secret: &str,
+    header: &str,
+    payload: &[u8],
+    tolerance: Duration,
+) -> Result<(), SignatureError> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default();
+
+    verify_at(secret, header, payload, tolerance, now)
+}
+
+/// Verify a signature header against an explicit current time (Unix seconds).
+///
+/// # Checks
+///
+/// 1. Header parses into one `t` and one or more `v1` entries (unknown keys
+///    are ignored so new schemes can be added)
+/// 2. `t` is within `tolerance` of `now`, in either direction
+/// 3. At least one `v1` matches, compared in constant time
+pub fn verify_at(
+    secret: &str,
+    header: &str,
+    payload: &[u8],
+    tolerance: Duration,
+    now: i64,
+) -> Result<(), SignatureError> {
+    let mut timestamp = None;
+    let mut signatures = Vec::new();
+
+    for part in header.split(',') {
+        let (key, value) = part
+            .trim()
+            .split_once('=')
+            .ok_or(SignatureError::MalformedHeader)?;
+
+        match key {
+            "t" if timestamp.is_none() => {
+                timestamp = Some(
+                    value
+                        .parse::<i64>()
+                        .map_err(|_| SignatureError::MalformedHeader)?,
+                );
+            }
+            "t" => return Err(SignatureError::MalformedHeader),
+            "v1" => signatures.push(value),
+            _ => {}
+        }
+    }
+
+    let timestamp = timestamp.ok_or(SignatureError::MalformedHeader)?;
+    if signatures.is_empty() {
+        return Err(SignatureError::MalformedHeader);
+    }
+
+    if now.abs_diff(timestamp) > tolerance.as_secs() {
+        return Err(SignatureError::TimestampOutsideTolerance);
+    }
+
+    let matches = signatures.iter().any(|signature| {
+        hex::decode(signature).is_ok_and(|expected| {
+            mac_for(secret, timestamp, payload)
+                .verify_slice(&expected)
+                .is_ok()
+        })
+    });
+
+    if matches {
+        Ok(())
+    } else {
+        Err(SignatureError::NoMatchingSignature)
+    }
+}
+
+/// HMAC over the signed message `"{timestamp}.{payload}"`.
+fn mac_for(secret: &str, timestamp: i64, payload: &[u8]) -> HmacSha256 {
+    let mut mac = HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC key length is valid");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(payload);
+    mac
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECRET: &str = "whsec_test";
+    const BODY: &[u8] = br#"{"event_type":"transaction.completed"}"#;
+    const SIGNED_AT: i64 = 1_735_000_000;
+    const TOLERANCE_SECS: i64 = DEFAULT_TOLERANCE.as_secs() as i64;
+
+    fn verify_now(header: &str, now: i64) -> Result<(), SignatureError> {
+        verify_at(SECRET, header, BODY, DEFAULT_TOLERANCE, now)
+    }
+
+    #[test]
+    fn accepts_signature_within_tolerance() {
+        let header = signature_header(&[SECRET], SIGNED_AT, BODY);
+
+        assert_eq!(verify_now(&header, SIGNED_AT), Ok(()));
+        assert_eq!(verify_now(&header, SIGNED_AT + TOLERANCE_SECS), Ok(()));
+        // The receiver's clock may be behind the sender's
+        assert_eq!(verify_now(&header, SIGNED_AT - TOLERANCE_SECS), Ok(()));
+    }
+
+    #[test]
+    fn rejects_replayed_and_future_signatures() {
+        let header = signature_header(&[SECRET], SIGNED_AT, BODY);
+
+        assert_eq!(
+            verify_now(&header, SIGNED_AT + TOLERANCE_SECS + 1),
+            Err(SignatureError::TimestampOutsideTolerance)
+        );
+        assert_eq!(
+            verify_now(&header, SIGNED_AT - TOLERANCE_SECS - 1),
+            Err(SignatureError::TimestampOutsideTolerance)
+        );
+    }
+
+    #[test]
+    fn accepts_any_matching_signature() {
+        // Both secrets during a rotation, in either order
+        let rotating = signature_header(&["whsec_new", SECRET], SIGNED_AT, BODY);
+        assert_eq!(verify_now(&rotating, SIGNED_AT), Ok(()));
+
+        // Unknown schemes are ignored
+        let header = format!(
+            "t={},v0=abc,v1={}",
+            SIGNED_AT,
+            sign(SECRET, SIGNED_AT, BODY)
+        );
+        assert_eq!(verify_now(&header, SIGNED_AT), Ok(()));
+    }
+
+    #[test]
+    fn rejects_other_secret_body_or_timestamp() {
+        let other_secret = signature_header(&["whsec_other"], SIGNED_AT, BODY);
+        assert_eq!(
+            verify_now(&other_secret, SIGNED_AT),
+            Err(SignatureError::NoMatchingSignature)
+        );
+
+        let header = signature_header(&[SECRET], SIGNED_AT, BODY);
+        assert_eq!(
+            verify_at(SECRET, &header, b"{}", DEFAULT_TOLERANCE, SIGNED_AT),
+            Err(SignatureError::NoMatchingSignature)
+        );
+
+        // The timestamp is signed, so a replay can't refresh it
+        let refreshed = format!("t={},v1={}", SIGNED_AT + 60, sign(SECRET, SIGNED_AT, BODY));
+        assert_eq!(
+            verify_now(&refreshed, SIGNED_AT + 60),
+            Err(SignatureError::NoMatchingSignature)
+        );
+
+        let not_hex = format!("t={},v1=not-hex", SIGNED_AT);
+        assert_eq!(
+            verify_now(&not_hex, SIGNED_AT),
+            Err(SignatureError::NoMatchingSignature)
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_headers() {
+        let signature = sign(SECRET, SIGNED_AT, BODY);
+
+        for header in [
+            String::new(),
+            format!("v1={}", signature),
+            format!("t={}", SIGNED_AT),
+            format!("t={},t={},v1={}", SIGNED_AT, SIGNED_AT, signature),
+            format!("t=soon,v1={}", signature),
+            format!("t={};v1={}", SIGNED_AT, signature),
+        ] {
+            assert_eq!(
+                verify_now(&header, SIGNED_AT),
+                Err(SignatureError::MalformedHeader),
+                "{}",
+                header
+            );
+        }
+    }
+}